@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+
+use eza::fs::feature::GitOptions;
+use eza::fs::feature::git::GitCache;
+
+const DIRS: usize = 40;
+const FILES_PER_DIR: usize = 100;
+
+/// Creates a repository where every file has been added to the index, so
+/// each one gets an entry in the list of statuses.
+fn make_repo(root: &Path) -> Vec<PathBuf> {
+    let repo = git2::Repository::init(root).unwrap();
+
+    let mut files = Vec::new();
+    for d in 0..DIRS {
+        let dir = root.join(format!("dir{d}"));
+        fs::create_dir(&dir).unwrap();
+        for f in 0..FILES_PER_DIR {
+            let file = dir.join(format!("file{f}.txt"));
+            fs::write(&file, "").unwrap();
+            files.push(file);
+        }
+    }
+
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+
+    files
+}
+
+/// The statuses as they used to be stored, searched from start to end for
+/// every file being listed.
+fn linear_statuses(root: &Path) -> Vec<(PathBuf, git2::Status)> {
+    let repo = git2::Repository::open(root).unwrap();
+    let statuses = repo.statuses(None).unwrap();
+    statuses
+        .iter()
+        .map(|e| (root.join(e.path().unwrap()), e.status()))
+        .collect()
+}
+
+fn linear_lookup(statuses: &[(PathBuf, git2::Status)], path: &Path) -> git2::Status {
+    let path = path.canonicalize().unwrap();
+    statuses
+        .iter()
+        .filter(|p| {
+            if p.1 == git2::Status::IGNORED {
+                path.starts_with(&p.0)
+            } else {
+                p.0 == path
+            }
+        })
+        .fold(git2::Status::empty(), |a, b| a | b.1)
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let dir = tempfile::Builder::new()
+        .prefix("eza-bench-git-")
+        .tempdir()
+        .unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    let files = make_repo(&root);
+
+    let cache = GitCache::new(vec![root.clone()], &GitOptions::default());
+    // The statuses are only looked up the first time they’re needed.
+    black_box(cache.get(&files[0], false));
+
+    let statuses = linear_statuses(&root);
+
+    let mut group = c.benchmark_group("git_status");
+    group.sample_size(10);
+
+    group.bench_function("indexed", |b| {
+        b.iter(|| {
+            for file in &files {
+                black_box(cache.get(black_box(file), false));
+            }
+        });
+    });
+
+    // Listing the current directory gives paths relative to it, which have
+    // to be made absolute to be looked up.
+    std::env::set_current_dir(&root).unwrap();
+    let relative_cache = GitCache::new(vec![PathBuf::from(".")], &GitOptions::default());
+    black_box(relative_cache.get(Path::new("."), true));
+    let relative_files = files
+        .iter()
+        .map(|file| Path::new(".").join(file.strip_prefix(&root).unwrap()))
+        .collect::<Vec<_>>();
+
+    group.bench_function("indexed_relative", |b| {
+        b.iter(|| {
+            for file in &relative_files {
+                black_box(relative_cache.get(black_box(file), false));
+            }
+        });
+    });
+
+    let dirs = (0..DIRS)
+        .map(|d| root.join(format!("dir{d}")))
+        .collect::<Vec<_>>();
+
+    group.bench_function("indexed_dirs", |b| {
+        b.iter(|| {
+            for dir in &dirs {
+                black_box(cache.get(black_box(dir), true));
+            }
+        });
+    });
+
+    group.bench_function("linear", |b| {
+        b.iter(|| {
+            for file in &files {
+                black_box(linear_lookup(&statuses, black_box(file)));
+            }
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
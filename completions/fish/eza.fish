@@ -9,6 +9,10 @@ complete -c eza -s G -l grid -d "Display entries in a grid"
 complete -c eza -s x -l across -d "Sort the grid across, rather than downwards"
 complete -c eza -s R -l recurse -d "Recurse into directories"
 complete -c eza -s T -l tree -d "Recurse into directories as a tree"
+complete -c eza -l json -d "Display each entry as a JSON object, one per line"
+complete -c eza -l columns -d "Which columns to show, in order" -x
+complete -c eza -l csv -d "Export the long view as comma-separated values"
+complete -c eza -l tsv -d "Export the long view as tab-separated values"
 complete -c eza -s X -l dereference -d "Dereference symbolic links when displaying file information"
 complete -c eza -s F -l classify -d "Display type indicator by file names"
 complete -c eza -l color \
@@ -23,6 +27,7 @@ complete -c eza -l color-scale \
     all\t''
     age\t''
     size\t''
+    entries\t''
 "
 complete -c eza -l color-scale-mode \
     -l colour-scale-mode \
@@ -37,6 +42,7 @@ complete -c eza -l icons -d "When to display icons" -x -a "
   never\t'Never display icons'
 "
 complete -c eza -l no-quotes -d "Don't quote file names with spaces"
+complete -c eza -l summary -d "Add up the entries of each listed directory in a footer"
 complete -c eza -l hyperlink -d "When to display entries as hyperlinks" -x -a "
   always\t'Always display entries as hyperlinks'
   auto\t'Display hyperlinks if standard output is a terminal'
@@ -55,24 +61,43 @@ complete -c eza -l smart-group -d "Only show group if it has a different name fr
 complete -c eza -l group-directories-first -d "Sort directories before other files"
 complete -c eza -l group-directories-last -d "Sort directories after other files"
 complete -c eza -l git-ignore -d "Ignore files mentioned in '.gitignore'"
+complete -c eza -l git-changed -d "Only show files with staged or unstaged Git changes"
+complete -c eza -l git-untracked -d "Only show files that Git doesn't track yet"
+complete -c eza -l only-glob -d "Only show files that match these glob patterns" -r
+complete -c eza -l prune-dirs -d "Hide directories without files matching --only-glob"
+complete -c eza -l min-size -d "Hide files smaller than the given size" -x
+complete -c eza -l max-size -d "Hide files larger than the given size" -x
+complete -c eza -l newer-than -d "Hide files older than a duration or date" -x
+complete -c eza -l older-than -d "Hide files newer than a duration or date" -x
+complete -c eza -l filter-time -d "Which timestamp --newer-than and --older-than compare" -x -a "
+    modified\t'Modified timestamp'
+    changed\t'Changed timestamp'
+    accessed\t'Accessed timestamp'
+    created\t'Created timestamp'
+"
 complete -c eza -s a -l all -d "Show hidden and 'dot' files. Use this twice to also show the '.' and '..' directories"
 complete -c eza -s A -l almost-all -d "Equivalent to --all; included for compatibility with `ls -A`"
 complete -c eza -s d -l treat-dirs-as-files -d "List directories like regular files"
 complete -c eza -s L -l level -d "Limit the depth of recursion" -x -a "1 2 3 4 5 6 7 8 9"
 complete -c eza -s w -l width -d "Limits column output of grid, 0 implies auto-width"
 complete -c eza -s r -l reverse -d "Reverse the sort order"
-complete -c eza -s s -l sort -d "Which field to sort by" -x -a "
+complete -c eza -s s -l sort -d "Which fields to sort by" -x -a "
     accessed\t'Sort by file accessed time'
     age\t'Sort by file modified time (newest first)'
+    author\t'Sort by author of the last commit'
+    committed\t'Sort by time of the last commit'
     changed\t'Sort by changed time'
+    count\t'Sort by number of directory entries'
     created\t'Sort by file modified time'
     date\t'Sort by file modified time'
+    entries\t'Sort by number of directory entries'
     ext\t'Sort by file extension'
     Ext\t'Sort by file extension (uppercase first)'
     extension\t'Sort by file extension'
     Extension\t'Sort by file extension (uppercase first)'
     filename\t'Sort by filename'
     Filename\t'Sort by filename (uppercase first)'
+    git\t'Sort files with Git changes first'
     inode\t'Sort by file inode'
     modified\t'Sort by file modified time'
     name\t'Sort by filename'
@@ -83,6 +108,8 @@ complete -c eza -s s -l sort -d "Which field to sort by" -x -a "
     size\t'Sort by file size'
     time\t'Sort by file modified time'
     type\t'Sort by file type'
+    usage\t'Sort by file size'
+    version\t'Sort by name as a version number'
 "
 
 complete -c eza -s I -l ignore-glob -d "Ignore files that match these glob patterns" -r
@@ -94,11 +121,28 @@ complete -c eza -l no-symlinks -d "Do not show symbolic links"
 # Long view options
 complete -c eza -s b -l binary -d "List file sizes with binary prefixes"
 complete -c eza -s B -l bytes -d "List file sizes in bytes, without any prefixes"
+complete -c eza -l size-unit -d "List file sizes in a fixed unit" -x -a "B k M G T P E Ki Mi Gi Ti Pi Ei"
+complete -c eza -l size-precision -d "List file sizes with this many decimal places" -x
+complete -c eza -l size-suffix -d "How to spell size units" -x -a "
+    short\t'Just the prefix, such as k or Mi'
+    si\t'Decimal units, such as kB or MB'
+    iec\t'Binary units, such as KiB or MiB'
+"
+complete -c eza -l size-separators -d "When to group file size digits into thousands" -x -a "
+    always\t'Always group digits'
+    auto\t'Group digits if the locale has a separator'
+    never\t'Never group digits'
+"
 complete -c eza -s g -l group -d "List each file's group"
 complete -c eza -s h -l header -d "Add a header row to each column"
 complete -c eza -s H -l links -d "List each file's number of hard links"
 complete -c eza -s i -l inode -d "List each file's inode number"
 complete -c eza -s S -l blocksize -d "List each file's size of allocated file system blocks"
+complete -c eza -l usage -d "List each file's share of its directory's size, with a bar"
+complete -c eza -l entries -d "List how many entries each directory has" -x -a "
+    direct\t'Count the entries directly inside'
+    recursive\t'Count every file below'
+"
 complete -c eza -s t -l time -d "Which timestamp field to list" -x -a "
     modified\t'Display modified time'
     changed\t'Display changed time'
@@ -119,6 +163,7 @@ complete -c eza -l time-style -d "How to format timestamps" -x -a "
     +FORMAT\t'Use custom time style'
 "
 complete -c eza -l total-size -d "Show recursive directory size (unix only)"
+complete -c eza -l one-file-system -d "Don't count other mounted filesystems in --total-size"
 complete -c eza -l no-permissions -d "Suppress the permissions field"
 complete -c eza -s o -l octal-permissions -d "List each file's permission in octal format"
 complete -c eza -l no-filesize -d "Suppress the filesize field"
@@ -126,9 +171,15 @@ complete -c eza -l no-user -d "Suppress the user field"
 complete -c eza -l no-time -d "Suppress the time field"
 complete -c eza -s M -l mounts -d "Show mount details"
 complete -c eza -l stdin -d "When piping to eza. Read file names from stdin"
+complete -c eza -l no-config -d "Ignore the config file"
+complete -c eza -l profile -d "Use the named profile from the config file" -x
 
 # Optional extras
 complete -c eza -l git -d "List each file's Git status, if tracked"
+complete -c eza -l git-base -d "Compare Git statuses against a revision instead of HEAD" -x
+complete -c eza -l git-deleted -d "List tracked files that have been deleted from the working tree"
+complete -c eza -l git-diffstat -d "List the number of lines added and removed in each file"
+complete -c eza -l git-commit -d "List the date, author and hash of each file's last commit"
 complete -c eza -l no-git -d "Suppress Git status"
 complete -c eza -l git-repos -d "List each git-repos status and branch name"
 complete -c eza -l git-repos-no-status -d "List each git-repos branch name (much faster)"
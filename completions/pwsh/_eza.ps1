@@ -5,9 +5,9 @@ Register-ArgumentCompleter -Native -CommandName 'eza' -ScriptBlock {
     param($wordToComplete, $commandAst, $cursorPosition)
 
     $ArrayWhen           = @('always', 'auto', 'never')
-    $ArraySort           = @('name', 'extension', 'size', 'type', 'created', 'modified', 'accessed', 'changed', 'inode', 'none')
+    $ArraySort           = @('name', 'extension', 'size', 'type', 'created', 'modified', 'accessed', 'changed', 'inode', 'committed', 'author', 'entries', 'none')
     $ArrayColorScaleMode = @('fixed', 'gradient')
-    $ArrayColorScale     = @('all', 'age', 'size')
+    $ArrayColorScale     = @('all', 'age', 'size', 'entries')
     $ArrayAbsolute       = @('on', 'follow', 'off')
     $ArrayTime           = @('modified', 'accessed', 'created')
     $ArrayTimeStyle      = @('default', 'iso', 'long-iso', 'full-iso', 'relative', '+%Y-%m-%d %H:%M', '+%Y.%m.%d %H:$M:$s')
@@ -92,6 +92,10 @@ Register-ArgumentCompleter -Native -CommandName 'eza' -ScriptBlock {
             [CompletionResult]::new('--binary'                   ,'binary'              , [CompletionResultType]::ParameterName, 'list file sizes with binary prefixes')
         #   [CompletionResult]::new('-B'                         ,'bytes'               , [CompletionResultType]::ParameterName, 'list file sizes in bytes, without any prefixes')
             [CompletionResult]::new('--bytes'                    ,'bytes'               , [CompletionResultType]::ParameterName, 'list file sizes in bytes, without any prefixes') 
+            [CompletionResult]::new('--size-unit'                ,'sizeunit'            , [CompletionResultType]::ParameterName, 'list file sizes in a fixed unit (B, k, M, G, T, P, E, or Ki, Mi, ...)')
+            [CompletionResult]::new('--size-precision'           ,'sizeprecision'       , [CompletionResultType]::ParameterName, 'list file sizes with this many decimal places')
+            [CompletionResult]::new('--size-suffix'              ,'sizesuffix'          , [CompletionResultType]::ParameterName, 'how to spell size units (short, si, iec)')
+            [CompletionResult]::new('--size-separators'          ,'sizeseparators'      , [CompletionResultType]::ParameterName, 'when to group file size digits into thousands (always, auto, never)')
         #   [CompletionResult]::new('-g'                         ,'group'               , [CompletionResultType]::ParameterName, 'list each file''s group')
             [CompletionResult]::new('--smart-group'              ,'smart-group'         , [CompletionResultType]::ParameterName, 'only show group if it has a different name from owner') 
             [CompletionResult]::new('--group'                    ,'group'               , [CompletionResultType]::ParameterName, 'list each file''s group')
@@ -109,6 +113,8 @@ Register-ArgumentCompleter -Native -CommandName 'eza' -ScriptBlock {
             [CompletionResult]::new('--flags'                    ,'flags'               , [CompletionResultType]::ParameterName, 'list file flags (Mac, BSD, and Windows only)') 
         #   [CompletionResult]::new('-S'                         ,'blocksize'           , [CompletionResultType]::ParameterName, 'show size of allocated file system blocks')
             [CompletionResult]::new('--blocksize'                ,'blocksize'           , [CompletionResultType]::ParameterName, 'show size of allocated file system blocks') 
+            [CompletionResult]::new('--usage'                    ,'usage'               , [CompletionResultType]::ParameterName, 'show each file''s share of its directory''s size, with a bar')
+            [CompletionResult]::new('--entries'                  ,'entries'             , [CompletionResultType]::ParameterName, 'show how many entries each directory has (direct, recursive)')
         #   [CompletionResult]::new('-t'                         ,'time'                , [CompletionResultType]::ParameterName, 'which timestamp field to list (modified, accessed, created)')
             [CompletionResult]::new('--time'                     ,'time'                , [CompletionResultType]::ParameterName, 'which timestamp field to list (modified, accessed, created)') 
         #   [CompletionResult]::new('-m'                         ,'modified'            , [CompletionResultType]::ParameterName, 'use the modified timestamp field')
@@ -120,6 +126,7 @@ Register-ArgumentCompleter -Native -CommandName 'eza' -ScriptBlock {
             [CompletionResult]::new('--created'                  ,'created'             , [CompletionResultType]::ParameterName, 'use the created timestamp field')
             [CompletionResult]::new('--time-style'               ,'time-style'          , [CompletionResultType]::ParameterName, 'how to format timestamps (default, iso, long-iso,full-iso, relative, or a custom style ''+<FORMAT>'' like ''+%Y-%m-%d %H:%M'')')
         #   [CompletionResult]::new('--total-size'               ,'total-size'          , [CompletionResultType]::ParameterName, 'show the size of a directory as the size of all files and directories inside (unix only)')
+            [CompletionResult]::new('--one-file-system'          ,'one-file-system'     , [CompletionResultType]::ParameterName, 'don''t count other mounted filesystems in --total-size')
         #   [CompletionResult]::new('-o'                         ,'octal-permissions'   , [CompletionResultType]::ParameterName, 'list each file''s permission in octal format')
             [CompletionResult]::new('--no-permissions'           ,'no-permissions'      , [CompletionResultType]::ParameterName, 'suppress the permissions field') 
             [CompletionResult]::new('--octal-permissions'        ,'octal-permissions'   , [CompletionResultType]::ParameterName, 'list each file''s permission in octal format')
@@ -127,7 +134,13 @@ Register-ArgumentCompleter -Native -CommandName 'eza' -ScriptBlock {
             [CompletionResult]::new('--no-user'                  ,'no-user'             , [CompletionResultType]::ParameterName, 'suppress the user field')
             [CompletionResult]::new('--no-time'                  ,'no-time'             , [CompletionResultType]::ParameterName, 'suppress the time field')
             [CompletionResult]::new('--stdin'                    ,'stdin'               , [CompletionResultType]::ParameterName, 'read file names from stdin, one per line or other separator specified in environment')
+            [CompletionResult]::new('--no-config'                ,'no-config'           , [CompletionResultType]::ParameterName, 'ignore the config file')
+            [CompletionResult]::new('--profile'                  ,'profile'             , [CompletionResultType]::ParameterName, 'use the named profile from the config file')
             [CompletionResult]::new('--git'                      ,'git'                 , [CompletionResultType]::ParameterName, 'list each file''s Git status, if tracked or ignored')
+            [CompletionResult]::new('--git-base'                 ,'git-base'            , [CompletionResultType]::ParameterName, 'compare Git statuses against a revision instead of HEAD')
+            [CompletionResult]::new('--git-deleted'              ,'git-deleted'         , [CompletionResultType]::ParameterName, 'list tracked files that have been deleted from the working tree')
+            [CompletionResult]::new('--git-diffstat'             ,'git-diffstat'        , [CompletionResultType]::ParameterName, 'list the number of lines added and removed in each file')
+            [CompletionResult]::new('--git-commit'               ,'git-commit'          , [CompletionResultType]::ParameterName, 'list the date, author and hash of each file''s last commit')
             [CompletionResult]::new('--no-git'                   ,'no-git'              , [CompletionResultType]::ParameterName, 'suppress Git status (always overrides -git, --git-repos, --git-repos-no-status)')
             [CompletionResult]::new('--git-repos'                ,'git-repos'           , [CompletionResultType]::ParameterName, 'list root of git-tree status')
             [CompletionResult]::new('--git-repos-no-status'      ,'git-repos-no-status' , [CompletionResultType]::ParameterName, 'list each git-repos branch name (much faster)')
@@ -151,18 +164,23 @@ Register-ArgumentCompleter -Native -CommandName 'eza' -ScriptBlock {
             [CompletionResult]::new('--recurse'                  ,'recurse'             , [CompletionResultType]::ParameterName, 'recurse into directories')
         #   [CompletionResult]::new('-T'                         ,'tree'                , [CompletionResultType]::ParameterName, 'recurse into directories as a tree')
             [CompletionResult]::new('--tree'                     ,'tree'                , [CompletionResultType]::ParameterName, 'recurse into directories as a tree')
+            [CompletionResult]::new('--json'                     ,'json'                , [CompletionResultType]::ParameterName, 'display each entry as a JSON object, one per line')
+            [CompletionResult]::new('--columns'                  ,'columns'             , [CompletionResultType]::ParameterName, 'which columns to show, in order')
+            [CompletionResult]::new('--csv'                      ,'csv'                 , [CompletionResultType]::ParameterName, 'export the long view as comma-separated values')
+            [CompletionResult]::new('--tsv'                      ,'tsv'                 , [CompletionResultType]::ParameterName, 'export the long view as tab-separated values')
         #   [CompletionResult]::new('-X'                         ,'dereference'         , [CompletionResultType]::ParameterName, 'dereference symbolic links when displaying information')
             [CompletionResult]::new('--dereference'              ,'dereference'         , [CompletionResultType]::ParameterName, 'dereference symbolic links when displaying information')
         #   [CompletionResult]::new('-F'                         ,'classify'            , [CompletionResultType]::ParameterName, 'display type indicator by file names (always, auto, never)')
             [CompletionResult]::new('--classify'                 ,'classify'            , [CompletionResultType]::ParameterName, 'display type indicator by file names (always, auto, never)')
             [CompletionResult]::new('--color'                    ,'color'               , [CompletionResultType]::ParameterName, 'when to use terminal colours (always, auto, never)')
         #   [CompletionResult]::new('--colour'                   ,'color'               , [CompletionResultType]::ParameterName, 'when to use terminal colours (always, auto, never)')
-            [CompletionResult]::new('--color-scale'              ,'colorscale'          , [CompletionResultType]::ParameterName, 'highlight levels of ''field'' distinctly(all, age, size)')
+            [CompletionResult]::new('--color-scale'              ,'colorscale'          , [CompletionResultType]::ParameterName, 'highlight levels of ''field'' distinctly(all, age, size, entries)')
         #   [CompletionResult]::new('--colour-scale'             ,'colorscale'          , [CompletionResultType]::ParameterName, 'highlight levels of ''field'' distinctly(all, age, size)')
             [CompletionResult]::new('--color-scale-mode'         ,'colorscalemode'      , [CompletionResultType]::ParameterName, 'use gradient or fixed colors in --color-scale (fixed, gradient)')
         #   [CompletionResult]::new('--colour-scale-mode'        ,'colorscalemode'      , [CompletionResultType]::ParameterName, 'use gradient or fixed colors in --color-scale (fixed, gradient)')
             [CompletionResult]::new('--icons'                    ,'icons'               , [CompletionResultType]::ParameterName, 'when to display icons (always, auto, never)')
             [CompletionResult]::new('--no-quotes'                ,'noquotes'            , [CompletionResultType]::ParameterName, 'don''t quote file names with spaces')
+            [CompletionResult]::new('--summary'                  ,'summary'             , [CompletionResultType]::ParameterName, 'add up the entries of each listed directory in a footer')
             [CompletionResult]::new('--hyperlink'                ,'hyperlink'           , [CompletionResultType]::ParameterName, 'when to display entries as hyperlinks (always, auto, never)')
             [CompletionResult]::new('--absolute'                 ,'absolute'            , [CompletionResultType]::ParameterName, 'display entries with their absolute path (on, follow, off)')
             [CompletionResult]::new('--follow-symlinks'          ,'followsymlinks'      , [CompletionResultType]::ParameterName, 'drill down into symbolic links that point to directories')
@@ -188,7 +206,16 @@ Register-ArgumentCompleter -Native -CommandName 'eza' -ScriptBlock {
             [CompletionResult]::new('--group-directories-last'   ,'gdl'                 , [CompletionResultType]::ParameterName, 'list directories after other files')
         #   [CompletionResult]::new('-I'                         ,'ignore-glob'         , [CompletionResultType]::ParameterName, 'glob patterns (pipe-separated) of files to ignore GLOBS')
             [CompletionResult]::new('--ignore-glob'              ,'ignore-glob'         , [CompletionResultType]::ParameterName, 'glob patterns (pipe-separated) of files to ignore GLOBS')
-            [CompletionResult]::new('--git-ignore'               ,'git-ignore'          , [CompletionResultType]::ParameterName, 'ignore files mentioned in ''.gitignore''')
+            [CompletionResult]::new('--git-ignore'               ,'git-ignore'          , [CompletionResultType]::ParameterName, 'ignore files mentioned in ''.gitignore'', ''.ignore'', and ''.fdignore''')
+            [CompletionResult]::new('--git-changed'              ,'git-changed'         , [CompletionResultType]::ParameterName, 'only show files with staged or unstaged Git changes')
+            [CompletionResult]::new('--git-untracked'            ,'git-untracked'       , [CompletionResultType]::ParameterName, 'only show files that Git doesn''t track yet')
+            [CompletionResult]::new('--only-glob'                ,'only-glob'           , [CompletionResultType]::ParameterName, 'glob patterns (pipe-separated) of the only files to show GLOBS')
+            [CompletionResult]::new('--prune-dirs'               ,'prune-dirs'          , [CompletionResultType]::ParameterName, 'hide directories without files matching --only-glob')
+            [CompletionResult]::new('--min-size'                 ,'min-size'            , [CompletionResultType]::ParameterName, 'hide files smaller than SIZE')
+            [CompletionResult]::new('--max-size'                 ,'max-size'            , [CompletionResultType]::ParameterName, 'hide files larger than SIZE')
+            [CompletionResult]::new('--newer-than'               ,'newer-than'          , [CompletionResultType]::ParameterName, 'hide files older than a duration or date WHEN')
+            [CompletionResult]::new('--older-than'               ,'older-than'          , [CompletionResultType]::ParameterName, 'hide files newer than a duration or date WHEN')
+            [CompletionResult]::new('--filter-time'              ,'filter-time'         , [CompletionResultType]::ParameterName, 'which timestamp --newer-than and --older-than compare FIELD')
             break
         }
         
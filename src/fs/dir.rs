@@ -11,10 +11,13 @@ use std::fs::DirEntry;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::slice::Iter as SliceIter;
+use std::vec::IntoIter as VecIntoIter;
 
 use log::info;
 
 use crate::fs::File;
+use crate::fs::filter::IgnorePatterns;
+use crate::fs::recursive_size::TotalSize;
 
 /// A **Dir** provides a cached list of the file paths in a directory that’s
 /// being listed.
@@ -83,10 +86,13 @@ impl Dir {
         git: Option<&'ig GitCache>,
         git_ignoring: bool,
         deref_links: bool,
-        total_size: bool,
+        total_size: TotalSize,
     ) -> Files<'dir, 'ig> {
+        let ghosts = git.map(|g| g.deleted_files(&self.path)).unwrap_or_default();
+
         Files {
             inner: self.contents.iter(),
+            ghosts: ghosts.into_iter(),
             dir: self,
             dotfiles: dots.shows_dotfiles(),
             dots: dots.dots(),
@@ -97,6 +103,32 @@ impl Dir {
         }
     }
 
+    /// The number of entries that a listing with the given filter leaves
+    /// out for being dotfiles.
+    #[must_use]
+    pub fn hidden_count(&self, dots: DotFilter) -> usize {
+        if dots.shows_dotfiles() {
+            return 0;
+        }
+
+        self.contents
+            .iter()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with('.'))
+            .count()
+    }
+
+    /// The number of entries that the ignore patterns leave out, among those
+    /// that a listing with the given filter would otherwise show.
+    #[must_use]
+    pub fn ignored_count(&self, dots: DotFilter, ignore_patterns: &IgnorePatterns) -> usize {
+        self.contents
+            .iter()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| dots.shows_dotfiles() || !name.starts_with('.'))
+            .filter(|name| ignore_patterns.is_ignored(name))
+            .count()
+    }
+
     /// Whether this directory contains a file with the given path.
     #[must_use]
     pub fn contains(&self, path: &Path) -> bool {
@@ -116,6 +148,10 @@ pub struct Files<'dir, 'ig> {
     /// The internal iterator over the paths that have been read already.
     inner: SliceIter<'dir, DirEntry>,
 
+    /// The paths of deleted files that Git still tracks, which get listed
+    /// after the files that are actually there.
+    ghosts: VecIntoIter<PathBuf>,
+
     /// The directory that begat those paths.
     dir: &'dir Dir,
 
@@ -134,7 +170,7 @@ pub struct Files<'dir, 'ig> {
     deref_links: bool,
 
     /// Whether to calculate the directory size recursively
-    total_size: bool,
+    total_size: TotalSize,
 }
 
 impl<'dir> Files<'dir, '_> {
@@ -191,6 +227,14 @@ impl<'dir> Files<'dir, '_> {
                 return Some(file);
             }
 
+            if let Some(path) = self.ghosts.next() {
+                if !self.dotfiles && File::filename(&path).starts_with('.') {
+                    continue;
+                }
+
+                return Some(File::new_ghost(path, self.dir));
+            }
+
             return None;
         }
     }
@@ -0,0 +1,254 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! The gitoxide backend, which is written in pure Rust, so builds that use it
+//! don’t need libgit2 or OpenSSL.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use gix::bstr::{BString, ByteSlice};
+use gix::diff::index::ChangeRef;
+use gix::dir::walk::EmissionMode;
+use gix::index::entry::Mode;
+use gix::status::index_worktree::iter::Summary;
+use gix::status::{UntrackedFiles, index_worktree, tree_index};
+use log::error;
+
+use super::{Backend, OpenMode, Status};
+use crate::fs::fields as f;
+
+/// A repository opened with gitoxide, which can be shared between threads.
+pub struct Repository(gix::ThreadSafeRepository);
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+impl Backend for Repository {
+    type Error = Error;
+
+    fn open(path: &Path, mode: OpenMode) -> Result<Self, Error> {
+        let repo = match mode {
+            OpenMode::GitDir | OpenMode::Exact => gix::ThreadSafeRepository::open(path)?,
+            OpenMode::Search => {
+                gix::ThreadSafeRepository::discover_with_environment_overrides(path)?
+            }
+        };
+        Ok(Self(repo))
+    }
+
+    /// gitoxide gives the workdir relative to the path the repository was
+    /// found from, so it’s made absolute here, the way libgit2 returns it.
+    fn workdir(&self) -> Option<PathBuf> {
+        let workdir = self.0.work_dir()?;
+        workdir
+            .canonicalize()
+            .or_else(|_| std::env::current_dir().map(|cwd| cwd.join(workdir)))
+            .ok()
+    }
+
+    fn statuses(&self, pathspecs: Option<&[PathBuf]>) -> Result<Vec<(PathBuf, Status)>, Error> {
+        let repo = self.0.to_thread_local();
+
+        // The paths are matched literally, the way libgit2 is told to.
+        let patterns = pathspecs.into_iter().flatten().map(|path| {
+            let mut pattern = BString::from(":(literal)");
+            pattern.extend_from_slice(&gix::path::to_unix_separators_on_windows(
+                gix::path::into_bstr(path),
+            ));
+            pattern
+        });
+
+        let items = repo
+            .status(gix::progress::Discard)?
+            .untracked_files(UntrackedFiles::Files)
+            .dirwalk_options(|options| options.emit_ignored(Some(EmissionMode::CollapseDirectory)))
+            .index_worktree_rewrites(None)
+            .tree_index_track_renames(tree_index::TrackRenames::Disabled)
+            .into_iter(patterns)?;
+
+        // Changes to the index and to the working tree come separately, so
+        // they get combined to give one status for each path.
+        let mut statuses = BTreeMap::<BString, Status>::new();
+        for item in items {
+            let (path, status) = match item? {
+                gix::status::Item::IndexWorktree(item) => match worktree_status(&item) {
+                    Some(status) => (item.rela_path().to_owned(), status),
+                    None => continue,
+                },
+                gix::status::Item::TreeIndex(change) => {
+                    (change.location().to_owned(), index_status(&change))
+                }
+            };
+            *statuses.entry(path).or_default() |= status;
+        }
+
+        Ok(statuses
+            .into_iter()
+            .map(|(path, status)| (gix::path::from_bstring(path), status))
+            .collect())
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        let repo = self.0.to_thread_local();
+        let result = (|| -> Result<bool, Error> {
+            let index = repo.index_or_empty()?;
+            let mut excludes = repo.excludes(
+                &index,
+                None,
+                gix::worktree::stack::state::ignore::Source::WorktreeThenIdMappingIfNotSkipped,
+            )?;
+
+            let is_dir = repo.workdir().is_some_and(|w| w.join(path).is_dir());
+            let mode = if is_dir { Mode::DIR } else { Mode::FILE };
+            if !excludes.at_path(path, Some(mode))?.is_excluded() {
+                return Ok(false);
+            }
+
+            // Directories holding tracked files aren’t reported as ignored,
+            // even if they match a pattern.
+            let mut prefix =
+                gix::path::to_unix_separators_on_windows(gix::path::into_bstr(path)).into_owned();
+            prefix.push(b'/');
+            Ok(index.prefixed_entries(prefix.as_bstr()).is_none())
+        })();
+
+        result.unwrap_or_else(|e| {
+            error!("Error checking whether {path:?} is ignored: {e:?}");
+            false
+        })
+    }
+
+    fn head(&self, tags: bool) -> Option<f::SubdirGitHead> {
+        let repo = self.0.to_thread_local();
+        let head = match repo.head() {
+            Ok(head) => head,
+            Err(e) => {
+                error!("Error looking up Git branch: {e:?}");
+                return None;
+            }
+        };
+
+        if head.is_unborn() {
+            return None;
+        }
+        if let Some(name) = head.referent_name() {
+            return Some(f::SubdirGitHead::Branch(name.shorten().to_string()));
+        }
+
+        let id = head.id()?;
+        let tag = if tags {
+            let references = repo.references().ok()?;
+            references.tags().ok()?.flatten().find_map(|mut tag| {
+                let target = tag.peel_to_id().ok()?.detach();
+                (target == id.detach()).then(|| tag.name().shorten().to_string())
+            })
+        } else {
+            None
+        };
+
+        Some(match tag {
+            Some(tag) => f::SubdirGitHead::Tag(tag),
+            None => f::SubdirGitHead::Detached(id.shorten_or_id().to_string()),
+        })
+    }
+
+    fn ahead_behind(&self) -> Option<(usize, usize)> {
+        let repo = self.0.to_thread_local();
+        let head = repo.head().ok()?;
+        let upstream_name = repo
+            .branch_remote_tracking_ref_name(head.referent_name()?, gix::remote::Direction::Fetch)?
+            .ok()?;
+
+        let local = head.id()?.detach();
+        let mut upstream = repo.find_reference(upstream_name.as_ref()).ok()?;
+        let upstream = upstream.peel_to_id().ok()?.detach();
+
+        let count = |tip, hidden| {
+            let walk = repo.rev_walk([tip]).with_hidden([hidden]).all().ok()?;
+            Some(walk.count())
+        };
+        Some((count(local, upstream)?, count(upstream, local)?))
+    }
+
+    fn stash_count(&mut self) -> usize {
+        let repo = self.0.to_thread_local();
+        let Ok(Some(stash)) = repo.try_find_reference("refs/stash") else {
+            return 0;
+        };
+
+        let mut log = stash.log_iter();
+        match log.all() {
+            Ok(entries) => entries.map_or(0, Iterator::count),
+            Err(e) => {
+                error!("Error looking up Git stashes: {e:?}");
+                0
+            }
+        }
+    }
+
+    fn operation(&self) -> Option<f::SubdirGitOperation> {
+        use gix::state::InProgress as S;
+
+        #[rustfmt::skip]
+        return match self.0.to_thread_local().state()? {
+            S::Merge                                       => Some(f::SubdirGitOperation::Merge),
+            S::Revert | S::RevertSequence                  => Some(f::SubdirGitOperation::Revert),
+            S::CherryPick | S::CherryPickSequence          => Some(f::SubdirGitOperation::CherryPick),
+            S::Bisect                                      => Some(f::SubdirGitOperation::Bisect),
+            S::Rebase | S::RebaseInteractive               => Some(f::SubdirGitOperation::Rebase),
+            S::ApplyMailbox | S::ApplyMailboxRebase        => Some(f::SubdirGitOperation::ApplyMailbox),
+        };
+    }
+}
+
+/// The status of a path in the working tree compared to the index, or
+/// `None` if there’s nothing to report.
+fn worktree_status(item: &index_worktree::Item) -> Option<Status> {
+    if let index_worktree::Item::DirectoryContents { entry, .. } = item
+        && matches!(entry.status, gix::dir::entry::Status::Ignored(_))
+    {
+        return Some(Status::IGNORED);
+    }
+
+    #[rustfmt::skip]
+    return Some(match item.summary()? {
+        Summary::Added | Summary::IntentToAdd   => Status::WT_NEW,
+        Summary::Removed                        => Status::WT_DELETED,
+        Summary::Modified                       => Status::WT_MODIFIED,
+        Summary::TypeChange                     => Status::WT_TYPECHANGE,
+        Summary::Renamed | Summary::Copied      => Status::WT_RENAMED,
+        Summary::Conflict                       => Status::CONFLICTED,
+    });
+}
+
+/// The status of a path in the index compared to `HEAD`. Only changing
+/// whether a file is executable doesn’t count as changing its type.
+fn index_status(change: &ChangeRef<'_, '_>) -> Status {
+    let kind = |mode: Mode| {
+        if mode == Mode::FILE_EXECUTABLE {
+            Mode::FILE
+        } else {
+            mode
+        }
+    };
+
+    match change {
+        ChangeRef::Addition { .. } => Status::INDEX_NEW,
+        ChangeRef::Deletion { .. } => Status::INDEX_DELETED,
+        ChangeRef::Rewrite { .. } => Status::INDEX_RENAMED,
+        ChangeRef::Modification {
+            previous_entry_mode,
+            entry_mode,
+            ..
+        } => {
+            if kind(*previous_entry_mode) == kind(*entry_mode) {
+                Status::INDEX_MODIFIED
+            } else {
+                Status::INDEX_TYPECHANGE
+            }
+        }
+    }
+}
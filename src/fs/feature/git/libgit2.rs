@@ -0,0 +1,591 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! The libgit2 backend. As well as statuses, this is what looks up the last
+//! commits, diff stats, `--git-base` revisions, and the markers for
+//! submodules, worktrees and LFS pointers, none of which the gitoxide backend
+//! can do.
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+#[cfg(target_family = "unix")]
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use chrono::DateTime;
+
+use git2::StatusEntry;
+use log::{error, info};
+
+use super::{Backend, OpenMode, Status};
+use crate::fs::fields as f;
+
+impl Backend for git2::Repository {
+    type Error = git2::Error;
+
+    fn open(path: &Path, mode: OpenMode) -> Result<Self, git2::Error> {
+        let flags = match mode {
+            // These flags are consistent with how `git` uses GIT_DIR:
+            OpenMode::GitDir => {
+                git2::RepositoryOpenFlags::NO_SEARCH | git2::RepositoryOpenFlags::NO_DOTGIT
+            }
+            OpenMode::Exact => return git2::Repository::open(path),
+            OpenMode::Search => git2::RepositoryOpenFlags::FROM_ENV,
+        };
+
+        let unused: [&OsStr; 0] = [];
+        git2::Repository::open_ext(path, flags, unused)
+    }
+
+    fn workdir(&self) -> Option<PathBuf> {
+        git2::Repository::workdir(self).map(Path::to_path_buf)
+    }
+
+    fn statuses(
+        &self,
+        pathspecs: Option<&[PathBuf]>,
+    ) -> Result<Vec<(PathBuf, Status)>, git2::Error> {
+        let mut options = git2::StatusOptions::new();
+        options
+            .include_untracked(true)
+            .include_ignored(true)
+            .recurse_untracked_dirs(true);
+
+        if let Some(pathspecs) = pathspecs {
+            options.disable_pathspec_match(true);
+            for pathspec in pathspecs {
+                options.pathspec(pathspec);
+            }
+        }
+
+        let statuses = git2::Repository::statuses(self, Some(&mut options))?;
+        Ok(statuses
+            .iter()
+            .filter_map(|e| {
+                let path = get_path_from_status_entry(&e)?;
+                Some((path, Status::from_bits(e.status().bits())))
+            })
+            .collect())
+    }
+
+    fn is_ignored(&self, path: &Path) -> bool {
+        // Directories holding tracked files aren’t reported as ignored, even
+        // if they match a pattern.
+        self.status_should_ignore(path).unwrap_or(false)
+            && match self.index() {
+                Ok(index) => index.find_prefix(path.join("")).is_err(),
+                Err(e) => {
+                    error!("Error reading Git index: {e:?}");
+                    false
+                }
+            }
+    }
+
+    fn head(&self, tags: bool) -> Option<f::SubdirGitHead> {
+        let head = match git2::Repository::head(self) {
+            Ok(head) => head,
+            Err(ref e)
+                if e.code() == git2::ErrorCode::UnbornBranch
+                    || e.code() == git2::ErrorCode::NotFound =>
+            {
+                return None;
+            }
+            Err(e) => {
+                error!("Error looking up Git branch: {e:?}");
+                return None;
+            }
+        };
+
+        if head.is_branch() {
+            return head
+                .shorthand()
+                .map(|name| f::SubdirGitHead::Branch(name.to_string()));
+        }
+
+        let commit = head.peel_to_commit().ok()?;
+        if tags && let Some(tag) = tag_pointing_at(self, commit.id()) {
+            return Some(f::SubdirGitHead::Tag(tag));
+        }
+
+        let hash = commit.as_object().short_id().ok()?;
+        hash.as_str()
+            .map(|hash| f::SubdirGitHead::Detached(hash.to_string()))
+    }
+
+    fn ahead_behind(&self) -> Option<(usize, usize)> {
+        let head = git2::Repository::head(self).ok()?;
+        if !head.is_branch() {
+            return None;
+        }
+
+        let upstream_name = self.branch_upstream_name(head.name()?).ok()?;
+        let upstream = self.find_reference(upstream_name.as_str()?).ok()?;
+        self.graph_ahead_behind(head.target()?, upstream.target()?)
+            .ok()
+    }
+
+    fn stash_count(&mut self) -> usize {
+        let mut count = 0;
+        if let Err(e) = self.stash_foreach(|_, _, _| {
+            count += 1;
+            true
+        }) {
+            error!("Error looking up Git stashes: {e:?}");
+        }
+        count
+    }
+
+    fn operation(&self) -> Option<f::SubdirGitOperation> {
+        use git2::RepositoryState as S;
+
+        #[rustfmt::skip]
+        return match self.state() {
+            S::Clean                                                 => None,
+            S::Merge                                                 => Some(f::SubdirGitOperation::Merge),
+            S::Revert | S::RevertSequence                            => Some(f::SubdirGitOperation::Revert),
+            S::CherryPick | S::CherryPickSequence                    => Some(f::SubdirGitOperation::CherryPick),
+            S::Bisect                                                => Some(f::SubdirGitOperation::Bisect),
+            S::Rebase | S::RebaseInteractive | S::RebaseMerge        => Some(f::SubdirGitOperation::Rebase),
+            S::ApplyMailbox | S::ApplyMailboxOrRebase                => Some(f::SubdirGitOperation::ApplyMailbox),
+        };
+    }
+}
+
+/// Looks up the submodules of a repository, returning their absolute paths
+/// and whether the commit checked out in each one differs from the one
+/// recorded in the index. Submodules that haven’t been checked out don’t
+/// count as differing.
+pub(super) fn find_submodules(repo: &git2::Repository, workdir: &Path) -> HashMap<PathBuf, bool> {
+    info!("Looking up Git submodules for repo with workdir {workdir:?}");
+    match repo.submodules() {
+        Ok(submodules) => submodules
+            .iter()
+            .map(|sm| {
+                let recorded = sm.index_id().or_else(|| sm.head_id());
+                let moved = sm.workdir_id().is_some_and(|id| Some(id) != recorded);
+                (workdir.join(sm.path()), moved)
+            })
+            .collect(),
+        Err(e) => {
+            error!("Error looking up Git submodules: {e:?}");
+            HashMap::new()
+        }
+    }
+}
+
+/// Whether a directory is the root of a linked worktree, which has a `.git`
+/// file pointing back to the main repository rather than a `.git` directory.
+/// Submodules have one of these too, but only a worktree’s Git directory has
+/// a `commondir` file leading to the main one, which is how libgit2 tells
+/// them apart without having to open the repository.
+pub(super) fn is_linked_worktree(dir: &Path) -> bool {
+    let dot_git = dir.join(".git");
+    if !dot_git
+        .symlink_metadata()
+        .is_ok_and(|m| m.is_file() && m.len() < 4096)
+    {
+        return false;
+    }
+
+    let Ok(contents) = std::fs::read_to_string(&dot_git) else {
+        return false;
+    };
+    contents
+        .strip_prefix("gitdir:")
+        .is_some_and(|git_dir| dir.join(git_dir.trim()).join("commondir").is_file())
+}
+
+/// The start of every Git LFS pointer file.
+const LFS_POINTER_HEADER: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+
+/// Whether a file is tracked by Git LFS, but its working tree copy is still
+/// the pointer rather than the real contents, because they haven’t been
+/// fetched. Pointer files are always smaller than a kilobyte, so anything
+/// bigger doesn’t need reading, and nothing but a regular file gets opened:
+/// opening a FIFO would wait for something to write to it.
+pub(super) fn is_lfs_pointer(repo: &git2::Repository, path: &Path, relative: &Path) -> bool {
+    use std::io::Read;
+
+    if !path
+        .symlink_metadata()
+        .is_ok_and(|m| m.is_file() && m.len() < 1024)
+    {
+        return false;
+    }
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+
+    let mut header = Vec::with_capacity(LFS_POINTER_HEADER.len());
+    if file
+        .take(LFS_POINTER_HEADER.len() as u64)
+        .read_to_end(&mut header)
+        .is_err()
+        || header != LFS_POINTER_HEADER
+    {
+        return false;
+    }
+
+    repo.get_attr(relative, "filter", git2::AttrCheckFlags::FILE_THEN_INDEX)
+        .is_ok_and(|filter| filter == Some("lfs"))
+}
+
+/// Looks up the tree of the revision given with `--git-base`.
+pub(super) fn base_tree(repo: &git2::Repository, rev: &str) -> Result<git2::Oid, git2::Error> {
+    Ok(repo.revparse_single(rev)?.peel_to_tree()?.id())
+}
+
+/// Replaces the index half of each status with the difference between the
+/// given tree and the index, with renames detected the way `git diff` does,
+/// so files changed in any commit since then show up as well as the ones that
+/// are only staged.
+pub(super) fn compare_with_base(
+    repo: &git2::Repository,
+    workdir: &Path,
+    base: git2::Oid,
+    statuses: &mut Vec<(PathBuf, Status)>,
+) -> Result<(), git2::Error> {
+    let index_bits = Status::INDEX_NEW
+        | Status::INDEX_MODIFIED
+        | Status::INDEX_DELETED
+        | Status::INDEX_RENAMED
+        | Status::INDEX_TYPECHANGE;
+
+    let tree = repo.find_tree(base)?;
+    let mut diff = repo.diff_tree_to_index(Some(&tree), None, None)?;
+    diff.find_similar(None)?;
+
+    for (_, status) in statuses.iter_mut() {
+        status.remove(index_bits);
+    }
+
+    for delta in diff.deltas() {
+        #[rustfmt::skip]
+        let status = match delta.status() {
+            git2::Delta::Added       => Status::INDEX_NEW,
+            git2::Delta::Modified    => Status::INDEX_MODIFIED,
+            git2::Delta::Deleted     => Status::INDEX_DELETED,
+            git2::Delta::Renamed     => Status::INDEX_RENAMED,
+            git2::Delta::Typechange  => Status::INDEX_TYPECHANGE,
+            _                        => continue,
+        };
+
+        if let Some(path) = delta.new_file().path() {
+            statuses.push((workdir.join(path), status));
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::unnecessary_wraps)]
+fn get_path_from_status_entry(e: &StatusEntry<'_>) -> Option<PathBuf> {
+    #[cfg(target_family = "unix")]
+    return Some(PathBuf::from(OsStr::from_bytes(e.path_bytes())));
+    #[cfg(not(target_family = "unix"))]
+    return if let Some(p) = e.path() {
+        Some(PathBuf::from(p))
+    } else {
+        info!("Git status ignored for non ASCII path {:?}", e.path_bytes());
+        None
+    };
+}
+
+/// The last commit to have touched each file and directory in a repository’s
+/// `HEAD` tree.
+pub(super) struct LastCommits {
+    /// The working directory that paths are looked up relative to.
+    workdir: PathBuf,
+
+    /// The commits that were found, each only stored once.
+    commits: Vec<f::GitCommit>,
+
+    /// The absolute paths of files and directories, and the index of the
+    /// last commit to have touched them.
+    paths: HashMap<PathBuf, usize>,
+
+    /// The files and directories in the `HEAD` tree, relative to the working
+    /// directory, that haven’t been seen changing yet.
+    remaining: HashSet<PathBuf>,
+
+    /// The commits that haven’t been looked at yet, with the next one to
+    /// look at last.
+    unwalked: Vec<git2::Oid>,
+}
+
+impl LastCommits {
+    /// Lists the history from `HEAD`, newest commits first, without looking
+    /// at what any of the commits changed yet. That only happens as paths
+    /// get asked about, so listing a few files doesn’t diff every commit
+    /// in the repository.
+    pub(super) fn walk(repo: &git2::Repository, workdir: &Path) -> Self {
+        let mut last_commits = Self {
+            workdir: workdir.to_path_buf(),
+            commits: Vec::new(),
+            paths: HashMap::new(),
+            remaining: HashSet::new(),
+            unwalked: Vec::new(),
+        };
+
+        info!("Walking Git history for repo with workdir {workdir:?}");
+        if let Err(e) = last_commits.fill(repo) {
+            error!("Error walking Git history: {e:?}");
+            last_commits.unwalked.clear();
+        }
+
+        last_commits
+    }
+
+    fn fill(&mut self, repo: &git2::Repository) -> Result<(), git2::Error> {
+        let head = match repo.head() {
+            Ok(head) => head.peel_to_commit()?,
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        self.remaining.insert(PathBuf::new());
+        head.tree()?
+            .walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+                self.remaining
+                    .insert(Path::new(dir).join(tree_entry_name(entry)));
+                git2::TreeWalkResult::Ok
+            })?;
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+        revwalk.push(head.id())?;
+        self.unwalked = revwalk.collect::<Result<_, _>>()?;
+        self.unwalked.reverse();
+
+        Ok(())
+    }
+
+    /// Returns the last commit to have touched the given path, looking
+    /// through the history until it’s found. A directory takes the most
+    /// recent commit of any file underneath it.
+    ///
+    /// A merge commit only counts as touching the files that differ from
+    /// *all* of its parents, the same way `git log -- <path>` treats them, so
+    /// files merged in from another branch keep the commit that changed them
+    /// there.
+    pub(super) fn get(&mut self, repo: &git2::Repository, path: &Path) -> Option<f::GitCommit> {
+        let relative = path.strip_prefix(&self.workdir).ok()?.to_path_buf();
+
+        while self.remaining.contains(&relative) {
+            let Some(oid) = self.unwalked.pop() else {
+                break;
+            };
+            if let Err(e) = self.look_at(repo, oid) {
+                error!("Error walking Git history: {e:?}");
+                self.unwalked.clear();
+            }
+        }
+
+        self.paths
+            .get(path)
+            .map(|&index| self.commits[index].clone())
+    }
+
+    /// Records the given commit against the files it changed, and their
+    /// directories, that haven’t been seen changing in a newer one.
+    fn look_at(&mut self, repo: &git2::Repository, oid: git2::Oid) -> Result<(), git2::Error> {
+        let commit = repo.find_commit(oid)?;
+        let mut commit_index = None;
+
+        for path in changed_paths(repo, &commit)? {
+            // A path that isn’t in `HEAD` any more, such as a file that has
+            // since been deleted, still counts as a change to its directories.
+            // Once a directory has a commit, so do all of its parents.
+            let mut touched = Vec::new();
+            if self.remaining.remove(&path) {
+                touched.push(path.clone());
+            }
+            for ancestor in path.ancestors().skip(1) {
+                if !self.remaining.remove(ancestor) {
+                    break;
+                }
+                touched.push(ancestor.to_path_buf());
+            }
+
+            if touched.is_empty() {
+                continue;
+            }
+
+            let index = *commit_index.get_or_insert_with(|| {
+                self.commits.push(commit_info(&commit));
+                self.commits.len() - 1
+            });
+            for path in touched {
+                self.paths.insert(self.workdir.join(path), index);
+            }
+        }
+
+        if self.remaining.is_empty() {
+            self.unwalked.clear();
+        }
+
+        Ok(())
+    }
+}
+
+/// The number of lines changed in each file between the base tree (or `HEAD`)
+/// and the working tree, the way `git diff --stat HEAD` counts them.
+///
+/// The totals for each directory get added up as the files are counted, so
+/// looking one up doesn’t have to go through every changed file.
+pub(super) struct DiffStats {
+    /// The absolute paths of the changed files, and their counts.
+    files: HashMap<PathBuf, f::GitDiffStat>,
+
+    /// The absolute paths of the changed files and the directories in the
+    /// working directory above them, and the totals of everything at or
+    /// underneath each one.
+    totals: HashMap<PathBuf, f::GitDiffStat>,
+}
+
+impl DiffStats {
+    pub(super) fn count(repo: &git2::Repository, workdir: &Path, base: Option<git2::Oid>) -> Self {
+        let mut diff_stats = Self {
+            files: HashMap::new(),
+            totals: HashMap::new(),
+        };
+
+        info!("Counting changed lines for repo with workdir {workdir:?}");
+        if let Err(e) = diff_stats.fill(repo, workdir, base) {
+            error!("Error counting changed lines: {e:?}");
+        }
+
+        diff_stats
+    }
+
+    fn fill(
+        &mut self,
+        repo: &git2::Repository,
+        workdir: &Path,
+        base: Option<git2::Oid>,
+    ) -> Result<(), git2::Error> {
+        let tree = match base {
+            Some(base) => Some(repo.find_tree(base)?),
+            None => match repo.head() {
+                Ok(head) => Some(head.peel_to_tree()?),
+                Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+                Err(e) => return Err(e),
+            },
+        };
+
+        let mut diff = repo.diff_tree_to_workdir_with_index(tree.as_ref(), None)?;
+        diff.find_similar(None)?;
+
+        for index in 0..diff.deltas().len() {
+            let Some(patch) = git2::Patch::from_diff(&diff, index)? else {
+                continue;
+            };
+            let Some(path) = patch.delta().new_file().path().map(|p| workdir.join(p)) else {
+                continue;
+            };
+
+            let (_, added, removed) = patch.line_stats()?;
+            for ancestor in path.ancestors().take_while(|p| p.starts_with(workdir)) {
+                let total = self.totals.entry(ancestor.to_path_buf()).or_default();
+                total.added += added;
+                total.removed += removed;
+            }
+
+            let stat = self.files.entry(path).or_default();
+            stat.added += added;
+            stat.removed += removed;
+        }
+
+        Ok(())
+    }
+
+    /// Get the counts for a file, or the totals for all the paths starting
+    /// with the given prefix (in other words, a directory), in the same way
+    /// as `Git::dir_status`. The path has to be absolute already.
+    pub(super) fn get(&self, path: &Path, prefix_lookup: bool) -> f::GitDiffStat {
+        let stats = if prefix_lookup {
+            &self.totals
+        } else {
+            &self.files
+        };
+
+        stats.get(path).copied().unwrap_or_default()
+    }
+}
+
+/// The paths that a commit changed compared to its parents, relative to the
+/// root of the repository. For a merge commit, this is only the paths that
+/// differ from every one of its parents.
+fn changed_paths(
+    repo: &git2::Repository,
+    commit: &git2::Commit<'_>,
+) -> Result<HashSet<PathBuf>, git2::Error> {
+    let tree = commit.tree()?;
+    let diff_paths = |parent_tree: Option<&git2::Tree<'_>>| {
+        let diff = repo.diff_tree_to_tree(parent_tree, Some(&tree), None)?;
+        Ok::<_, git2::Error>(
+            diff.deltas()
+                .filter_map(|delta| delta.new_file().path().map(Path::to_path_buf))
+                .collect::<HashSet<_>>(),
+        )
+    };
+
+    let mut parents = commit.parents();
+    let Some(first_parent) = parents.next() else {
+        return diff_paths(None);
+    };
+
+    let mut paths = diff_paths(Some(&first_parent.tree()?))?;
+    for parent in parents {
+        if paths.is_empty() {
+            break;
+        }
+        let others = diff_paths(Some(&parent.tree()?))?;
+        paths.retain(|path| others.contains(path));
+    }
+
+    Ok(paths)
+}
+
+fn commit_info(commit: &git2::Commit<'_>) -> f::GitCommit {
+    let time = DateTime::from_timestamp(commit.time().seconds(), 0)
+        .unwrap_or_default()
+        .naive_utc();
+    let author = String::from_utf8_lossy(commit.author().name_bytes()).into_owned();
+    let hash = match commit.as_object().short_id() {
+        Ok(buf) => buf.as_str().unwrap_or_default().to_string(),
+        Err(_) => commit.id().to_string()[..7].to_string(),
+    };
+
+    f::GitCommit { time, author, hash }
+}
+
+fn tree_entry_name(entry: &git2::TreeEntry<'_>) -> PathBuf {
+    #[cfg(target_family = "unix")]
+    return PathBuf::from(OsStr::from_bytes(entry.name_bytes()));
+    #[cfg(not(target_family = "unix"))]
+    return PathBuf::from(String::from_utf8_lossy(entry.name_bytes()).into_owned());
+}
+
+/// Finds the name of a tag that points at the given commit, either directly
+/// or through an annotated tag object.
+fn tag_pointing_at(repo: &git2::Repository, commit: git2::Oid) -> Option<String> {
+    let mut found = None;
+
+    // Returning `false` stops the iteration early, which gets reported as an
+    // error that can be ignored.
+    let _ = repo.tag_foreach(|oid, name| {
+        let target = repo.find_object(oid, None).and_then(|o| o.peel_to_commit());
+        if target.is_ok_and(|c| c.id() == commit) {
+            let name = String::from_utf8_lossy(name);
+            found = Some(name.trim_start_matches("refs/tags/").to_string());
+            return false;
+        }
+        true
+    });
+
+    found
+}
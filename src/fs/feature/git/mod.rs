@@ -0,0 +1,1376 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Getting the Git status of files and directories.
+//!
+//! Repositories are read through a `Backend`: libgit2 when the `git`
+//! feature is enabled, or the pure-Rust gitoxide when only the `gix` feature
+//! is. Statuses, ignores and `--git-repos` work with either, but the last
+//! commits, diff stats, `--git-base` and markers need libgit2.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::ops::{BitOr, BitOrAssign};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::{debug, error, info, warn};
+
+use crate::fs::feature::GitOptions;
+use crate::fs::fields as f;
+
+// With both features enabled, gitoxide only gets used by the tests.
+#[cfg(feature = "gix")]
+#[cfg_attr(feature = "git", allow(dead_code))]
+mod gitoxide;
+#[cfg(feature = "git")]
+mod libgit2;
+
+#[cfg(feature = "git")]
+use self::libgit2::{
+    DiffStats, LastCommits, base_tree, compare_with_base, find_submodules, is_lfs_pointer,
+    is_linked_worktree,
+};
+
+/// The backend that repositories get opened with, which is libgit2 whenever
+/// it’s been compiled in.
+#[cfg(feature = "git")]
+type Repo = git2::Repository;
+
+#[cfg(not(feature = "git"))]
+type Repo = gitoxide::Repository;
+
+/// How the path given to [`Backend::open`] leads to a repository.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+enum OpenMode {
+    /// The path is the repository’s Git directory, as given by `GIT_DIR`.
+    GitDir,
+
+    /// The path is the repository’s working directory or Git directory.
+    Exact,
+
+    /// The path is the start of a rootwards search for the repository, which
+    /// takes the `GIT_*` environment variables into account.
+    Search,
+}
+
+/// What eza needs from a Git implementation to find repositories and look up
+/// the statuses of the files in them.
+trait Backend: Sized {
+    type Error: fmt::Debug;
+
+    /// Opens the repository that the path leads to.
+    fn open(path: &Path, mode: OpenMode) -> Result<Self, Self::Error>;
+
+    /// The repository’s working directory, or `None` if it’s bare.
+    fn workdir(&self) -> Option<PathBuf>;
+
+    /// The status of every file that differs from `HEAD`, or is untracked or
+    /// ignored, with paths relative to the working directory. Untracked
+    /// directories are listed file by file, but an ignored directory is a
+    /// single entry. With pathspecs, only the paths under them are looked at.
+    fn statuses(
+        &self,
+        pathspecs: Option<&[PathBuf]>,
+    ) -> Result<Vec<(PathBuf, Status)>, Self::Error>;
+
+    /// Whether a path, relative to the working directory, matches an ignore
+    /// rule. Directories holding tracked files are never ignored.
+    fn is_ignored(&self, path: &Path) -> bool;
+
+    /// What `HEAD` points to, or `None` if its branch hasn’t been born yet.
+    /// A detached `HEAD` is only named after a tag pointing at it when
+    /// `tags` is set, as finding one means going through every tag.
+    fn head(&self, tags: bool) -> Option<f::SubdirGitHead>;
+
+    /// The number of commits the checked-out branch is ahead of and behind
+    /// its upstream branch, if it has one.
+    fn ahead_behind(&self) -> Option<(usize, usize)>;
+
+    /// The number of entries in the stash.
+    fn stash_count(&mut self) -> usize;
+
+    /// The operation that has been started but not finished, if any.
+    fn operation(&self) -> Option<f::SubdirGitOperation>;
+}
+
+/// The status of a path, as a set of flags. The bits are the same as the
+/// ones libgit2 uses, so its statuses convert without a lookup.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+struct Status(u32);
+
+#[rustfmt::skip]
+#[allow(dead_code)]
+impl Status {
+    const INDEX_NEW:         Self = Self(1 << 0);
+    const INDEX_MODIFIED:    Self = Self(1 << 1);
+    const INDEX_DELETED:     Self = Self(1 << 2);
+    const INDEX_RENAMED:     Self = Self(1 << 3);
+    const INDEX_TYPECHANGE:  Self = Self(1 << 4);
+    const WT_NEW:            Self = Self(1 << 7);
+    const WT_MODIFIED:       Self = Self(1 << 8);
+    const WT_DELETED:        Self = Self(1 << 9);
+    const WT_TYPECHANGE:     Self = Self(1 << 10);
+    const WT_RENAMED:        Self = Self(1 << 11);
+    const IGNORED:           Self = Self(1 << 14);
+    const CONFLICTED:        Self = Self(1 << 15);
+}
+
+#[allow(dead_code)]
+impl Status {
+    const fn empty() -> Self {
+        Self(0)
+    }
+
+    const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+}
+
+impl BitOr for Status {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for Status {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+/// A **Git cache** is assembled based on the user’s input arguments.
+///
+/// This uses vectors to avoid the overhead of hashing: it’s not worth it when the
+/// expected number of Git repositories per exa invocation is 0 or 1...
+pub struct GitCache {
+    /// A list of discovered Git repositories and their paths.
+    repos: Vec<GitRepo>,
+
+    /// Paths that we’ve confirmed do not have Git repositories underneath them.
+    misses: Vec<PathBuf>,
+
+    /// Whether deleted files should be listed as ghost entries.
+    show_deleted: bool,
+}
+
+impl GitCache {
+    #[must_use]
+    pub fn has_anything_for(&self, index: &Path) -> bool {
+        self.repos.iter().any(|e| e.has_path(index))
+    }
+
+    /// Returns the status of the given path. This doesn’t include its
+    /// marker, which only the Git column shows, so looking one up doesn’t
+    /// need to touch the file itself.
+    #[must_use]
+    pub fn get(&self, index: &Path, prefix_lookup: bool) -> f::Git {
+        self.repos
+            .iter()
+            .find(|repo| repo.has_path(index))
+            .map(|repo| repo.search(index, prefix_lookup))
+            .unwrap_or_default()
+    }
+
+    /// Returns the marker for a path that Git treats specially, if it is one.
+    #[cfg(feature = "git")]
+    #[must_use]
+    pub fn marker(&self, index: &Path, prefix_lookup: bool) -> Option<f::GitMarker> {
+        if prefix_lookup && is_linked_worktree(index) {
+            return Some(f::GitMarker::Worktree);
+        }
+
+        self.repos
+            .iter()
+            .find(|repo| repo.has_path(index))
+            .and_then(|repo| repo.marker(index, prefix_lookup))
+    }
+
+    /// Markers are only looked up with libgit2.
+    #[cfg(not(feature = "git"))]
+    #[must_use]
+    pub fn marker(&self, _index: &Path, _prefix_lookup: bool) -> Option<f::GitMarker> {
+        None
+    }
+
+    /// Returns the last commit to have touched the given path, if it’s in a
+    /// repository and has been committed to it.
+    #[cfg(feature = "git")]
+    #[must_use]
+    pub fn last_commit(&self, index: &Path) -> Option<f::GitCommit> {
+        self.repos
+            .iter()
+            .find(|repo| repo.has_path(index))
+            .and_then(|repo| repo.last_commit(index))
+    }
+
+    /// Returns the number of lines added to and removed from the given path
+    /// since the last commit, or since the `--git-base` revision if one was
+    /// given. Directories get the totals of all the files underneath them.
+    #[cfg(feature = "git")]
+    #[must_use]
+    pub fn diff_stat(&self, index: &Path, prefix_lookup: bool) -> f::GitDiffStat {
+        self.repos
+            .iter()
+            .find(|repo| repo.has_path(index))
+            .map(|repo| repo.diff_stat(index, prefix_lookup))
+            .unwrap_or_default()
+    }
+
+    /// Returns the paths of the entries in the given directory that Git
+    /// tracks but that are missing from the working tree, if deleted files
+    /// are being listed. A deleted directory is returned as a single entry.
+    #[must_use]
+    pub fn deleted_files(&self, dir: &Path) -> Vec<PathBuf> {
+        if !self.show_deleted {
+            return Vec::new();
+        }
+
+        self.repos
+            .iter()
+            .find(|repo| repo.has_path(dir))
+            .map(|repo| repo.with_statuses(|statuses| statuses.deleted_names(dir)))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|name| dir.join(name))
+            .collect()
+    }
+}
+
+impl GitCache {
+    /// Discovers the Git repositories that the given paths are in, if any.
+    pub fn new<I>(iter: I, options: &GitOptions) -> Self
+    where
+        I: IntoIterator<Item = PathBuf>,
+    {
+        let base = options.base.as_deref();
+        let iter = iter.into_iter();
+        let mut git = Self {
+            repos: Vec::with_capacity(iter.size_hint().0),
+            misses: Vec::new(),
+            show_deleted: options.deleted,
+        };
+
+        if let Ok(path) = env::var("GIT_DIR") {
+            match GitRepo::discover(path.into(), OpenMode::GitDir, base) {
+                Ok(repo) => {
+                    debug!("Opened GIT_DIR repo");
+                    git.repos.push(repo);
+                }
+                Err(miss) => {
+                    git.misses.push(miss);
+                }
+            }
+        }
+
+        for path in iter {
+            if git.misses.contains(&path) {
+                debug!("Skipping {path:?} because it already came back Gitless");
+            } else if git.repos.iter().any(|e| e.has_path(&path)) {
+                debug!("Skipping {path:?} because we already queried it");
+            } else {
+                match GitRepo::discover(path, OpenMode::Search, base) {
+                    Ok(r) => {
+                        if let Some(r2) = git.repos.iter_mut().find(|e| e.has_workdir(&r.workdir)) {
+                            debug!(
+                                "Adding to existing repo (workdir matches with {:?})",
+                                r2.workdir
+                            );
+                            r2.extra_paths.push(r.original_path);
+                            continue;
+                        }
+
+                        debug!("Discovered new Git repo");
+                        git.repos.push(r);
+                    }
+                    Err(miss) => {
+                        git.misses.push(miss);
+                    }
+                }
+            }
+        }
+
+        git
+    }
+}
+
+/// A **Git repository** is one we’ve discovered somewhere on the filesystem.
+pub struct GitRepo {
+    /// The queryable contents of the repository: either an open repo, or the
+    /// cached results from when we queried it last time.
+    contents: Mutex<GitContents>,
+
+    /// The working directory of this repository.
+    /// This is used to check whether two repositories are the same.
+    workdir: PathBuf,
+
+    /// The path that was originally checked to discover this repository.
+    /// This is as important as the `extra_paths` (it gets checked first), but
+    /// is separate to avoid having to deal with a non-empty Vec.
+    original_path: PathBuf,
+
+    /// Any other paths that were checked only to result in this same
+    /// repository.
+    extra_paths: Vec<PathBuf>,
+
+    /// The tree of the revision given with `--git-base`, which staged
+    /// statuses are compared against instead of `HEAD`.
+    #[cfg(feature = "git")]
+    base: Option<git2::Oid>,
+}
+
+/// A repository’s queried state.
+struct GitContents {
+    /// All the interesting Git stuff goes through this.
+    repo: Repo,
+
+    /// The Git statuses of the repository’s files, once they’ve been
+    /// queried for the first time.
+    statuses: Option<Git>,
+
+    /// Makes the paths absolute for the lookups that don’t go through the
+    /// statuses, which have their own.
+    #[cfg(feature = "git")]
+    paths: PathResolver,
+
+    /// The last commit to have touched each file, once the history has been
+    /// walked for the first time.
+    #[cfg(feature = "git")]
+    last_commits: Option<LastCommits>,
+
+    /// The number of lines changed in each file, once they’ve been counted
+    /// for the first time.
+    #[cfg(feature = "git")]
+    diff_stats: Option<DiffStats>,
+
+    /// The absolute paths of the repository’s submodules, and whether the
+    /// commit checked out in each one differs from the one recorded for it,
+    /// once they’ve been looked up for the first time.
+    #[cfg(feature = "git")]
+    submodules: Option<HashMap<PathBuf, bool>>,
+}
+
+impl GitRepo {
+    /// Searches through this repository for a path (to a file or directory,
+    /// depending on the prefix-lookup flag) and returns its Git status.
+    ///
+    /// Actually querying the repository for the mapping of paths to Git
+    /// statuses is only done once, and gets cached so we don’t need to
+    /// re-query the entire repository the times after that.
+    fn search(&self, index: &Path, prefix_lookup: bool) -> f::Git {
+        self.with_statuses(|statuses| statuses.status(index, prefix_lookup))
+    }
+
+    /// Returns the number of lines changed in the given path (or, with the
+    /// prefix-lookup flag, under it), counting them the first time it’s asked.
+    #[cfg(feature = "git")]
+    fn diff_stat(&self, index: &Path, prefix_lookup: bool) -> f::GitDiffStat {
+        let mut contents = self.contents.lock().unwrap();
+        let GitContents {
+            repo,
+            diff_stats,
+            paths,
+            ..
+        } = &mut *contents;
+
+        diff_stats
+            .get_or_insert_with(|| DiffStats::count(repo, &self.workdir, self.base))
+            .get(&paths.resolve(index), prefix_lookup)
+    }
+
+    /// Runs a function over this repository’s statuses, querying them first
+    /// if this is the first time they’ve been needed.
+    ///
+    /// With a base tree, the staged statuses are replaced by the changes
+    /// between that tree and the index.
+    fn with_statuses<T>(&self, f: impl FnOnce(&Git) -> T) -> T {
+        let mut contents = self.contents.lock().unwrap();
+        let GitContents { repo, statuses, .. } = &mut *contents;
+
+        if statuses.is_some() {
+            debug!("Git repo {:?} has been found in cache", &self.workdir);
+        } else {
+            debug!("Querying Git repo {:?} for the first time", &self.workdir);
+        }
+
+        f(statuses.get_or_insert_with(|| {
+            let pathspecs = self.pathspecs();
+            #[cfg_attr(not(feature = "git"), allow(unused_mut))]
+            let mut found = repo_to_statuses(repo, &self.workdir, pathspecs.as_deref());
+
+            #[cfg(feature = "git")]
+            if let Some(base) = self.base
+                && let Err(e) = compare_with_base(repo, &self.workdir, base, &mut found)
+            {
+                error!("Error comparing Git index with base revision: {e:?}");
+            }
+
+            Git::new(found)
+        }))
+    }
+
+    /// The paths that were checked to discover this repository, relative to
+    /// its working directory, which are the only ones that statuses get
+    /// looked up for. Returns `None` if the whole repository is needed,
+    /// because one of the paths is the working directory itself.
+    ///
+    /// There’s no point in limiting this by the recursion depth as well, as
+    /// the deepest directories that get listed still need the statuses of
+    /// everything underneath them.
+    fn pathspecs(&self) -> Option<Vec<PathBuf>> {
+        std::iter::once(&self.original_path)
+            .chain(&self.extra_paths)
+            .map(|path| {
+                let path = reorient(path);
+                let relative = path.strip_prefix(&self.workdir).ok()?;
+                (relative != Path::new("")).then(|| relative.to_path_buf())
+            })
+            .collect()
+    }
+
+    /// Returns the last commit to have touched the given path, walking the
+    /// repository’s history only as far back as it takes to find out.
+    #[cfg(feature = "git")]
+    fn last_commit(&self, index: &Path) -> Option<f::GitCommit> {
+        let mut contents = self.contents.lock().unwrap();
+        let GitContents {
+            repo,
+            last_commits,
+            paths,
+            ..
+        } = &mut *contents;
+
+        last_commits
+            .get_or_insert_with(|| LastCommits::walk(repo, &self.workdir))
+            .get(repo, &paths.resolve(index))
+    }
+
+    /// Returns the marker for a path that’s more than a plain file or
+    /// directory: a submodule, or a file stored with Git LFS that still only
+    /// holds its pointer.
+    #[cfg(feature = "git")]
+    fn marker(&self, index: &Path, prefix_lookup: bool) -> Option<f::GitMarker> {
+        let mut contents = self.contents.lock().unwrap();
+        let GitContents {
+            repo,
+            submodules,
+            paths,
+            ..
+        } = &mut *contents;
+
+        let path = paths.resolve(index);
+        if prefix_lookup {
+            let submodules = submodules.get_or_insert_with(|| find_submodules(repo, &self.workdir));
+            return submodules
+                .get(&path)
+                .map(|&moved| f::GitMarker::Submodule { moved });
+        }
+
+        let relative = path.strip_prefix(&self.workdir).ok()?;
+        is_lfs_pointer(repo, &path, relative).then_some(f::GitMarker::LfsPointer)
+    }
+
+    /// Whether this repository has the given working directory.
+    fn has_workdir(&self, path: &Path) -> bool {
+        self.workdir == path
+    }
+
+    /// Whether this repository cares about the given path at all.
+    fn has_path(&self, path: &Path) -> bool {
+        path.starts_with(&self.original_path)
+            || self.extra_paths.iter().any(|e| path.starts_with(e))
+    }
+
+    /// Open a Git repository. Depending on the mode, the path is either
+    /// the repository's "gitdir" (or a "gitlink" to the gitdir), or the
+    /// path is the start of a rootwards search for the repository.
+    ///
+    /// If a base revision is given but can’t be found in the repository, a
+    /// warning is printed and statuses are compared against `HEAD` instead.
+    #[cfg_attr(not(feature = "git"), allow(unused_variables))]
+    fn discover(path: PathBuf, mode: OpenMode, base: Option<&str>) -> Result<Self, PathBuf> {
+        info!("Opening Git repository for {path:?} ({mode:?})");
+        let repo = match <Repo as Backend>::open(&path, mode) {
+            Ok(r) => r,
+            Err(e) => {
+                error!("Error opening Git repository for {path:?}: {e:?}");
+                return Err(path);
+            }
+        };
+
+        if let Some(workdir) = Backend::workdir(&repo) {
+            #[cfg(feature = "git")]
+            let base = base.and_then(|rev| match base_tree(&repo, rev) {
+                Ok(tree) => Some(tree),
+                Err(e) => {
+                    eprintln!(
+                        "eza: {}: unknown revision {rev:?} for --git-base",
+                        workdir.display()
+                    );
+                    error!("Error looking up Git revision {rev:?}: {e:?}");
+                    None
+                }
+            });
+            let contents = Mutex::new(GitContents {
+                repo,
+                statuses: None,
+                #[cfg(feature = "git")]
+                paths: PathResolver::default(),
+                #[cfg(feature = "git")]
+                last_commits: None,
+                #[cfg(feature = "git")]
+                diff_stats: None,
+                #[cfg(feature = "git")]
+                submodules: None,
+            });
+            Ok(Self {
+                contents,
+                workdir,
+                original_path: path,
+                extra_paths: Vec::new(),
+                #[cfg(feature = "git")]
+                base,
+            })
+        } else {
+            warn!("Repository has no workdir?");
+            Err(path)
+        }
+    }
+}
+
+/// Iterates through a repository’s statuses, returning the
+/// mapping of files to their Git status.
+/// We will have already used the working directory at this point, so it gets
+/// passed in rather than deriving it from the repository again.
+///
+/// With pathspecs, only the files under those paths are looked at, which
+/// saves scanning the rest of a large repository.
+fn repo_to_statuses<B: Backend>(
+    repo: &B,
+    workdir: &Path,
+    pathspecs: Option<&[PathBuf]>,
+) -> Vec<(PathBuf, Status)> {
+    let mut statuses = Vec::new();
+
+    if let Some(pathspecs) = pathspecs {
+        info!("Limiting Git statuses to {pathspecs:?}");
+        ignored_ancestors(repo, workdir, pathspecs, &mut statuses);
+    }
+
+    info!("Getting Git statuses for repo with workdir {workdir:?}");
+    match repo.statuses(pathspecs) {
+        Ok(es) => {
+            for (path, status) in es {
+                statuses.push((workdir.join(path), status));
+            }
+            // We manually add the `.git` at the root of the repo as ignored, since it is in practice.
+            // Also we want to avoid `eza --tree --all --git-ignore` to display files inside `.git`.
+            statuses.push((workdir.join(".git"), Status::IGNORED));
+        }
+        Err(e) => {
+            error!("Error looking up Git statuses: {e:?}");
+        }
+    }
+
+    statuses
+}
+
+/// Adds an ignored status for each pathspec, or directory above one, that
+/// would have been reported as ignored if the whole repository had been
+/// looked at. Git only reports the topmost ignored directory, which falls
+/// outside the pathspecs when one of them is inside it.
+fn ignored_ancestors<B: Backend>(
+    repo: &B,
+    workdir: &Path,
+    pathspecs: &[PathBuf],
+    statuses: &mut Vec<(PathBuf, Status)>,
+) {
+    for pathspec in pathspecs {
+        let ignored = pathspec
+            .ancestors()
+            .filter(|path| *path != Path::new(""))
+            .find(|path| repo.is_ignored(path));
+
+        if let Some(path) = ignored {
+            statuses.push((workdir.join(path), Status::IGNORED));
+        }
+    }
+}
+
+// The `repo.statuses` call above takes a long time. exa debug output:
+//
+//   20.311276  INFO:exa::fs::feature::git: Getting Git statuses for repo with workdir "/vagrant/"
+//   20.799610  DEBUG:exa::output::table: Getting Git status for file "./Cargo.toml"
+//
+// Even inserting another logging line immediately afterwards doesn’t make it
+// look any faster.
+
+/// Container of Git statuses for all the files in this folder’s Git repository.
+///
+/// The statuses are stored in a tree with one node per path component, so
+/// looking up a file only has to follow its own path rather than checking
+/// every changed file in the repository. Each node also holds the combined
+/// status of everything underneath it, which is what directories display.
+#[derive(Default)]
+struct Git {
+    root: StatusNode,
+
+    /// Makes the paths that get looked up absolute.
+    paths: PathResolver,
+}
+
+struct StatusNode {
+    /// The statuses reported for exactly this path, apart from it being
+    /// ignored.
+    status: Status,
+
+    /// The statuses of this path and everything underneath it, apart from
+    /// any of them being ignored.
+    aggregate: Status,
+
+    /// Whether this path is ignored, which applies to everything under it.
+    ignored: bool,
+
+    children: HashMap<OsString, StatusNode>,
+}
+
+impl Default for StatusNode {
+    fn default() -> Self {
+        Self {
+            status: Status::empty(),
+            aggregate: Status::empty(),
+            ignored: false,
+            children: HashMap::new(),
+        }
+    }
+}
+
+impl Git {
+    fn new(statuses: Vec<(PathBuf, Status)>) -> Self {
+        let mut git = Self::default();
+        for (path, status) in statuses {
+            git.insert(&path, status);
+        }
+        git
+    }
+
+    fn insert(&mut self, path: &Path, status: Status) {
+        let ignored = status == Status::IGNORED;
+
+        let mut node = &mut self.root;
+        for component in path.components() {
+            if !ignored {
+                node.aggregate |= status;
+            }
+            node = node
+                .children
+                .entry(component.as_os_str().to_os_string())
+                .or_default();
+        }
+
+        if ignored {
+            node.ignored = true;
+        } else {
+            node.aggregate |= status;
+            node.status |= status;
+        }
+    }
+
+    /// Follows a path down the tree, returning its node if anything has
+    /// been recorded at or under it, and whether it or any of its parent
+    /// directories is ignored.
+    fn find(&self, path: &Path) -> (Option<&StatusNode>, bool) {
+        let mut node = &self.root;
+        let mut ignored = false;
+
+        for component in path.components() {
+            match node.children.get(component.as_os_str()) {
+                Some(child) => {
+                    node = child;
+                    ignored |= node.ignored;
+                }
+                None => return (None, ignored),
+            }
+        }
+
+        (Some(node), ignored)
+    }
+
+    /// Get either the file or directory status for the given path.
+    /// “Prefix lookup” means that it should report an aggregate status of all
+    /// paths starting with the given prefix (in other words, a directory).
+    fn status(&self, index: &Path, prefix_lookup: bool) -> f::Git {
+        if prefix_lookup {
+            self.dir_status(index)
+        } else {
+            self.file_status(index)
+        }
+    }
+
+    /// Get the user-facing status of a file.
+    /// We check the statuses directly applying to a file, and for the ignored
+    /// status we check if any of its parents directories is ignored by git.
+    fn file_status(&self, file: &Path) -> f::Git {
+        let (node, ignored) = self.find(&self.paths.resolve(file));
+        Self::user_facing(node.map_or(Status::empty(), |n| n.status), ignored)
+    }
+
+    /// Get the combined, user-facing status of a directory.
+    /// Statuses are aggregating (for example, a directory is considered
+    /// modified if any file under it has the status modified), except for
+    /// ignored status which applies to files under (for example, a directory
+    /// is considered ignored if one of its parent directories is ignored).
+    fn dir_status(&self, dir: &Path) -> f::Git {
+        let (node, ignored) = self.find(&self.paths.resolve(dir));
+        Self::user_facing(node.map_or(Status::empty(), |n| n.aggregate), ignored)
+    }
+
+    fn user_facing(mut status: Status, ignored: bool) -> f::Git {
+        if ignored {
+            status |= Status::IGNORED;
+        }
+
+        let staged = index_status(status);
+        let unstaged = working_tree_status(status);
+        f::Git {
+            staged,
+            unstaged,
+            marker: None,
+        }
+    }
+
+    /// Get the names of the entries directly inside a directory that have
+    /// been deleted, either in the working tree or in the index, and are no
+    /// longer on disk. Files deleted from a subdirectory that has gone too
+    /// are reported as that subdirectory.
+    fn deleted_names(&self, dir: &Path) -> Vec<PathBuf> {
+        let path = reorient(dir);
+        let deleted = Status::WT_DELETED | Status::INDEX_DELETED;
+
+        let Some(node) = self.find(&path).0 else {
+            return Vec::new();
+        };
+
+        let mut names = node
+            .children
+            .iter()
+            .filter(|(_, child)| child.aggregate.intersects(deleted))
+            .map(|(name, _)| PathBuf::from(name))
+            .filter(|name| path.join(name).symlink_metadata().is_err())
+            .collect::<Vec<_>>();
+
+        names.sort_unstable();
+        names
+    }
+}
+
+/// Makes paths absolute to look them up, like `reorient`, but only
+/// canonicalizes the directory a path is in, and only when that differs from
+/// the last one’s. The files of a directory are listed together, so this
+/// saves canonicalizing each one of them.
+#[derive(Default)]
+struct PathResolver {
+    /// The last directory that had a file looked up in it, and its
+    /// canonical path.
+    listed_dir: RefCell<Option<(PathBuf, PathBuf)>>,
+}
+
+impl PathResolver {
+    /// Makes a path absolute. A symbolic link is still followed all the
+    /// way, so it gets looked up as the file it points to.
+    fn resolve(&self, path: &Path) -> PathBuf {
+        let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+            return reorient(path);
+        };
+        let parent = if parent == Path::new("") {
+            Path::new(".")
+        } else {
+            parent
+        };
+
+        let mut listed_dir = self.listed_dir.borrow_mut();
+        let path = match &*listed_dir {
+            Some((dir, canonical)) if dir == parent => canonical.join(name),
+            _ => {
+                let canonical = reorient(parent);
+                let path = canonical.join(name);
+                *listed_dir = Some((parent.to_path_buf(), canonical));
+                path
+            }
+        };
+
+        if path.is_symlink() {
+            reorient(&path)
+        } else {
+            path
+        }
+    }
+}
+
+/// Converts a path to an absolute path based on the current directory.
+/// Paths need to be absolute for them to be compared properly, otherwise
+/// you’d ask a repo about “./README.md” but it only knows about
+/// “/vagrant/README.md”, prefixed by the workdir.
+#[cfg(unix)]
+fn reorient(path: &Path) -> PathBuf {
+    use std::env::current_dir;
+
+    // TODO: I’m not 100% on this func tbh
+    let path = match current_dir() {
+        Err(_) => Path::new(".").join(path),
+        Ok(dir) => dir.join(path),
+    };
+
+    path.canonicalize().unwrap_or(path)
+}
+
+#[cfg(windows)]
+fn reorient(path: &Path) -> PathBuf {
+    let unc_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    // On Windows UNC path is returned. We need to strip the prefix for it to work.
+    let normal_path = unc_path
+        .as_os_str()
+        .to_str()
+        .unwrap()
+        .trim_start_matches("\\\\?\\");
+    PathBuf::from(normal_path)
+}
+
+/// The character to display if the file has been modified, but not staged.
+fn working_tree_status(status: Status) -> f::GitStatus {
+    #[rustfmt::skip]
+    return match status {
+        s if s.contains(Status::WT_NEW)         => f::GitStatus::New,
+        s if s.contains(Status::WT_MODIFIED)    => f::GitStatus::Modified,
+        s if s.contains(Status::WT_DELETED)     => f::GitStatus::Deleted,
+        s if s.contains(Status::WT_RENAMED)     => f::GitStatus::Renamed,
+        s if s.contains(Status::WT_TYPECHANGE)  => f::GitStatus::TypeChange,
+        s if s.contains(Status::IGNORED)        => f::GitStatus::Ignored,
+        s if s.contains(Status::CONFLICTED)     => f::GitStatus::Conflicted,
+        _                                             => f::GitStatus::NotModified,
+    };
+}
+
+/// The character to display if the file has been modified and the change
+/// has been staged.
+fn index_status(status: Status) -> f::GitStatus {
+    #[rustfmt::skip]
+    return match status {
+        s if s.contains(Status::INDEX_NEW)         => f::GitStatus::New,
+        s if s.contains(Status::INDEX_MODIFIED)    => f::GitStatus::Modified,
+        s if s.contains(Status::INDEX_DELETED)     => f::GitStatus::Deleted,
+        s if s.contains(Status::INDEX_RENAMED)     => f::GitStatus::Renamed,
+        s if s.contains(Status::INDEX_TYPECHANGE)  => f::GitStatus::TypeChange,
+        _                                                => f::GitStatus::NotModified,
+    };
+}
+
+impl f::SubdirGitRepo {
+    #[must_use]
+    pub fn from_path(dir: &Path, status: bool) -> Self {
+        subdir_repo::<Repo>(&reorient(dir), status)
+    }
+}
+
+/// Whether the given path is the working directory or Git directory of a
+/// repository.
+#[must_use]
+pub fn is_repository(path: &Path) -> bool {
+    <Repo as Backend>::open(path, OpenMode::Exact).is_ok()
+}
+
+/// Looks up the state of the repository whose working directory is the given
+/// path, without searching any further up for one.
+fn subdir_repo<B: Backend>(path: &Path, status: bool) -> f::SubdirGitRepo {
+    let Ok(mut repo) = B::open(path, OpenMode::Exact) else {
+        return f::SubdirGitRepo {
+            status: status.then_some(f::SubdirGitRepoStatus::NoRepo),
+            ..f::SubdirGitRepo::default()
+        };
+    };
+
+    let mut result = f::SubdirGitRepo {
+        status: None,
+        head: repo.head(status),
+        operation: repo.operation(),
+        ..f::SubdirGitRepo::default()
+    };
+
+    // Without the status, only what can be read straight from the
+    // repository’s files is looked up.
+    if status {
+        result.ahead_behind = repo.ahead_behind();
+        result.stashes = repo.stash_count();
+
+        match repo.statuses(None) {
+            Ok(es) => {
+                result.dirty = es.iter().filter(|s| s.1 != Status::IGNORED).count();
+                result.status = if result.dirty > 0 {
+                    Some(f::SubdirGitRepoStatus::GitDirty)
+                } else {
+                    Some(f::SubdirGitRepoStatus::GitClean)
+                };
+            }
+            Err(e) => {
+                error!("Error looking up Git statuses: {e:?}");
+                result = f::SubdirGitRepo {
+                    status: Some(f::SubdirGitRepoStatus::NoRepo),
+                    ..f::SubdirGitRepo::default()
+                };
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fs::test::TestDir;
+
+    #[test]
+    fn deleted_names_are_direct_children_missing_from_disk() {
+        let root = TestDir::create();
+        std::fs::write(root.join("restored"), "").unwrap();
+
+        let git = Git::new(vec![
+            (root.join("gone"), Status::WT_DELETED),
+            (root.join("restored"), Status::INDEX_DELETED),
+            (root.join("old").join("a"), Status::INDEX_DELETED),
+            (root.join("old").join("b"), Status::WT_DELETED),
+            (root.join("changed"), Status::WT_MODIFIED),
+        ]);
+
+        assert_eq!(
+            git.deleted_names(&root),
+            vec![PathBuf::from("gone"), PathBuf::from("old")]
+        );
+    }
+
+    #[test]
+    fn statuses_aggregate_up_and_ignores_apply_down() {
+        let root = std::env::temp_dir()
+            .canonicalize()
+            .unwrap()
+            .join("eza-git-status-index");
+
+        let git = Git::new(vec![
+            (root.join("src").join("new.rs"), Status::WT_NEW),
+            (root.join("src").join("lib.rs"), Status::INDEX_MODIFIED),
+            (root.join("target"), Status::IGNORED),
+        ]);
+
+        let status = |path: PathBuf, prefix_lookup| {
+            let s = git.status(&path, prefix_lookup);
+            (s.staged, s.unstaged)
+        };
+
+        use f::GitStatus::*;
+        assert_eq!(
+            status(root.join("src").join("new.rs"), false),
+            (NotModified, New)
+        );
+        assert_eq!(
+            status(root.join("src").join("lib.rs"), false),
+            (Modified, NotModified)
+        );
+        assert_eq!(status(root.join("src"), true), (Modified, New));
+        assert_eq!(status(root.join("src"), false), (NotModified, NotModified));
+        assert_eq!(
+            status(root.join("target").join("debug"), true),
+            (NotModified, Ignored)
+        );
+        assert_eq!(
+            status(root.join("README.md"), false),
+            (NotModified, NotModified)
+        );
+        assert_eq!(status(root.clone(), true), (Modified, New));
+    }
+}
+
+/// Tests that every backend compiled into this build has to pass, run against
+/// repositories set up with the `git` command.
+#[cfg(test)]
+mod backend_test {
+    use std::process::Command;
+
+    use super::*;
+    use crate::fs::test::TestDir;
+
+    /// A repository with something in every state: committed, modified,
+    /// staged, added, deleted, untracked and ignored.
+    fn fixture() -> TestDir {
+        fixture_in(&std::env::temp_dir())
+    }
+
+    fn fixture_in(parent: &Path) -> TestDir {
+        let root = TestDir::create_in(parent);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        let git = |args: &[&str]| git(&root, args);
+
+        git(&["init", "--quiet", "--initial-branch=main"]);
+        std::fs::write(root.join(".gitignore"), "target/\n*.log\n").unwrap();
+        std::fs::write(root.join("tracked.txt"), "one\n").unwrap();
+        std::fs::write(root.join("src").join("lib.rs"), "one\n").unwrap();
+        std::fs::write(root.join("docs").join("old.md"), "one\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "--quiet", "--message", "First"]);
+        git(&["tag", "--annotate", "--message", "Release", "v1"]);
+
+        std::fs::write(root.join("tracked.txt"), "two\n").unwrap();
+        std::fs::write(root.join("src").join("lib.rs"), "two\n").unwrap();
+        std::fs::write(root.join("src").join("new.rs"), "one\n").unwrap();
+        git(&["add", "src"]);
+        std::fs::remove_file(root.join("docs").join("old.md")).unwrap();
+        std::fs::create_dir_all(root.join("notes")).unwrap();
+        std::fs::write(root.join("notes").join("todo.txt"), "").unwrap();
+        std::fs::create_dir_all(root.join("target").join("debug")).unwrap();
+        std::fs::write(root.join("target").join("debug").join("out"), "").unwrap();
+        std::fs::write(root.join("build.log"), "").unwrap();
+
+        root
+    }
+
+    /// Runs a `git` command in the given repository, without any of the
+    /// user’s own settings.
+    fn git(root: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=eza", "-c", "user.email=eza@example.com"])
+            .args(["-c", "commit.gpgsign=false", "-c", "tag.gpgsign=false"])
+            .args(args)
+            .current_dir(root)
+            .env("GIT_CONFIG_GLOBAL", "/dev/null")
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    pub fn statuses<B: Backend>() {
+        let root = fixture();
+        let repo = B::open(&root, OpenMode::Search).unwrap();
+        assert_eq!(repo.workdir().unwrap().canonicalize().unwrap(), *root);
+
+        let mut statuses = repo.statuses(None).unwrap();
+        statuses.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            statuses,
+            vec![
+                (PathBuf::from("build.log"), Status::IGNORED),
+                (PathBuf::from("docs/old.md"), Status::WT_DELETED),
+                (PathBuf::from("notes/todo.txt"), Status::WT_NEW),
+                (PathBuf::from("src/lib.rs"), Status::INDEX_MODIFIED),
+                (PathBuf::from("src/new.rs"), Status::INDEX_NEW),
+                (PathBuf::from("target"), Status::IGNORED),
+                (PathBuf::from("tracked.txt"), Status::WT_MODIFIED),
+            ]
+        );
+
+        let statuses = repo.statuses(Some(&[PathBuf::from("src")])).unwrap();
+        let mut paths = statuses.into_iter().map(|s| s.0).collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/new.rs")]
+        );
+    }
+
+    pub fn relative_path<B: Backend>() {
+        // Tests run from the crate root, so the repository goes under its
+        // target directory to be reachable by a relative path.
+        let root = fixture_in(&std::env::current_dir().unwrap().join("target"));
+        let relative = Path::new("target").join(root.file_name().unwrap());
+
+        let repo = B::open(&relative, OpenMode::Search).unwrap();
+        let workdir = repo.workdir().unwrap();
+        assert!(workdir.is_absolute());
+
+        let git = Git::new(repo_to_statuses(&repo, &workdir, None));
+        let tracked = root.join("tracked.txt");
+        assert_eq!(git.status(&tracked, false).unstaged, f::GitStatus::Modified);
+        let tracked = relative.join("tracked.txt");
+        assert_eq!(git.status(&tracked, false).unstaged, f::GitStatus::Modified);
+        let lib = relative.join("src").join("lib.rs");
+        assert_eq!(git.status(&lib, false).staged, f::GitStatus::Modified);
+    }
+
+    /// A symbolic link has the status of the file it points to, the same as
+    /// when the whole path gets canonicalized.
+    #[cfg(unix)]
+    pub fn symlinks_follow_their_targets<B: Backend>() {
+        let root = fixture();
+        std::os::unix::fs::symlink("tracked.txt", root.join("link")).unwrap();
+        std::os::unix::fs::symlink("../src/lib.rs", root.join("docs").join("lib")).unwrap();
+
+        let repo = B::open(&root, OpenMode::Search).unwrap();
+        let git = Git::new(repo_to_statuses(&repo, &root, None));
+        let link = git.status(&root.join("link"), false);
+        assert_eq!(link.unstaged, f::GitStatus::Modified);
+        let lib = git.status(&root.join("docs").join("lib"), false);
+        assert_eq!(lib.staged, f::GitStatus::Modified);
+    }
+
+    pub fn pathspecs_keep_ignored_parents<B: Backend>() {
+        let root = fixture();
+        let repo = B::open(&root, OpenMode::Search).unwrap();
+
+        let pathspecs = [PathBuf::from("target/debug")];
+        let git = Git::new(repo_to_statuses(&repo, &root, Some(&pathspecs)));
+        let out = root.join("target").join("debug").join("out");
+        assert_eq!(git.status(&out, false).unstaged, f::GitStatus::Ignored);
+
+        assert!(repo.is_ignored(Path::new("target")));
+        assert!(repo.is_ignored(Path::new("build.log")));
+        assert!(!repo.is_ignored(Path::new("src")));
+    }
+
+    pub fn subdir_repos<B: Backend>() {
+        let root = fixture();
+
+        let repo = subdir_repo::<B>(&root, true);
+        assert_eq!(repo.head, Some(f::SubdirGitHead::Branch("main".into())));
+        assert_eq!(repo.dirty, 5);
+        assert!(repo.status == Some(f::SubdirGitRepoStatus::GitDirty));
+
+        let repo = subdir_repo::<B>(&root, false);
+        assert_eq!(repo.head, Some(f::SubdirGitHead::Branch("main".into())));
+        assert_eq!(repo.dirty, 0);
+        assert!(repo.status.is_none());
+
+        let repo = subdir_repo::<B>(&root.join("src"), true);
+        assert!(repo.status == Some(f::SubdirGitRepoStatus::NoRepo));
+    }
+
+    pub fn detached_head_shows_tag<B: Backend>() {
+        let root = fixture();
+        let head =
+            std::fs::read_to_string(root.join(".git").join("refs").join("heads").join("main"));
+        std::fs::write(root.join(".git").join("HEAD"), head.unwrap()).unwrap();
+
+        let repo = B::open(&root, OpenMode::Exact).unwrap();
+        assert_eq!(repo.head(true), Some(f::SubdirGitHead::Tag("v1".into())));
+        assert!(matches!(
+            repo.head(false),
+            Some(f::SubdirGitHead::Detached(_))
+        ));
+    }
+
+    macro_rules! backend_tests {
+        ($backend:ident: $repo:ty) => {
+            mod $backend {
+                #[test]
+                fn statuses() {
+                    super::statuses::<$repo>();
+                }
+
+                #[test]
+                fn relative_path() {
+                    super::relative_path::<$repo>();
+                }
+
+                #[cfg(unix)]
+                #[test]
+                fn symlinks_follow_their_targets() {
+                    super::symlinks_follow_their_targets::<$repo>();
+                }
+
+                #[test]
+                fn pathspecs_keep_ignored_parents() {
+                    super::pathspecs_keep_ignored_parents::<$repo>();
+                }
+
+                #[test]
+                fn subdir_repos() {
+                    super::subdir_repos::<$repo>();
+                }
+
+                #[test]
+                fn detached_head_shows_tag() {
+                    super::detached_head_shows_tag::<$repo>();
+                }
+            }
+        };
+    }
+
+    /// Comparing against an earlier commit with `--git-base` shows what
+    /// has been committed since as staged, along with what’s only staged.
+    #[cfg(feature = "git")]
+    #[test]
+    fn base_revision_shows_later_commits_as_staged() {
+        let root = fixture();
+        git(&root, &["commit", "--quiet", "--message", "Second"]);
+        std::fs::write(root.join("tracked.txt"), "three\n").unwrap();
+        git(&root, &["add", "tracked.txt"]);
+
+        let status = |base: Option<&str>, path: &Path| {
+            let options = GitOptions {
+                base: base.map(String::from),
+                deleted: false,
+            };
+            let cache = GitCache::new(vec![root.to_path_buf()], &options);
+            let git = cache.get(&root.join(path), false);
+            (git.staged, git.unstaged)
+        };
+
+        use f::GitStatus::{Deleted, Modified, New, NotModified};
+        let lib = Path::new("src/lib.rs");
+        let new = Path::new("src/new.rs");
+        let tracked = Path::new("tracked.txt");
+        let old = Path::new("docs/old.md");
+
+        assert_eq!(status(None, lib), (NotModified, NotModified));
+        assert_eq!(status(None, new), (NotModified, NotModified));
+        assert_eq!(status(None, tracked), (Modified, NotModified));
+
+        assert_eq!(status(Some("HEAD~1"), lib), (Modified, NotModified));
+        assert_eq!(status(Some("HEAD~1"), new), (New, NotModified));
+        assert_eq!(status(Some("HEAD~1"), tracked), (Modified, NotModified));
+        assert_eq!(status(Some("HEAD~1"), old), (NotModified, Deleted));
+    }
+
+    /// The history is only walked as far as it takes to find the commits
+    /// of the paths that get asked about, but each still gets the newest
+    /// commit to have changed it, or anything underneath it.
+    #[cfg(feature = "git")]
+    #[test]
+    fn last_commits_of_files_and_directories() {
+        let root = fixture();
+        git(&root, &["commit", "--quiet", "--message", "Second"]);
+
+        let cache = GitCache::new(vec![root.to_path_buf()], &GitOptions::default());
+        let hash = |path: &str| cache.last_commit(&root.join(path)).map(|c| c.hash);
+
+        let second = hash("src/lib.rs");
+        assert!(second.is_some());
+        assert_eq!(hash("src"), second);
+        assert_eq!(hash(""), second);
+
+        let first = hash("tracked.txt");
+        assert!(first.is_some() && first != second);
+        assert_eq!(hash("docs"), first);
+        assert_eq!(hash("docs/old.md"), first);
+        assert_eq!(hash("notes/todo.txt"), None);
+    }
+
+    /// A directory whose newest change was deleting a file gets the commit
+    /// that deleted it, the same as `git log -1 -- <dir>`.
+    #[cfg(feature = "git")]
+    #[test]
+    fn last_commits_count_deleted_files() {
+        let root = fixture();
+        std::fs::write(root.join("docs").join("kept.md"), "one\n").unwrap();
+        git(&root, &["add", "docs/kept.md"]);
+        git(&root, &["commit", "--quiet", "--message", "Second"]);
+        git(&root, &["rm", "--quiet", "--cached", "docs/old.md"]);
+        git(&root, &["commit", "--quiet", "--message", "Third"]);
+
+        let cache = GitCache::new(vec![root.to_path_buf()], &GitOptions::default());
+        let hash = |path: &str| cache.last_commit(&root.join(path)).map(|c| c.hash);
+
+        let third = hash("docs");
+        let second = hash("docs/kept.md");
+        assert!(third.is_some() && second.is_some() && third != second);
+        assert_eq!(hash(""), third);
+        assert_eq!(hash("src/lib.rs"), second);
+    }
+
+    /// Directories get the totals of the lines changed in all the files
+    /// underneath them, with paths relative to the current directory.
+    #[cfg(feature = "git")]
+    #[test]
+    fn diff_stats_of_files_and_directories() {
+        let root = fixture_in(&std::env::current_dir().unwrap().join("target"));
+        let relative = Path::new("target").join(root.file_name().unwrap());
+        std::fs::write(root.join("src").join("more.rs"), "one\ntwo\n").unwrap();
+        git(&root, &["add", "src/more.rs"]);
+
+        let cache = GitCache::new(vec![relative.clone()], &GitOptions::default());
+        let stat = |path: &Path, prefix_lookup| {
+            let stat = cache.diff_stat(path, prefix_lookup);
+            (stat.added, stat.removed)
+        };
+
+        assert_eq!(stat(&relative.join("src").join("lib.rs"), false), (1, 1));
+        assert_eq!(stat(&relative.join("src").join("more.rs"), false), (2, 0));
+        assert_eq!(stat(&relative.join("src"), true), (3, 1));
+        assert_eq!(stat(&relative.join("src"), false), (0, 0));
+        assert_eq!(stat(&relative.join("tracked.txt"), false), (1, 1));
+        assert_eq!(stat(&relative.join("notes"), true), (0, 0));
+        assert_eq!(stat(&relative, true), (4, 2));
+
+        // `src/new.rs` has the same contents as the deleted `docs/old.md`,
+        // so it counts as that file having moved, like with `git diff -M`.
+        assert_eq!(stat(&relative.join("src").join("new.rs"), false), (0, 0));
+        assert_eq!(stat(&relative.join("docs"), true), (0, 0));
+    }
+
+    /// Looking up a FIFO never opens it, which would wait for something to
+    /// write to it, whether it’s only its status or its marker as well.
+    #[cfg(all(feature = "git", unix))]
+    #[test]
+    fn fifos_are_not_opened() {
+        let root = fixture();
+        let pipe = root.join("pipe");
+        assert!(
+            Command::new("mkfifo")
+                .arg(&pipe)
+                .status()
+                .unwrap()
+                .success()
+        );
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let (cache_root, cache_pipe) = (root.to_path_buf(), pipe.clone());
+        std::thread::spawn(move || {
+            let cache = GitCache::new(vec![cache_root], &GitOptions::default());
+            let _ = cache.get(&cache_pipe, false);
+            sender.send(cache.marker(&cache_pipe, false)).unwrap();
+        });
+
+        let marker = receiver.recv_timeout(std::time::Duration::from_secs(10));
+        assert_eq!(marker, Ok(None));
+    }
+
+    /// A linked worktree is told apart from a submodule by its `commondir`
+    /// file, without having to open it.
+    #[cfg(feature = "git")]
+    #[test]
+    fn linked_worktrees_get_marked() {
+        let root = fixture();
+        git(&root, &["worktree", "add", "--quiet", "linked"]);
+
+        let cache = GitCache::new(vec![root.to_path_buf()], &GitOptions::default());
+        let marker = cache.marker(&root.join("linked"), true);
+        assert_eq!(marker, Some(f::GitMarker::Worktree));
+        assert_eq!(cache.marker(&root.join("src"), true), None);
+        assert_eq!(cache.marker(&root, true), None);
+    }
+
+    #[cfg(feature = "git")]
+    backend_tests!(with_libgit2: git2::Repository);
+
+    #[cfg(feature = "gix")]
+    backend_tests!(with_gitoxide: super::gitoxide::Repository);
+}
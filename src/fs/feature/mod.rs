@@ -6,28 +6,37 @@
 // SPDX-License-Identifier: MIT
 pub mod xattr;
 
-#[cfg(feature = "git")]
+/// Options that change what the Git cache reports about files.
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct GitOptions {
+    /// The revision to compare files against, instead of `HEAD`.
+    pub base: Option<String>,
+
+    /// Whether to list tracked files that have been deleted from the working
+    /// tree as ghost entries.
+    pub deleted: bool,
+}
+
+#[cfg(any(feature = "git", feature = "gix"))]
 pub mod git;
 
-#[cfg(not(feature = "git"))]
+#[cfg(not(any(feature = "git", feature = "gix")))]
 pub mod git {
-    use std::iter::FromIterator;
     use std::path::{Path, PathBuf};
 
+    use super::GitOptions;
     use crate::fs::fields as f;
 
     pub struct GitCache;
 
-    impl FromIterator<PathBuf> for GitCache {
-        fn from_iter<I>(_iter: I) -> Self
+    impl GitCache {
+        pub fn new<I>(_iter: I, _options: &GitOptions) -> Self
         where
             I: IntoIterator<Item = PathBuf>,
         {
             Self
         }
-    }
 
-    impl GitCache {
         pub fn has_anything_for(&self, _index: &Path) -> bool {
             false
         }
@@ -35,6 +44,10 @@ pub mod git {
         pub fn get(&self, _index: &Path, _prefix_lookup: bool) -> f::Git {
             unreachable!();
         }
+
+        pub fn deleted_files(&self, _dir: &Path) -> Vec<PathBuf> {
+            Vec::new()
+        }
     }
 
     impl f::SubdirGitRepo {
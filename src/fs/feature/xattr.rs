@@ -528,29 +528,38 @@ mod extended_attrs {
 
 const ATTRIBUTE_VALUE_MAX_HEX_LENGTH: usize = 16;
 
-// Display for an attribute.  Attribute values that have a custom display are
-// enclosed in curley brackets.
-impl Display for Attribute {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{}: ", self.name))?;
+impl Attribute {
+    /// Describes this attribute the way its `Display` implementation does,
+    /// but with `length` formatting the size of values too long to show.
+    pub fn describe(&self, length: impl Fn(usize) -> String) -> String {
+        let mut description = format!("{}: ", self.name);
         if let Some(value) = custom_attr_display(self) {
-            f.write_fmt(format_args!("<{value}>"))
+            description += &format!("<{value}>");
         } else {
             match &self.value {
-                None => f.write_str("<empty>"),
+                None => description += "<empty>",
                 Some(value) => {
                     if let Some(val) = custom_value_display(value) {
-                        f.write_fmt(format_args!("<{val}>"))
+                        description += &format!("<{val}>");
                     } else if let Ok(v) = str::from_utf8(value) {
-                        f.write_fmt(format_args!("{:?}", v.trim_end_matches(char::from(0))))
+                        description += &format!("{:?}", v.trim_end_matches(char::from(0)));
                     } else if value.len() <= ATTRIBUTE_VALUE_MAX_HEX_LENGTH {
-                        f.write_fmt(format_args!("{value:02x?}"))
+                        description += &format!("{value:02x?}");
                     } else {
-                        f.write_fmt(format_args!("<length {}>", value.len()))
+                        description += &format!("<length {}>", length(value.len()));
                     }
                 }
             }
         }
+        description
+    }
+}
+
+// Display for an attribute.  Attribute values that have a custom display are
+// enclosed in curley brackets.
+impl Display for Attribute {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.describe(|length| length.to_string()))
     }
 }
 
@@ -206,10 +206,32 @@ pub struct DeviceIDs {
     pub minor: u32,
 }
 
+/// A file’s share of the total size of the files listed alongside it, as
+/// shown in the usage column.
+#[derive(Copy, Clone)]
+pub struct Usage {
+    /// The file’s size in bytes, or `None` if it has no size to count.
+    pub size: Option<u64>,
+
+    /// The total size of all the files listed alongside it, this one
+    /// included.
+    pub total: u64,
+}
+
+impl Usage {
+    /// The fraction of the total that this file takes up, if it has a size
+    /// and there’s anything to take a share of.
+    #[must_use]
+    pub fn ratio(self) -> Option<f64> {
+        let size = self.size?;
+        (self.total > 0).then(|| size as f64 / self.total as f64)
+    }
+}
+
 /// A file’s status in a Git repository. Whether a file is in a repository or
 /// not is handled by the Git module, rather than having a “null” variant in
 /// this enum.
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum GitStatus {
     /// This file hasn’t changed since the last commit.
     NotModified,
@@ -244,6 +266,9 @@ pub enum GitStatus {
 pub struct Git {
     pub staged: GitStatus,
     pub unstaged: GitStatus,
+
+    /// Whether the file is something Git treats specially.
+    pub marker: Option<GitMarker>,
 }
 
 impl Default for Git {
@@ -252,10 +277,52 @@ impl Default for Git {
         Self {
             staged: GitStatus::NotModified,
             unstaged: GitStatus::NotModified,
+            marker: None,
         }
     }
 }
 
+/// Something about a file that Git treats specially, and which is shown next
+/// to its status.
+#[allow(dead_code)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum GitMarker {
+    /// The directory is a submodule. If the commit checked out in it differs
+    /// from the one that has been recorded for it, it has `moved`.
+    Submodule { moved: bool },
+
+    /// The directory is the root of a linked worktree.
+    Worktree,
+
+    /// The file is stored with Git LFS, but only its pointer has been
+    /// checked out.
+    LfsPointer,
+}
+
+/// The number of lines added to and removed from a file since the last
+/// commit, or the totals for all the files underneath a directory.
+#[cfg(feature = "git")]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct GitDiffStat {
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// The most recent commit to have touched a file. For a directory, this is
+/// the most recent commit to have touched anything underneath it.
+#[cfg(feature = "git")]
+#[derive(Clone)]
+pub struct GitCommit {
+    /// The time the commit was made, in UTC.
+    pub time: chrono::NaiveDateTime,
+
+    /// The name of the commit’s author.
+    pub author: String,
+
+    /// The commit’s hash, abbreviated the way `git log --oneline` does.
+    pub hash: String,
+}
+
 pub enum SecurityContextType<'a> {
     SELinux(&'a str),
     None,
@@ -273,17 +340,66 @@ pub enum SubdirGitRepoStatus {
     GitDirty,
 }
 
+/// What a repository’s `HEAD` points to.
+#[cfg(any(feature = "git", feature = "gix"))]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum SubdirGitHead {
+    /// A branch is checked out.
+    Branch(String),
+
+    /// `HEAD` is detached at a commit that has this tag.
+    Tag(String),
+
+    /// `HEAD` is detached at a commit with this abbreviated hash.
+    Detached(String),
+}
+
+/// An operation that has been started in a repository, but not finished.
+#[cfg(any(feature = "git", feature = "gix"))]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum SubdirGitOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+    ApplyMailbox,
+}
+
 #[derive(Clone)]
 pub struct SubdirGitRepo {
     pub status: Option<SubdirGitRepoStatus>,
-    pub branch: Option<String>,
+
+    /// What `HEAD` points to, if it has been born.
+    #[cfg(any(feature = "git", feature = "gix"))]
+    pub head: Option<SubdirGitHead>,
+
+    /// The number of commits the branch is ahead of and behind its upstream,
+    /// if it has one.
+    pub ahead_behind: Option<(usize, usize)>,
+
+    /// The number of files with changes, if the status was looked up.
+    pub dirty: usize,
+
+    /// The number of entries in the stash.
+    pub stashes: usize,
+
+    /// The operation in progress, if any.
+    #[cfg(any(feature = "git", feature = "gix"))]
+    pub operation: Option<SubdirGitOperation>,
 }
 
 impl Default for SubdirGitRepo {
     fn default() -> Self {
         Self {
             status: Some(SubdirGitRepoStatus::NoRepo),
-            branch: None,
+            #[cfg(any(feature = "git", feature = "gix"))]
+            head: None,
+            ahead_behind: None,
+            dirty: 0,
+            stashes: 0,
+            #[cfg(any(feature = "git", feature = "gix"))]
+            operation: None,
         }
     }
 }
@@ -6,8 +6,6 @@
 // SPDX-License-Identifier: MIT
 //! Files, and methods and fields to access their metadata.
 
-#[cfg(unix)]
-use std::collections::HashMap;
 use std::fs::FileType;
 use std::io;
 #[cfg(unix)]
@@ -17,35 +15,23 @@ use std::os::windows::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 #[cfg(unix)]
 use std::str;
-#[cfg(unix)]
-use std::sync::Mutex;
 use std::sync::OnceLock;
 use std::time::SystemTime;
 
 use chrono::prelude::*;
 
 use log::{debug, error, trace};
-#[cfg(unix)]
-use std::sync::LazyLock;
 
 use crate::fs::dir::Dir;
 use crate::fs::feature::xattr;
 use crate::fs::feature::xattr::{Attribute, FileAttributes};
 use crate::fs::fields as f;
 use crate::fs::fields::SecurityContextType;
-use crate::fs::recursive_size::RecursiveSize;
+use crate::fs::recursive_size::{self, RecursiveSize, TotalSize};
 
 use super::mounts::MountedFs;
 use super::mounts::all_mounts;
 
-// Maps (device_id, inode) => (size_in_bytes, size_in_blocks)
-// Mutex::new is const but HashMap::new is not const requiring us to use lazy
-// initialization.
-#[allow(clippy::type_complexity)]
-#[cfg(unix)]
-static DIRECTORY_SIZE_CACHE: LazyLock<Mutex<HashMap<(u64, u64), (u64, u64)>>> =
-    LazyLock::new(|| Mutex::new(HashMap::new()));
-
 /// A **File** is a wrapper around one of Rust’s `PathBuf` values, along with
 /// associated data about the file.
 ///
@@ -101,6 +87,13 @@ pub struct File<'dir> {
     /// means that they should be skipped when recursing.
     pub is_all_all: bool,
 
+    /// Whether this is a “ghost” entry for a file that Git tracks but that
+    /// has been deleted from the working tree.
+    ///
+    /// These don’t exist on the filesystem, so querying their metadata will
+    /// fail; they are only listed to show the full set of pending changes.
+    pub is_ghost: bool,
+
     /// Whether to dereference symbolic links when querying for information.
     ///
     /// For instance, when querying the size of a symbolic link, if
@@ -111,11 +104,19 @@ pub struct File<'dir> {
     /// The recursive directory size when `total_size` is used.
     recursive_size: RecursiveSize,
 
+    /// How recursive directory sizes are computed, which the files inside
+    /// this one inherit.
+    total_size: TotalSize,
+
     /// The extended attributes of this file.
     extended_attributes: OnceLock<Vec<Attribute>>,
 
     /// The absolute value of this path, used to look up mount points.
     absolute_path: OnceLock<Option<PathBuf>>,
+
+    /// The number of entries in this directory, once the file filter has
+    /// counted them.
+    entry_count: OnceLock<Option<u64>>,
 }
 
 impl<'dir> File<'dir> {
@@ -124,7 +125,7 @@ impl<'dir> File<'dir> {
         parent_dir: PD,
         filename: FN,
         deref_links: bool,
-        total_size: bool,
+        total_size: TotalSize,
         filetype: Option<std::fs::FileType>,
     ) -> File<'dir>
     where
@@ -136,7 +137,7 @@ impl<'dir> File<'dir> {
         let ext = File::ext(&path);
 
         let is_all_all = false;
-        let recursive_size = if total_size {
+        let recursive_size = if total_size.is_on() {
             RecursiveSize::Unknown
         } else {
             RecursiveSize::None
@@ -157,15 +158,18 @@ impl<'dir> File<'dir> {
             path,
             parent_dir,
             is_all_all,
+            is_ghost: false,
             deref_links,
             recursive_size,
+            total_size,
             filetype,
             metadata: OnceLock::new(),
             extended_attributes: OnceLock::new(),
+            entry_count: OnceLock::new(),
             absolute_path: OnceLock::new(),
         };
 
-        if total_size {
+        if total_size.is_on() {
             file.recursive_size = file.recursive_directory_size();
         }
 
@@ -176,13 +180,13 @@ impl<'dir> File<'dir> {
         path: PathBuf,
         parent_dir: &'dir Dir,
         name: &'static str,
-        total_size: bool,
+        total_size: TotalSize,
     ) -> File<'dir> {
         let ext = File::ext(&path);
 
         let is_all_all = true;
         let parent_dir = Some(parent_dir);
-        let recursive_size = if total_size {
+        let recursive_size = if total_size.is_on() {
             RecursiveSize::Unknown
         } else {
             RecursiveSize::None
@@ -194,15 +198,18 @@ impl<'dir> File<'dir> {
             path,
             parent_dir,
             is_all_all,
+            is_ghost: false,
             deref_links: false,
             recursive_size,
+            total_size,
             metadata: OnceLock::new(),
             absolute_path: OnceLock::new(),
             extended_attributes: OnceLock::new(),
+            entry_count: OnceLock::new(),
             filetype: OnceLock::new(),
         };
 
-        if total_size {
+        if total_size.is_on() {
             file.recursive_size = file.recursive_directory_size();
         }
 
@@ -210,15 +217,28 @@ impl<'dir> File<'dir> {
     }
 
     #[must_use]
-    pub fn new_aa_current(parent_dir: &'dir Dir, total_size: bool) -> File<'dir> {
+    pub fn new_aa_current(parent_dir: &'dir Dir, total_size: TotalSize) -> File<'dir> {
         File::new_aa(parent_dir.path.clone(), parent_dir, ".", total_size)
     }
 
     #[must_use]
-    pub fn new_aa_parent(path: PathBuf, parent_dir: &'dir Dir, total_size: bool) -> File<'dir> {
+    pub fn new_aa_parent(
+        path: PathBuf,
+        parent_dir: &'dir Dir,
+        total_size: TotalSize,
+    ) -> File<'dir> {
         File::new_aa(path, parent_dir, "..", total_size)
     }
 
+    /// Creates a ghost entry for a file in the given directory that Git
+    /// tracks, but that has been deleted from the working tree.
+    #[must_use]
+    pub fn new_ghost(path: PathBuf, parent_dir: &'dir Dir) -> File<'dir> {
+        let mut file = File::from_args(path, parent_dir, None, false, TotalSize::Off, None);
+        file.is_ghost = true;
+        file
+    }
+
     /// A file’s name is derived from its string. This needs to handle directories
     /// such as `/` or `..`, which have no `file_name` component. So instead, just
     /// use the last component as the name.
@@ -472,10 +492,13 @@ impl<'dir> File<'dir> {
                     metadata: OnceLock::from(Ok(metadata)),
                     name,
                     is_all_all: false,
+                    is_ghost: false,
                     deref_links: self.deref_links,
                     extended_attributes,
+                    entry_count: OnceLock::new(),
                     absolute_path: absolute_path_cell,
                     recursive_size: RecursiveSize::None,
+                    total_size: TotalSize::Off,
                 };
                 FileTarget::Ok(Box::new(file))
             }
@@ -647,38 +670,12 @@ impl<'dir> File<'dir> {
     /// listing.
     #[cfg(unix)]
     fn recursive_directory_size(&self) -> RecursiveSize {
-        if self.is_directory() {
-            let key = (
-                self.metadata().map_or(0, MetadataExt::dev),
-                self.metadata().map_or(0, MetadataExt::ino),
-            );
-            if let Some(size) = DIRECTORY_SIZE_CACHE.lock().unwrap().get(&key) {
-                return RecursiveSize::Some(size.0, size.1);
-            }
-            Dir::read_dir(self.path.clone()).map_or(RecursiveSize::Unknown, |dir| {
-                let mut size = 0;
-                let mut blocks = 0;
-                for file in dir.files(super::DotFilter::Dotfiles, None, false, false, true) {
-                    match file.recursive_directory_size() {
-                        RecursiveSize::Some(bytes, blks) => {
-                            size += bytes;
-                            blocks += blks;
-                        }
-                        RecursiveSize::Unknown => {}
-                        RecursiveSize::None => {
-                            size += file.metadata().map_or(0, MetadataExt::size);
-                            blocks += file.metadata().map_or(0, MetadataExt::blocks);
-                        }
-                    }
-                }
-                DIRECTORY_SIZE_CACHE
-                    .lock()
-                    .unwrap()
-                    .insert(key, (size, blocks));
-                RecursiveSize::Some(size, blocks)
-            })
-        } else {
-            RecursiveSize::None
+        if !self.is_directory() {
+            return RecursiveSize::None;
+        }
+        match self.metadata() {
+            Ok(metadata) => recursive_size::directory_size(&self.path, metadata, self.total_size),
+            Err(_) => RecursiveSize::Unknown,
         }
     }
 
@@ -699,6 +696,31 @@ impl<'dir> File<'dir> {
             .unwrap_bytes_or(self.metadata().map_or(0, std::fs::Metadata::len))
     }
 
+    /// The recursive size of this directory, as computed for `total_size`.
+    #[inline]
+    pub fn recursive_size(&self) -> RecursiveSize {
+        self.recursive_size
+    }
+
+    /// How recursive directory sizes are computed for this file and the
+    /// files inside it.
+    #[inline]
+    pub fn total_size(&self) -> TotalSize {
+        self.total_size
+    }
+
+    /// The number of entries in this directory, counted by `count` the first
+    /// time this is called and cached after that.
+    pub(crate) fn entry_count_with(&self, count: impl FnOnce() -> Option<u64>) -> Option<u64> {
+        *self.entry_count.get_or_init(count)
+    }
+
+    /// The number of entries in this directory, if they’ve been counted.
+    #[must_use]
+    pub fn counted_entries(&self) -> Option<u64> {
+        self.entry_count.get().copied().flatten()
+    }
+
     /// Is the file is using recursive size calculation
     #[inline]
     pub fn is_recursive_size(&self) -> bool {
@@ -759,7 +781,13 @@ impl<'dir> File<'dir> {
         match Dir::read_dir(self.path.clone()) {
             // . & .. are skipped, if the returned iterator has .next(), it's not empty
             Ok(has_files) => has_files
-                .files(super::DotFilter::Dotfiles, None, false, false, false)
+                .files(
+                    super::DotFilter::Dotfiles,
+                    None,
+                    false,
+                    false,
+                    TotalSize::Off,
+                )
                 .next()
                 .is_none(),
             Err(_) => false,
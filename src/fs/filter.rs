@@ -7,12 +7,27 @@
 //! Filtering and sorting the list of files before displaying them.
 
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::iter::FromIterator;
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::NaiveDateTime;
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder, gitconfig_excludes_path};
+use log::{debug, warn};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 use crate::fs::DotFilter;
 use crate::fs::File;
+use crate::fs::dir::Dir;
+use crate::fs::dir_action::RecurseOptions;
+use crate::fs::feature::git::GitCache;
+use crate::fs::fields::GitStatus;
+use crate::fs::recursive_size::TotalSize;
+use crate::output::table::TimeType;
 
 /// Flags used to manage the **file filter** process
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -41,6 +56,10 @@ pub enum FileFilterFlags {
     /// Whether directories should be listed as the last items, after other
     /// types of file. Some users prefer it like this.
     ListDirsLast,
+
+    /// Whether to hide directories that contain no files matching the
+    /// include patterns when recursing.
+    PruneDirs,
 }
 
 /// The **file filter** processes a list of files before displaying them to
@@ -55,12 +74,13 @@ pub enum FileFilterFlags {
 /// need to be inserted into the list, in a special case.
 ///
 /// The filter also governs sorting the list. After being filtered, pairs of
-/// files are compared and sorted based on the result, with the sort field
-/// performing the comparison.
+/// files are compared and sorted based on the result, with the sort keys
+/// performing the comparison in turn.
 #[derive(PartialEq, Eq, Debug, Clone)]
 pub struct FileFilter {
-    /// The metadata field to sort by.
-    pub sort_field: SortField,
+    /// The metadata fields to sort by. Files that compare equal by the first
+    /// key are ordered by the second, and so on.
+    pub sort_keys: Vec<SortKey>,
 
     // Flags that the file filtering process follow
     pub flags: Vec<FileFilterFlags>,
@@ -82,24 +102,122 @@ pub struct FileFilter {
     /// patterns won’t be displayed in the list.
     pub ignore_patterns: IgnorePatterns,
 
+    /// Glob patterns to include. When there are any, only files with a
+    /// name that matches *one* of these patterns will be displayed, with
+    /// directories being kept when recursing so their contents can be seen.
+    pub only_patterns: IgnorePatterns,
+
     /// Whether to ignore Git-ignored patterns.
     pub git_ignore: GitIgnore,
 
+    /// Whether to only show files with pending Git changes.
+    pub git_filter: GitFilter,
+
+    /// Bounds on the size and timestamp of the files to display.
+    pub limits: MetadataLimits,
+
     /// Whether to ignore symlinks
     pub no_symlinks: bool,
 
     /// Whether to explicitly show symlinks
     pub show_symlinks: bool,
+
+    /// What to count inside directories for the entries column and sort
+    /// field.
+    pub entry_count: EntryCount,
+
+    /// What has been found out about the directories filtered so far.
+    pub cache: FilterCache,
+}
+
+/// How far a listing goes into the directories among the files being
+/// filtered.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Recursion {
+    /// Their contents aren’t listed.
+    Off,
+
+    /// Their contents are listed to this many levels deep, or all the way
+    /// down if there’s no limit.
+    Levels(Option<usize>),
+}
+
+impl Recursion {
+    /// How far the given recursion options go into directories at the given
+    /// depth, which is the one they get checked against `--level` with.
+    #[must_use]
+    pub fn at(recurse: Option<RecurseOptions>, depth: usize) -> Self {
+        match recurse {
+            None => Self::Off,
+            Some(r) => Self::Levels(r.max_depth.map(|max| max.saturating_sub(depth))),
+        }
+    }
+}
+
+/// What the filter has worked out about the directories it’s looked at,
+/// so it doesn’t have to read them again. This isn’t part of the filter’s
+/// settings, so it’s left out when comparing filters, and a cloned filter
+/// starts out with an empty one.
+#[derive(Default)]
+pub struct FilterCache {
+    /// Whether a directory, searched to the given number of levels, has
+    /// anything inside it that the include patterns would show.
+    included_descendants: Mutex<HashMap<(PathBuf, Option<usize>), bool>>,
+
+    /// The ignore files read for `--git-ignore`.
+    ignore_files: Mutex<IgnoreCache>,
+}
+
+impl PartialEq for FilterCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for FilterCache {}
+
+impl Clone for FilterCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl std::fmt::Debug for FilterCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilterCache").finish_non_exhaustive()
+    }
 }
 
 impl FileFilter {
     /// Remove every file in the given vector that does *not* pass the
     /// filter predicate for files found inside a directory.
     #[rustfmt::skip]
-    pub fn filter_child_files(&self, is_recurse: bool, files: &mut Vec<File<'_>>) {
+    pub fn filter_child_files(&self, recurse: Recursion, files: &mut Vec<File<'_>>, git: Option<&GitCache>) {
         use FileFilterFlags::{NoSymlinks, OnlyDirs, OnlyFiles, ShowSymlinks};
+        let is_recurse = recurse != Recursion::Off;
 
         files.retain(|f| !self.ignore_patterns.is_ignored(&f.name));
+        files.retain(|f| self.limits.allows(f));
+
+        if self.git_ignore == GitIgnore::CheckAndIgnore {
+            files.retain(|f| !IgnoreFiles::is_ignored(f, &self.cache.ignore_files));
+        }
+
+        if self.git_filter != GitFilter::Off {
+            files.retain(|f| self.git_filter.allows(f, git));
+        }
+
+        if !self.only_patterns.is_empty() {
+            let prune = self.flags.contains(&FileFilterFlags::PruneDirs);
+            files.retain(|f| {
+                if let Recursion::Levels(levels) = recurse && f.is_directory() {
+                    !prune || self.has_included_descendant(&f.path, levels)
+                } else {
+                    self.only_patterns.matches(&f.name)
+                }
+            });
+        }
+
         files.retain(|f| {
             match (
                 self.flags.contains(&OnlyDirs),
@@ -130,14 +248,110 @@ impl FileFilter {
     /// from the glob, even though the globbing is done by the shell!
     pub fn filter_argument_files(&self, files: &mut Vec<File<'_>>) {
         files.retain(|f| !self.ignore_patterns.is_ignored(&f.name));
+        files.retain(|f| self.limits.allows(f));
+        files.retain(|f| {
+            self.only_patterns.is_empty() || f.is_directory() || self.only_patterns.matches(&f.name)
+        });
+    }
+
+    /// Whether anything inside the given directory, down to the given
+    /// number of levels, would be shown by the include patterns. Hidden and
+    /// ignored files are skipped, and symlinks to directories aren’t
+    /// followed. The answer is cached, as the same directories get asked
+    /// about again when their own contents are listed.
+    fn has_included_descendant(&self, path: &Path, levels: Option<usize>) -> bool {
+        if levels == Some(0) {
+            return false;
+        }
+
+        let key = (path.to_path_buf(), levels);
+        if let Some(&found) = self.cache.included_descendants.lock().unwrap().get(&key) {
+            return found;
+        }
+
+        let found = std::fs::read_dir(path).is_ok_and(|entries| {
+            entries.flatten().any(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+
+                if (self.dot_filter == DotFilter::JustFiles && name.starts_with('.'))
+                    || self.ignore_patterns.is_ignored(&name)
+                {
+                    false
+                } else if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    self.has_included_descendant(&entry.path(), levels.map(|l| l - 1))
+                } else {
+                    self.only_patterns.matches(&name)
+                }
+            })
+        });
+
+        self.cache
+            .included_descendants
+            .lock()
+            .unwrap()
+            .insert(key, found);
+        found
+    }
+
+    /// The number of entries in the given directory that this filter would
+    /// list, or `None` if it isn’t a directory or can’t be read. With
+    /// `EntryCount::Recursive`, this is the number of files at any depth
+    /// beneath it instead. The count is cached in the file.
+    pub fn count_entries(&self, file: &File<'_>, git: Option<&GitCache>) -> Option<u64> {
+        file.entry_count_with(|| {
+            if file.is_all_all || !file.is_directory() {
+                return None;
+            }
+            let dir = file.read_dir().ok()?;
+            Some(self.count_dir_entries(&dir, git))
+        })
+    }
+
+    fn count_dir_entries(&self, dir: &Dir, git: Option<&GitCache>) -> u64 {
+        let git_ignoring = self.git_ignore == GitIgnore::CheckAndIgnore;
+        let mut files = dir
+            .files(self.dot_filter, git, git_ignoring, false, TotalSize::Off)
+            .filter(|f| !f.is_all_all)
+            .collect::<Vec<_>>();
+
+        match self.entry_count {
+            EntryCount::Direct => {
+                self.filter_child_files(Recursion::Off, &mut files, git);
+                files.len() as u64
+            }
+            EntryCount::Recursive => {
+                self.filter_child_files(Recursion::Levels(None), &mut files, git);
+                files
+                    .par_iter()
+                    .map(|f| {
+                        if f.is_directory() {
+                            f.read_dir()
+                                .map_or(0, |dir| self.count_dir_entries(&dir, git))
+                        } else {
+                            1
+                        }
+                    })
+                    .sum()
+            }
+        }
     }
 
     /// Sort the files in the given vector based on the sort field option.
-    pub fn sort_files<'a, F>(&self, files: &mut [F])
+    /// The Git cache is used when sorting by Git status.
+    pub fn sort_files<'a, F>(&self, files: &mut [F], git: Option<&GitCache>)
     where
         F: AsRef<File<'a>>,
     {
-        files.sort_by(|a, b| self.sort_field.compare_files(a.as_ref(), b.as_ref()));
+        // The entries sort field compares the counts cached in each file,
+        // so they have to be counted first.
+        if self.sort_keys.iter().any(|k| k.field == SortField::Entries) {
+            for file in files.iter() {
+                self.count_entries(file.as_ref(), git);
+            }
+        }
+
+        files.sort_by(|a, b| self.compare_files(a.as_ref(), b.as_ref(), git));
 
         if self.flags.contains(&FileFilterFlags::Reverse) {
             files.reverse();
@@ -159,6 +373,20 @@ impl FileFilter {
             });
         }
     }
+
+    /// Compares two files by each sort key in turn, until one of them tells
+    /// the files apart. Only the last key falls back to comparing names, so
+    /// the ones after it still get a say.
+    fn compare_files(&self, a: &File<'_>, b: &File<'_>, git: Option<&GitCache>) -> Ordering {
+        let Some((last, keys)) = self.sort_keys.split_last() else {
+            return Ordering::Equal;
+        };
+
+        keys.iter()
+            .map(|key| key.compare_files_strictly(a, b, git))
+            .find(|order| order.is_ne())
+            .unwrap_or_else(|| last.compare_files(a, b, git))
+    }
 }
 
 /// User-supplied field to sort by.
@@ -177,6 +405,10 @@ pub enum SortField {
     /// The file’s size, in bytes.
     Size,
 
+    /// The number of entries in a directory, as counted by
+    /// `FileFilter::count_entries`. Other files come first.
+    Entries,
+
     /// The file’s inode, which usually corresponds to the order in which
     /// files were created on the filesystem, more or less.
     #[cfg(unix)]
@@ -231,6 +463,246 @@ pub enum SortField {
     /// The file's name, however if the name of the file begins with `.`
     /// ignore the leading `.` and then sort as Name
     NameMixHidden(SortCase),
+
+    /// The file’s name compared as a version number, the way `ls -v` does,
+    /// so `v1.2.10` comes after `v1.2.9`, and `v1.2~rc1` before `v1.2`.
+    ///
+    /// This differs from the natural ordering of `Name` in how it treats
+    /// punctuation, file extensions, and the `~` pre-release marker.
+    Version,
+
+    /// The file’s Git status, with files that have pending changes coming
+    /// first: conflicted, then modified, renamed, deleted, new, and
+    /// untracked, followed by unchanged files and then ignored ones.
+    #[cfg(any(feature = "git", feature = "gix"))]
+    GitStatus,
+
+    /// The time of the last commit to have touched the file, with files that
+    /// have never been committed coming first.
+    #[cfg(feature = "git")]
+    CommitDate,
+
+    /// The name of the author of the last commit to have touched the file.
+    #[cfg(feature = "git")]
+    CommitAuthor,
+}
+
+/// Ranks a file by how interesting its Git status is, lowest first. A file’s
+/// rank is that of the more interesting of its staged and unstaged statuses,
+/// with an unstaged new file being untracked.
+#[cfg(any(feature = "git", feature = "gix"))]
+fn git_rank(file: &File<'_>, git: Option<&GitCache>) -> u8 {
+    let Some(git) = git else {
+        return 0;
+    };
+
+    let status = git.get(&file.path, file.is_directory());
+    if status.unstaged == GitStatus::Ignored {
+        return 8;
+    }
+
+    let rank = |status, staged| match status {
+        GitStatus::Conflicted => 0,
+        GitStatus::Modified => 1,
+        GitStatus::TypeChange => 2,
+        GitStatus::Renamed => 3,
+        GitStatus::Deleted => 4,
+        GitStatus::New if staged => 5,
+        GitStatus::New => 6,
+        GitStatus::NotModified => 7,
+        GitStatus::Ignored => 8,
+    };
+
+    rank(status.staged, true).min(rank(status.unstaged, false))
+}
+
+/// The time of the last commit to have touched a file, if it has one.
+#[cfg(feature = "git")]
+fn commit_time(file: &File<'_>, git: Option<&GitCache>) -> Option<NaiveDateTime> {
+    git?.last_commit(&file.path).map(|commit| commit.time)
+}
+
+/// Compares the authors of the last commits to have touched two files,
+/// ignoring case, with files that have never been committed coming first.
+#[cfg(feature = "git")]
+fn compare_commit_authors(a: &File<'_>, b: &File<'_>, git: Option<&GitCache>) -> Ordering {
+    let author = |file: &File<'_>| git?.last_commit(&file.path).map(|commit| commit.author);
+    match (author(a), author(b)) {
+        (Some(a), Some(b)) => natord::compare_ignore_case(&a, &b),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+/// Compares two file names as version numbers, following the rules of
+/// GNU’s `filevercmp` used by `ls -v`:
+///
+/// - `.` and `..` come first, then other names starting with a dot;
+/// - file extensions such as `.tar.gz` are only compared if the rest of the
+///   names are equal;
+/// - runs of digits are compared as numbers, and everything else character
+///   by character, with letters before other characters;
+/// - a `~` comes before anything, even the end of the name, so it can mark
+///   pre-releases.
+///
+/// Names that compare equal as versions, such as `a01` and `a1`, fall back
+/// to comparing their bytes.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_bytes, b_bytes) = (a.as_bytes(), b.as_bytes());
+
+    let order = match (a_bytes.first(), b_bytes.first()) {
+        (Some(b'.'), Some(b'.')) => match (a, b) {
+            (".", _) | (_, ".") | ("..", _) | (_, "..") => a_bytes.len().cmp(&b_bytes.len()),
+            _ => compare_version_names(&a_bytes[1..], &b_bytes[1..]),
+        },
+        (Some(b'.'), _) => Ordering::Less,
+        (_, Some(b'.')) => Ordering::Greater,
+        _ => compare_version_names(a_bytes, b_bytes),
+    };
+
+    order.then_with(|| a.cmp(b))
+}
+
+/// Compares the parts of two names before their file extensions, then the
+/// whole names if those are equal.
+fn compare_version_names(a: &[u8], b: &[u8]) -> Ordering {
+    let a_prefix = &a[..version_prefix_len(a)];
+    let b_prefix = &b[..version_prefix_len(b)];
+
+    match compare_version_strings(a_prefix, b_prefix) {
+        Ordering::Equal if a_prefix.len() != a.len() || b_prefix.len() != b.len() => {
+            compare_version_strings(a, b)
+        }
+        order => order,
+    }
+}
+
+/// The length of a name without its file extensions, which are the trailing
+/// runs of a `.` followed by a letter or `~`, then letters, digits, or `~`.
+fn version_prefix_len(s: &[u8]) -> usize {
+    let is_suffix_start = |c: u8| c.is_ascii_alphabetic() || c == b'~';
+    let is_suffix_char = |c: u8| c.is_ascii_alphanumeric() || c == b'~';
+
+    let mut prefix_len = 0;
+    let mut i = 0;
+    while i < s.len() {
+        i += 1;
+        prefix_len = i;
+        while i + 1 < s.len() && s[i] == b'.' && is_suffix_start(s[i + 1]) {
+            i += 2;
+            while i < s.len() && is_suffix_char(s[i]) {
+                i += 1;
+            }
+        }
+    }
+
+    prefix_len
+}
+
+/// Compares two strings as alternating runs of non-digits and digits.
+fn compare_version_strings(a: &[u8], b: &[u8]) -> Ordering {
+    // The sort weight of the character at the given position: the end of
+    // the string comes after a `~` but before everything else, and letters
+    // come before other non-digits.
+    fn weight(s: &[u8], pos: usize) -> i32 {
+        match s.get(pos) {
+            None => -1,
+            Some(c) if c.is_ascii_digit() => 0,
+            Some(c) if c.is_ascii_alphabetic() => i32::from(*c),
+            Some(b'~') => -2,
+            Some(c) => i32::from(*c) + 256,
+        }
+    }
+
+    let is_digit_at = |s: &[u8], pos: usize| s.get(pos).is_some_and(u8::is_ascii_digit);
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !is_digit_at(a, i)) || (j < b.len() && !is_digit_at(b, j)) {
+            let order = weight(a, i).cmp(&weight(b, j));
+            if order.is_ne() {
+                return order;
+            }
+            i += 1;
+            j += 1;
+        }
+
+        while a.get(i) == Some(&b'0') {
+            i += 1;
+        }
+        while b.get(j) == Some(&b'0') {
+            j += 1;
+        }
+
+        let mut first_diff = Ordering::Equal;
+        while is_digit_at(a, i) && is_digit_at(b, j) {
+            if first_diff.is_eq() {
+                first_diff = a[i].cmp(&b[j]);
+            }
+            i += 1;
+            j += 1;
+        }
+
+        if is_digit_at(a, i) {
+            return Ordering::Greater;
+        }
+        if is_digit_at(b, j) {
+            return Ordering::Less;
+        }
+        if first_diff.is_ne() {
+            return first_diff;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// One key of a compound sort, such as the `-size` in `--sort=type,-size`:
+/// a field to sort by, and whether to sort by it in descending order.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct SortKey {
+    /// The field to compare files by.
+    pub field: SortField,
+
+    /// Whether this key’s order is reversed. This is separate from the
+    /// `--reverse` flag, which reverses the whole list afterwards.
+    pub descending: bool,
+}
+
+impl SortKey {
+    /// Compares two files by this key’s field, in this key’s direction.
+    #[must_use]
+    pub fn compare_files(self, a: &File<'_>, b: &File<'_>, git: Option<&GitCache>) -> Ordering {
+        self.direct(self.field.compare_files(a, b, git))
+    }
+
+    /// Compares two files by this key’s field alone, in this key’s
+    /// direction.
+    #[must_use]
+    pub fn compare_files_strictly(
+        self,
+        a: &File<'_>,
+        b: &File<'_>,
+        git: Option<&GitCache>,
+    ) -> Ordering {
+        self.direct(self.field.compare_files_strictly(a, b, git))
+    }
+
+    fn direct(self, order: Ordering) -> Ordering {
+        if self.descending {
+            order.reverse()
+        } else {
+            order
+        }
+    }
+}
+
+impl From<SortField> for SortKey {
+    fn from(field: SortField) -> Self {
+        Self {
+            field,
+            descending: false,
+        }
+    }
 }
 
 /// Whether a field should be sorted case-sensitively or case-insensitively.
@@ -261,7 +733,8 @@ impl SortField {
     /// into groups between letters and numbers, and then sorts those blocks
     /// together, so `file10` will sort after `file9`, instead of before it
     /// because of the `1`.
-    pub fn compare_files(self, a: &File<'_>, b: &File<'_>) -> Ordering {
+    #[cfg_attr(not(any(feature = "git", feature = "gix")), allow(unused_variables))]
+    pub fn compare_files(self, a: &File<'_>, b: &File<'_>, git: Option<&GitCache>) -> Ordering {
         use self::SortCase::{ABCabc, AaBbCc};
 
         #[rustfmt::skip]
@@ -272,6 +745,7 @@ impl SortField {
             Self::Name(AaBbCc)  => natord::compare_ignore_case(&a.name, &b.name),
 
             Self::Size          => a.length().cmp(&b.length()),
+            Self::Entries       => a.counted_entries().cmp(&b.counted_entries()),
 
             #[cfg(unix)]
             Self::FileInode     => {
@@ -307,9 +781,48 @@ impl SortField {
                 Self::strip_dot(&a.name),
                 Self::strip_dot(&b.name)
             ),
+
+            Self::Version => compare_versions(&a.name, &b.name),
+
+            #[cfg(any(feature = "git", feature = "gix"))]
+            Self::GitStatus => match git_rank(a, git).cmp(&git_rank(b, git)) {
+                Ordering::Equal  => natord::compare_ignore_case(&a.name, &b.name),
+                order            => order,
+            },
+            #[cfg(feature = "git")]
+            Self::CommitDate => commit_time(a, git).cmp(&commit_time(b, git)),
+            #[cfg(feature = "git")]
+            Self::CommitAuthor => match compare_commit_authors(a, b, git) {
+                Ordering::Equal  => natord::compare_ignore_case(&a.name, &b.name),
+                order            => order,
+            },
         };
     }
 
+    /// Compares two files by this field alone. Unlike `compare_files`, files
+    /// of the same type or with the same extension compare as equal, rather
+    /// than falling back to comparing their names.
+    pub fn compare_files_strictly(
+        self,
+        a: &File<'_>,
+        b: &File<'_>,
+        git: Option<&GitCache>,
+    ) -> Ordering {
+        match self {
+            Self::FileType => a.type_char().cmp(&b.type_char()),
+            Self::Extension(_) => {
+                let left = if a.is_directory() { &None } else { &a.ext };
+                let right = if b.is_directory() { &None } else { &b.ext };
+                left.cmp(right)
+            }
+            #[cfg(any(feature = "git", feature = "gix"))]
+            Self::GitStatus => git_rank(a, git).cmp(&git_rank(b, git)),
+            #[cfg(feature = "git")]
+            Self::CommitAuthor => compare_commit_authors(a, b, git),
+            _ => self.compare_files(a, b, git),
+        }
+    }
+
     fn strip_dot(n: &str) -> &str {
         match n.strip_prefix('.') {
             Some(s) => s,
@@ -321,6 +834,9 @@ impl SortField {
 /// The **ignore patterns** are a list of globs that are tested against
 /// each filename, and if any of them match, that file isn’t displayed.
 /// This lets a user hide, say, text files by ignoring `*.txt`.
+///
+/// The same list is used for the include patterns of `--only-glob`, where a
+/// match means the file *is* displayed.
 #[derive(PartialEq, Eq, Default, Debug, Clone)]
 pub struct IgnorePatterns {
     patterns: Vec<glob::Pattern>,
@@ -373,22 +889,302 @@ impl IgnorePatterns {
         }
     }
 
+    /// Whether there are no patterns in the list.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
     /// Test whether the given file should be hidden from the results.
-    fn is_ignored(&self, file: &str) -> bool {
+    #[must_use]
+    pub fn is_ignored(&self, file: &str) -> bool {
+        self.matches(file)
+    }
+
+    /// Test whether any of the patterns match the given file name.
+    fn matches(&self, file: &str) -> bool {
         self.patterns.iter().any(|p| p.matches(file))
     }
 }
 
+/// The **metadata limits** hide files whose size or timestamp falls outside
+/// the given bounds, such as files smaller than a kilobyte, or ones that
+/// haven’t been modified in the last two days.
+///
+/// Directories are never hidden by these limits, so that the files inside
+/// them can still be reached when recursing or drawing a tree. Neither are
+/// the ghost entries for deleted files, which have no metadata to compare.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct MetadataLimits {
+    /// The smallest size, in bytes, of a file to display.
+    pub min_size: Option<u64>,
+
+    /// The largest size, in bytes, of a file to display.
+    pub max_size: Option<u64>,
+
+    /// Which of the file’s timestamps to compare against the time bounds.
+    pub time_type: TimeType,
+
+    /// Only files with a timestamp at or after this, in UTC, are displayed.
+    pub newer_than: Option<NaiveDateTime>,
+
+    /// Only files with a timestamp before this, in UTC, are displayed.
+    pub older_than: Option<NaiveDateTime>,
+}
+
+impl MetadataLimits {
+    /// Create a new set of limits that allows every file.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            min_size: None,
+            max_size: None,
+            time_type: TimeType::Modified,
+            newer_than: None,
+            older_than: None,
+        }
+    }
+
+    /// Test whether the given file is within the limits. Files without the
+    /// timestamp being compared are hidden when there is a time bound,
+    /// unless they’re ghost entries for files that have been deleted.
+    fn allows(&self, file: &File<'_>) -> bool {
+        if file.is_directory() || file.is_ghost {
+            return true;
+        }
+
+        if self.min_size.is_some() || self.max_size.is_some() {
+            let size = file.length();
+            if self.min_size.is_some_and(|min| size < min)
+                || self.max_size.is_some_and(|max| size > max)
+            {
+                return false;
+            }
+        }
+
+        if self.newer_than.is_some() || self.older_than.is_some() {
+            let Some(time) = self.time_type.get_corresponding_time(file) else {
+                return false;
+            };
+            if self.newer_than.is_some_and(|newer| time < newer)
+                || self.older_than.is_some_and(|older| time >= older)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
 /// Whether to ignore or display files that Git would ignore.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum GitIgnore {
-    /// Ignore files that Git would ignore.
+    /// Ignore files that Git, ripgrep, or fd would ignore.
     CheckAndIgnore,
 
     /// Display files, even if Git would ignore them.
     Off,
 }
 
+/// What to count inside each directory for the entries column.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum EntryCount {
+    /// The entries directly inside it.
+    #[default]
+    Direct,
+
+    /// The files at any depth beneath it, not counting the directories
+    /// along the way.
+    Recursive,
+}
+
+/// Whether to only display files that Git has something to say about.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum GitFilter {
+    /// Only display files that are new, modified, deleted, renamed, or
+    /// otherwise changed, either in the index or in the working tree.
+    Changed,
+
+    /// Only display files that Git isn’t tracking yet.
+    Untracked,
+
+    /// Display files no matter what their Git status is.
+    Off,
+}
+
+impl GitFilter {
+    /// Whether a file should be displayed. Directories use the combined
+    /// status of everything underneath them, so they are kept whenever they
+    /// contain a matching file. Files outside of any repository never match.
+    fn allows(self, file: &File<'_>, git: Option<&GitCache>) -> bool {
+        let Some(git) = git else {
+            return self == Self::Off;
+        };
+
+        let status = git.get(&file.path, file.is_directory() || file.is_ghost);
+        let changed = |status| !matches!(status, GitStatus::NotModified | GitStatus::Ignored);
+
+        match self {
+            Self::Changed => changed(status.staged) || changed(status.unstaged),
+            Self::Untracked => status.unstaged == GitStatus::New,
+            Self::Off => true,
+        }
+    }
+}
+
+/// The names of the per-directory ignore files, from the highest precedence
+/// to the lowest. These follow fd and ripgrep, where a `.ignore` file can
+/// override what a `.gitignore` file in the same directory says.
+const IGNORE_FILE_NAMES: [&str; 3] = [".fdignore", ".ignore", ".gitignore"];
+
+/// The ignore files that have been read so far, so each directory’s files are
+/// only read once, no matter how many children it and its subdirectories have.
+#[derive(Default)]
+struct IgnoreCache {
+    /// Maps the path of a listed directory to its canonical path, if it
+    /// has one.
+    canonical: HashMap<PathBuf, Option<PathBuf>>,
+
+    /// Maps a canonical directory path to the ignore files that apply to
+    /// its contents.
+    ignores: HashMap<PathBuf, Arc<IgnoreFiles>>,
+}
+
+/// The **ignore files** that apply to the contents of a directory: the
+/// `.fdignore`, `.ignore`, and `.gitignore` files in it and in each of its
+/// parents up to the root of the repository it’s in, followed by the
+/// repository’s `.git/info/exclude` file and the global ignore file.
+///
+/// Outside of a repository, only the `.fdignore` and `.ignore` files apply,
+/// all the way up to the root of the filesystem, the same as with fd and
+/// ripgrep. These are read from disk, so they work without the `git` feature.
+#[derive(Debug)]
+struct IgnoreFiles {
+    /// The matchers, in order of precedence: a matcher from a deeper
+    /// directory wins over one from its parent, so a `!negation` in a
+    /// nested file can re-include something ignored further up.
+    matchers: Vec<Arc<Gitignore>>,
+
+    /// Whether the directory is in a Git repository.
+    in_repo: bool,
+}
+
+impl IgnoreFiles {
+    /// Whether the given file is ignored by the ignore files of the
+    /// directory it’s in. A repository’s `.git` directory is always ignored,
+    /// so `eza --tree --all --git-ignore` doesn’t display the files in it.
+    fn is_ignored(file: &File<'_>, cache: &Mutex<IgnoreCache>) -> bool {
+        if file.name == ".git" {
+            return true;
+        }
+
+        let Some(dir) = file.path.parent() else {
+            return false;
+        };
+
+        let mut cache = cache.lock().unwrap();
+        let canonical = cache.canonical.entry(dir.to_path_buf()).or_insert_with(|| {
+            let dir = if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir
+            };
+            dir.canonicalize().ok()
+        });
+
+        let Some(canonical) = canonical.clone() else {
+            return false;
+        };
+
+        let ignores = Self::for_dir(&canonical, &mut cache.ignores);
+        ignores.matches(&canonical.join(&file.name), file.is_directory())
+    }
+
+    /// Tests the path against each matcher in turn, with the first one to
+    /// either ignore or whitelist it having the final say. A path inside an
+    /// ignored directory is ignored too, even when the directory itself
+    /// isn’t being listed.
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        for matcher in &self.matchers {
+            let Ok(relative) = path.strip_prefix(matcher.path()) else {
+                continue;
+            };
+
+            match matcher.matched_path_or_any_parents(relative, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+
+        false
+    }
+
+    /// Collects the ignore files for the given canonical directory path:
+    /// its own, followed by those of its parent, up to the root of the
+    /// repository or filesystem.
+    fn for_dir(dir: &Path, cache: &mut HashMap<PathBuf, Arc<Self>>) -> Arc<Self> {
+        if let Some(ignores) = cache.get(dir) {
+            return Arc::clone(ignores);
+        }
+
+        let git_dir = dir.join(".git");
+        let is_repo_root = git_dir.exists();
+        let parent = match dir.parent() {
+            Some(parent) if !is_repo_root => Some(Self::for_dir(parent, cache)),
+            _ => None,
+        };
+        let in_repo = is_repo_root || parent.as_ref().is_some_and(|p| p.in_repo);
+
+        let mut matchers = IGNORE_FILE_NAMES
+            .iter()
+            .filter(|name| in_repo || **name != ".gitignore")
+            .filter_map(|name| Self::read(dir, &dir.join(name)))
+            .collect::<Vec<_>>();
+
+        if let Some(parent) = parent {
+            matchers.extend(parent.matchers.iter().cloned());
+        } else if is_repo_root {
+            let exclude = git_dir.join("info").join("exclude");
+            matchers.extend(Self::read(dir, &exclude));
+
+            // The user’s global ignore file, named by Git’s
+            // `core.excludesFile` setting or defaulting to
+            // `$XDG_CONFIG_HOME/git/ignore`, applies from the top.
+            if let Some(global) = gitconfig_excludes_path() {
+                matchers.extend(Self::read(dir, &global));
+            }
+        }
+
+        let ignores = Arc::new(Self { matchers, in_repo });
+        cache.insert(dir.to_path_buf(), Arc::clone(&ignores));
+        ignores
+    }
+
+    /// Reads a single ignore file, whose patterns are relative to `root`.
+    fn read(root: &Path, path: &Path) -> Option<Arc<Gitignore>> {
+        if !path.is_file() {
+            return None;
+        }
+
+        debug!("Reading ignore file {}", path.display());
+        let mut builder = GitignoreBuilder::new(root);
+        if let Some(e) = builder.add(path) {
+            warn!("Error reading ignore file {}: {e}", path.display());
+        }
+
+        match builder.build() {
+            Ok(matcher) if !matcher.is_empty() => Some(Arc::new(matcher)),
+            Ok(_) => None,
+            Err(e) => {
+                warn!("Error reading ignore file {}: {e}", path.display());
+                None
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test_ignores {
     use super::*;
@@ -424,3 +1220,236 @@ mod test_ignores {
         assert!(pats.is_ignored("test.mp3"));
     }
 }
+
+#[cfg(test)]
+mod test_versions {
+    use super::*;
+
+    fn sorted(names: &[&'static str]) -> Vec<&'static str> {
+        let mut names = names.to_vec();
+        names.sort_by(|a, b| compare_versions(a, b));
+        names
+    }
+
+    #[test]
+    fn numbers_compare_as_numbers() {
+        assert_eq!(
+            sorted(&["v1.2.10", "v1.2.9", "v1.10.0", "v1.2.0"]),
+            vec!["v1.2.0", "v1.2.9", "v1.2.10", "v1.10.0"]
+        );
+    }
+
+    #[test]
+    fn tilde_marks_pre_releases() {
+        assert_eq!(
+            sorted(&["1.2", "1.2~rc1", "1.2-1", "1.2~beta"]),
+            vec!["1.2~beta", "1.2~rc1", "1.2", "1.2-1"]
+        );
+    }
+
+    #[test]
+    fn extensions_compare_last() {
+        assert_eq!(
+            sorted(&["foo-1.10.tar.gz", "foo-1.9.tar.gz", "foo-1.9.zip"]),
+            vec!["foo-1.9.tar.gz", "foo-1.9.zip", "foo-1.10.tar.gz"]
+        );
+    }
+
+    #[test]
+    fn dotfiles_come_first() {
+        assert_eq!(
+            sorted(&["a", ".b", "..", ".", ".a10", ".a9"]),
+            vec![".", "..", ".a9", ".a10", ".b", "a"]
+        );
+    }
+
+    #[test]
+    fn leading_zeroes_fall_back_to_bytes() {
+        assert_eq!(sorted(&["a1", "a01", "a001"]), vec!["a001", "a01", "a1"]);
+    }
+}
+
+#[cfg(test)]
+mod test_ignore_files {
+    use super::*;
+    use crate::fs::test::TestDir;
+    use crate::options::parser::test::mock_cli;
+
+    #[test]
+    fn nested_files_take_precedence() {
+        let root = TestDir::create();
+        let sub = root.join("sub");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::create_dir_all(root.join(".git").join("info")).unwrap();
+        std::fs::write(root.join(".gitignore"), "*.log\ntarget/\n").unwrap();
+        std::fs::write(root.join(".git").join("info").join("exclude"), "*.tmp\n").unwrap();
+        std::fs::write(sub.join(".ignore"), "!keep.log\n").unwrap();
+
+        let mut cache = HashMap::new();
+        let top = IgnoreFiles::for_dir(&root, &mut cache);
+        let nested = IgnoreFiles::for_dir(&sub, &mut cache);
+
+        assert!(top.matches(&root.join("debug.log"), false));
+        assert!(top.matches(&root.join("target"), true));
+        assert!(!top.matches(&root.join("target"), false));
+        assert!(top.matches(&root.join("target").join("debug"), false));
+        assert!(top.matches(&root.join("scratch.tmp"), false));
+        assert!(!top.matches(&root.join("main.rs"), false));
+
+        assert!(nested.matches(&sub.join("other.log"), false));
+        assert!(!nested.matches(&sub.join("keep.log"), false));
+        assert!(nested.matches(&sub.join("scratch.tmp"), false));
+    }
+
+    #[test]
+    fn gitignore_files_only_apply_in_repositories() {
+        let root = TestDir::create();
+        let repo = root.join("repo");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(root.join(".ignore"), "*.tmp\n").unwrap();
+        std::fs::write(repo.join(".gitignore"), "*.bak\n").unwrap();
+
+        let mut cache = HashMap::new();
+        let outside = IgnoreFiles::for_dir(&root, &mut cache);
+        let inside = IgnoreFiles::for_dir(&repo, &mut cache);
+
+        assert!(!outside.matches(&root.join("debug.log"), false));
+        assert!(outside.matches(&root.join("scratch.tmp"), false));
+
+        assert!(inside.matches(&repo.join("old.bak"), false));
+        assert!(!inside.matches(&repo.join("debug.log"), false));
+        assert!(!inside.matches(&repo.join("scratch.tmp"), false));
+    }
+
+    #[test]
+    fn listing_inside_an_ignored_directory() {
+        let root = TestDir::create();
+        let debug = root.join("target").join("debug");
+        std::fs::create_dir_all(&debug).unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(debug.join("out"), "").unwrap();
+
+        let filter = FileFilter::deduce(&mock_cli(vec!["--git-ignore"]), false).unwrap();
+        let dir = Dir::read_dir(debug.clone()).unwrap();
+        let mut files = dir
+            .files(filter.dot_filter, None, false, false, TotalSize::Off)
+            .collect::<Vec<_>>();
+        filter.filter_child_files(Recursion::Off, &mut files, None);
+        assert!(files.is_empty());
+
+        // The ignore files are kept with the filter that read them.
+        let cache = filter.cache.ignore_files.lock().unwrap();
+        assert!(cache.ignores.contains_key(&debug));
+    }
+}
+
+#[cfg(test)]
+mod test_limits {
+    use super::*;
+    use crate::fs::test::TestDir;
+
+    #[test]
+    fn ghosts_are_always_allowed() {
+        let root = TestDir::create();
+        std::fs::write(root.join("empty"), "").unwrap();
+        let dir = Dir::read_dir(root.to_path_buf()).unwrap();
+
+        let limits = MetadataLimits {
+            min_size: Some(1),
+            newer_than: Some(chrono::DateTime::UNIX_EPOCH.naive_utc()),
+            ..MetadataLimits::empty()
+        };
+        let file = File::from_args(root.join("empty"), &dir, None, false, TotalSize::Off, None);
+        assert!(!limits.allows(&file));
+        assert!(limits.allows(&File::new_ghost(root.join("deleted"), &dir)));
+    }
+}
+
+#[cfg(test)]
+mod test_entry_counts {
+    use super::*;
+    use crate::fs::test::TestDir;
+
+    fn filter(dot_filter: DotFilter, ignores: &[&str], entry_count: EntryCount) -> FileFilter {
+        FileFilter {
+            sort_keys: vec![SortField::default().into()],
+            flags: vec![],
+            dot_filter,
+            ignore_patterns: IgnorePatterns::parse_from_iter(ignores.iter().copied()).0,
+            only_patterns: IgnorePatterns::empty(),
+            git_ignore: GitIgnore::Off,
+            git_filter: GitFilter::Off,
+            limits: MetadataLimits::empty(),
+            no_symlinks: false,
+            show_symlinks: false,
+            entry_count,
+            cache: FilterCache::default(),
+        }
+    }
+
+    #[test]
+    fn counts_respect_dotfiles_and_ignores() {
+        let root = TestDir::create();
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        for name in ["one", "two.log", ".hidden", "sub/three", "sub/four.log"] {
+            std::fs::write(root.join(name), "").unwrap();
+        }
+
+        let count = |filter: FileFilter| {
+            let file = File::from_args(root.to_path_buf(), None, None, false, TotalSize::Off, None);
+            filter.count_entries(&file, None)
+        };
+
+        use EntryCount::{Direct, Recursive};
+        assert_eq!(count(filter(DotFilter::JustFiles, &[], Direct)), Some(3));
+        assert_eq!(count(filter(DotFilter::Dotfiles, &[], Direct)), Some(4));
+        assert_eq!(
+            count(filter(DotFilter::JustFiles, &["*.log"], Direct)),
+            Some(2)
+        );
+        assert_eq!(count(filter(DotFilter::JustFiles, &[], Recursive)), Some(4));
+        assert_eq!(
+            count(filter(DotFilter::Dotfiles, &["*.log"], Recursive)),
+            Some(3)
+        );
+
+        let file = File::from_args(root.join("one"), None, None, false, TotalSize::Off, None);
+        assert_eq!(
+            filter(DotFilter::JustFiles, &[], Direct).count_entries(&file, None),
+            None
+        );
+    }
+
+    #[test]
+    fn pruning_only_searches_the_listed_levels() {
+        let root = TestDir::create();
+        std::fs::create_dir_all(root.join("deep").join("deeper")).unwrap();
+        std::fs::create_dir_all(root.join("empty")).unwrap();
+        std::fs::write(root.join("deep").join("deeper").join("lib.rs"), "").unwrap();
+        std::fs::write(root.join("empty").join("notes.txt"), "").unwrap();
+
+        let mut filter = filter(DotFilter::JustFiles, &[], EntryCount::Direct);
+        filter.only_patterns = IgnorePatterns::parse_from_iter(["*.rs"]).0;
+        filter.flags.push(FileFilterFlags::PruneDirs);
+
+        let kept = |recurse| {
+            let dir = Dir::read_dir(root.to_path_buf()).unwrap();
+            let mut files = dir
+                .files(DotFilter::JustFiles, None, false, false, TotalSize::Off)
+                .collect::<Vec<_>>();
+            filter.filter_child_files(recurse, &mut files, None);
+            files.into_iter().map(|f| f.name).collect::<Vec<_>>()
+        };
+
+        assert_eq!(kept(Recursion::Levels(None)), vec!["deep"]);
+        assert_eq!(kept(Recursion::Levels(Some(2))), vec!["deep"]);
+        assert!(kept(Recursion::Levels(Some(1))).is_empty());
+
+        // The directories that were searched are remembered.
+        let deeper = (root.join("deep").join("deeper"), Some(1));
+        let cache = &filter.cache.included_descendants;
+        assert_eq!(cache.lock().unwrap().get(&deeper), Some(&true));
+    }
+}
@@ -16,3 +16,44 @@ pub mod fields;
 pub mod filter;
 pub mod mounts;
 pub mod recursive_size;
+
+#[cfg(test)]
+pub mod test {
+    use std::ops::Deref;
+    use std::path::{Path, PathBuf};
+
+    use tempfile::TempDir;
+
+    /// A directory for a test to create files in, which gets removed when
+    /// it’s dropped, even if the test fails. It dereferences to its
+    /// canonical path, so it can be compared with paths that get resolved.
+    pub struct TestDir {
+        _dir: TempDir,
+        path: PathBuf,
+    }
+
+    impl TestDir {
+        /// Creates a new, empty directory under the system’s temporary one.
+        pub fn create() -> Self {
+            Self::create_in(&std::env::temp_dir())
+        }
+
+        /// Creates a new, empty directory under the given one.
+        pub fn create_in(parent: &Path) -> Self {
+            let dir = tempfile::Builder::new()
+                .prefix("eza-")
+                .tempdir_in(parent)
+                .unwrap();
+            let path = dir.path().canonicalize().unwrap();
+            Self { _dir: dir, path }
+        }
+    }
+
+    impl Deref for TestDir {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.path
+        }
+    }
+}
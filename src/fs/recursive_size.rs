@@ -4,6 +4,23 @@
 // SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
+#[cfg(unix)]
+use std::collections::HashMap;
+#[cfg(unix)]
+use std::fs::Metadata;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+#[cfg(unix)]
+use std::path::Path;
+#[cfg(unix)]
+use std::sync::{LazyLock, Mutex};
+
+#[cfg(unix)]
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+#[cfg(unix)]
+use super::mounts::all_mounts;
+
 /// Used to represent a the size of a recursive directory traversal.  `None`
 /// should be used when the file does not represent a directory or the recursive
 /// size should not be calculated.
@@ -13,8 +30,8 @@ pub enum RecursiveSize {
     None,
     /// Size should be computed but has not been computed yet
     Unknown,
-    /// Size has been computed.  First field is size in bytes and second field
-    /// is size in blocks
+    /// Size has been computed.  First field is the apparent size in bytes and
+    /// second field is the allocated size in 512-byte blocks
     #[cfg_attr(target_family = "windows", allow(dead_code))]
     Some(u64, u64),
 }
@@ -86,3 +103,170 @@ impl RecursiveSize {
         }
     }
 }
+
+/// Whether to compute the recursive size of directories, and whether that
+/// should stop at the edges of the filesystem each directory is on.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Default)]
+pub enum TotalSize {
+    /// Directories get no size of their own.
+    #[default]
+    Off,
+
+    /// Add up everything beneath each directory.
+    All,
+
+    /// Add up everything beneath each directory, but don’t descend into
+    /// other mounted filesystems.
+    OneFileSystem,
+}
+
+impl TotalSize {
+    /// Whether recursive sizes should be computed at all.
+    #[inline]
+    #[must_use]
+    pub const fn is_on(self) -> bool {
+        !matches!(self, Self::Off)
+    }
+}
+
+// Maps (device_id, inode) => (size_in_bytes, size_in_blocks)
+// Mutex::new is const but HashMap::new is not const requiring us to use lazy
+// initialization.
+#[allow(clippy::type_complexity)]
+#[cfg(unix)]
+static DIRECTORY_SIZE_CACHE: LazyLock<Mutex<HashMap<(u64, u64), (u64, u64)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Calculate the total size of everything beneath the directory at `path`,
+/// or `Unknown` if it can’t be read. Subdirectories are walked in parallel,
+/// a file with several hard links is only counted once, and the size of
+/// every directory met on the way is cached for recursive listings.
+#[cfg(unix)]
+pub(super) fn directory_size(
+    path: &Path,
+    metadata: &Metadata,
+    total_size: TotalSize,
+) -> RecursiveSize {
+    let key = (metadata.dev(), metadata.ino());
+    if let Some(&(bytes, blocks)) = DIRECTORY_SIZE_CACHE.lock().unwrap().get(&key) {
+        return RecursiveSize::Some(bytes, blocks);
+    }
+
+    // Mount points are looked up by their canonical path, and as symbolic
+    // links aren’t followed, every path below this one stays canonical.
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let boundary = (total_size == TotalSize::OneFileSystem).then_some(metadata.dev());
+
+    walk(&path, key, boundary).map_or(RecursiveSize::Unknown, |usage| {
+        RecursiveSize::Some(usage.bytes, usage.blocks)
+    })
+}
+
+/// The space taken up by part of a directory tree.
+#[cfg(unix)]
+#[derive(Default)]
+struct Usage {
+    bytes: u64,
+    blocks: u64,
+
+    /// The sizes of the files with more than one hard link that have been
+    /// counted, so that other links to them don’t get counted again.
+    linked: HashMap<(u64, u64), (u64, u64)>,
+}
+
+#[cfg(unix)]
+impl Usage {
+    fn of_file(metadata: &Metadata) -> Self {
+        let mut usage = Self {
+            bytes: metadata.size(),
+            blocks: metadata.blocks(),
+            linked: HashMap::new(),
+        };
+        if metadata.nlink() > 1 {
+            let key = (metadata.dev(), metadata.ino());
+            usage.linked.insert(key, (usage.bytes, usage.blocks));
+        }
+        usage
+    }
+
+    /// Combine the usage of two separate parts of a tree, taking off the
+    /// size of any file that both of them have counted.
+    fn merge(mut self, mut other: Self) -> Self {
+        if self.linked.len() < other.linked.len() {
+            std::mem::swap(&mut self, &mut other);
+        }
+
+        self.bytes += other.bytes;
+        self.blocks += other.blocks;
+        for (key, (bytes, blocks)) in other.linked {
+            if self.linked.insert(key, (bytes, blocks)).is_some() {
+                self.bytes -= bytes;
+                self.blocks -= blocks;
+            }
+        }
+        self
+    }
+}
+
+/// Walk the directory at `path`, whose device and inode are `key`. When
+/// there’s a `boundary` device, directories on other devices, or that have
+/// something else mounted on them, are left out.
+#[cfg(unix)]
+fn walk(path: &Path, key: (u64, u64), boundary: Option<u64>) -> Option<Usage> {
+    let entries = std::fs::read_dir(path)
+        .ok()?
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+
+    let usage = entries
+        .par_iter()
+        .filter_map(|entry| {
+            // This doesn’t follow symbolic links, which count as themselves.
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_dir() {
+                return Some(Usage::of_file(&metadata));
+            }
+
+            let path = entry.path();
+            if boundary.is_some_and(|dev| metadata.dev() != dev || all_mounts().contains_key(&path))
+            {
+                return None;
+            }
+            walk(&path, (metadata.dev(), metadata.ino()), boundary)
+        })
+        .reduce(Usage::default, Usage::merge);
+
+    DIRECTORY_SIZE_CACHE
+        .lock()
+        .unwrap()
+        .insert(key, (usage.bytes, usage.blocks));
+    Some(usage)
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use crate::fs::test::TestDir;
+
+    #[test]
+    fn hard_links_are_counted_once() {
+        let root = TestDir::create();
+        std::fs::create_dir_all(root.join("a")).unwrap();
+        std::fs::create_dir_all(root.join("b")).unwrap();
+        std::fs::write(root.join("a").join("data"), [0; 1000]).unwrap();
+        std::fs::hard_link(root.join("a").join("data"), root.join("b").join("data")).unwrap();
+        std::fs::hard_link(root.join("a").join("data"), root.join("b").join("copy")).unwrap();
+        std::fs::write(root.join("b").join("other"), [0; 10]).unwrap();
+
+        let size = |path: &Path| {
+            let metadata = std::fs::metadata(path).unwrap();
+            directory_size(path, &metadata, TotalSize::All).unwrap_bytes_or(0)
+        };
+
+        // Each directory on its own counts the file once, and so does the
+        // directory holding both of them.
+        assert_eq!(size(&root.join("a")), 1000);
+        assert_eq!(size(&root.join("b")), 1010);
+        assert_eq!(size(&root), 1010);
+    }
+}
@@ -12,18 +12,22 @@
 use std::env;
 use std::ffi::{OsStr, OsString};
 use std::io::{self, ErrorKind, IsTerminal, Read, Write, stdin};
-use std::path::{Component, PathBuf};
+use std::path::PathBuf;
 use std::process::exit;
+use std::sync::Mutex;
 
+use clap::ArgMatches;
 use nu_ansi_term::{AnsiStrings as ANSIStrings, Style};
 use options::parser::get_command;
 
 use crate::fs::feature::git::GitCache;
-use crate::fs::filter::{FileFilterFlags::OnlyFiles, GitIgnore};
+use crate::fs::filter::{FileFilterFlags::OnlyFiles, GitIgnore, Recursion};
 use crate::fs::{Dir, File};
+use crate::options::config::Config;
 use crate::options::stdin::FilesInput;
-use crate::options::{Options, Vars, vars};
-use crate::output::{Mode, View, details, escape, file_name, grid, grid_details, lines};
+use crate::options::{Options, OptionsError, Vars, vars};
+use crate::output::summary::Summary;
+use crate::output::{Mode, View, details, escape, file_name, grid, grid_details, json, lines};
 use crate::theme::Theme;
 use log::*;
 
@@ -42,7 +46,7 @@ fn main() {
 
     logger::configure(env::var_os(vars::EZA_DEBUG).or_else(|| env::var_os(vars::EXA_DEBUG)));
 
-    let cli = get_command().get_matches();
+    let cli = get_matches();
 
     let stdout_istty = io::stdout().is_terminal();
     let mut input = String::new();
@@ -86,6 +90,7 @@ fn main() {
                 console_width,
                 git,
                 git_repos,
+                header_written: false,
             };
 
             info!("matching on exa.run");
@@ -114,6 +119,38 @@ fn main() {
     }
 }
 
+/// Parses the command-line arguments, filling in the settings from the config
+/// file for the options they leave out, unless `--no-config` is given.
+fn get_matches() -> ArgMatches {
+    let args = env::args_os().collect::<Vec<_>>();
+    let cli = get_command().get_matches_from(&args);
+    match apply_config(cli, args) {
+        Ok(matches) => matches,
+        Err(error) => {
+            eprintln!("eza: {error}");
+            exit(exits::OPTIONS_ERROR);
+        }
+    }
+}
+
+/// Merges the config file’s settings, and those of the selected profile, into
+/// the command-line arguments.
+fn apply_config(cli: ArgMatches, args: Vec<OsString>) -> Result<ArgMatches, OptionsError> {
+    let profile = Config::deduce_profile(&cli, &LiveVars)?;
+    if cli.get_flag("no-config") {
+        return Ok(cli);
+    }
+
+    let Some(config) = Config::deduce(&LiveVars) else {
+        return match profile {
+            Some(name) => Err(OptionsError::UnknownProfile(name)),
+            None => Ok(cli),
+        };
+    };
+
+    config.apply(get_command(), &cli, args, profile.as_deref())
+}
+
 /// The main program wrapper.
 pub struct Exa<'args> {
     /// List of command-line options, having been successfully parsed.
@@ -141,6 +178,10 @@ pub struct Exa<'args> {
     pub git: Option<GitCache>,
 
     pub git_repos: bool,
+
+    /// Whether the header row of delimited output has been written, which
+    /// only happens once however many directories get listed.
+    pub header_written: bool,
 }
 
 /// The “real” environment variables type.
@@ -157,18 +198,18 @@ impl Vars for LiveVars {
 /// listed before they’re actually listed, if the options demand it.
 fn git_options(options: &Options, args: &[&OsStr]) -> Option<GitCache> {
     if options.should_scan_for_git() {
-        Some(args.iter().map(PathBuf::from).collect())
+        Some(GitCache::new(args.iter().map(PathBuf::from), &options.git))
     } else {
         None
     }
 }
 
-#[cfg(not(feature = "git"))]
+#[cfg(not(any(feature = "git", feature = "gix")))]
 fn git_repos(_options: &Options, _args: &[&OsStr]) -> bool {
     return false;
 }
 
-#[cfg(feature = "git")]
+#[cfg(any(feature = "git", feature = "gix"))]
 fn get_files_in_dir(paths: &mut Vec<PathBuf>, path: PathBuf) {
     let temp_paths = if path.is_dir() {
         match path.read_dir() {
@@ -185,7 +226,7 @@ fn get_files_in_dir(paths: &mut Vec<PathBuf>, path: PathBuf) {
     paths.extend(temp_paths);
 }
 
-#[cfg(feature = "git")]
+#[cfg(any(feature = "git", feature = "gix"))]
 fn git_repos(options: &Options, args: &[&OsStr]) -> bool {
     let option_enabled = match options.view.mode {
         Mode::Details(details::Options {
@@ -210,8 +251,7 @@ fn git_repos(options: &Options, args: &[&OsStr]) -> bool {
         }
         let repos: Vec<bool> = files
             .iter()
-            .map(git2::Repository::open)
-            .map(|repo| repo.is_ok())
+            .map(|file| fs::feature::git::is_repository(file))
             .collect();
         repos.contains(&true)
     } else {
@@ -264,23 +304,39 @@ impl Exa<'_> {
         let is_only_dir = dirs.len() == 1 && no_files;
 
         self.options.filter.filter_argument_files(&mut files);
-        self.print_files(None, files)?;
+        self.print_files(None, files, 0)?;
 
-        self.print_dirs(dirs, no_files, is_only_dir, exit_status)
+        self.print_dirs(dirs, no_files, is_only_dir, exit_status, 0)
     }
 
+    /// Lists each directory, recursing into their subdirectories with
+    /// `--recurse`. The depth is how far the directories are below the ones
+    /// given as arguments, which is what `--level` limits, and what the JSON
+    /// view reports.
     fn print_dirs(
         &mut self,
         dir_files: Vec<Dir>,
         mut first: bool,
         is_only_dir: bool,
         exit_status: i32,
+        depth: usize,
     ) -> io::Result<i32> {
         let View {
             file_style: file_name::Options { quote_style, .. },
             ..
         } = self.options.view;
 
+        // JSON and delimited rows carry their own paths, so the output stays
+        // one entry per line without any headers or gaps.
+        let is_export = matches!(
+            self.options.view.mode,
+            Mode::Json(_)
+                | Mode::Details(details::Options {
+                    delimiter: Some(_),
+                    ..
+                })
+        );
+
         let mut denied_dirs = vec![];
 
         for mut dir in dir_files {
@@ -306,11 +362,11 @@ impl Exa<'_> {
             // the first directory.
             if first {
                 first = false;
-            } else {
+            } else if !is_export {
                 writeln!(&mut self.writer)?;
             }
 
-            if !is_only_dir {
+            if !is_only_dir && !is_export {
                 let mut bits = Vec::new();
                 escape(
                     dir.path.display().to_string(),
@@ -333,21 +389,17 @@ impl Exa<'_> {
             ) {
                 children.push(file);
             }
-            let recursing = self.options.dir_action.recurse_options().is_some();
+            let recursion = Recursion::at(self.options.dir_action.recurse_options(), depth + 1);
             self.options
                 .filter
-                .filter_child_files(recursing, &mut children);
-            self.options.filter.sort_files(&mut children);
+                .filter_child_files(recursion, &mut children, self.git.as_ref());
+            self.options
+                .filter
+                .sort_files(&mut children, self.git.as_ref());
 
             if let Some(recurse_opts) = self.options.dir_action.recurse_options() {
-                let depth = dir
-                    .path
-                    .components()
-                    .filter(|&c| c != Component::CurDir)
-                    .count()
-                    + 1;
                 let follow_links = self.options.view.follow_links;
-                if !recurse_opts.tree && !recurse_opts.is_too_deep(depth) {
+                if !recurse_opts.tree && !recurse_opts.is_too_deep(depth + 1) {
                     let child_dirs = children
                         .iter()
                         .filter(|f| {
@@ -360,8 +412,8 @@ impl Exa<'_> {
                         .map(fs::File::to_dir)
                         .collect::<Vec<Dir>>();
 
-                    self.print_files(Some(dir), children)?;
-                    match self.print_dirs(child_dirs, false, false, exit_status) {
+                    self.print_files(Some(dir), children, depth)?;
+                    match self.print_dirs(child_dirs, false, false, exit_status, depth + 1) {
                         Ok(_) => (),
                         Err(e) => return Err(e),
                     }
@@ -369,7 +421,7 @@ impl Exa<'_> {
                 }
             }
 
-            self.print_files(Some(dir), children)?;
+            self.print_files(Some(dir), children, depth)?;
         }
 
         if !denied_dirs.is_empty() {
@@ -385,8 +437,63 @@ impl Exa<'_> {
         Ok(exit_status)
     }
 
-    /// Prints the list of files using whichever view is selected.
-    fn print_files(&mut self, dir: Option<&Dir>, mut files: Vec<File<'_>>) -> io::Result<()> {
+    /// Prints the list of files using whichever view is selected, followed
+    /// by the summary footer if one was asked for.
+    fn print_files(
+        &mut self,
+        dir: Option<&Dir>,
+        files: Vec<File<'_>>,
+        depth: usize,
+    ) -> io::Result<()> {
+        let is_export = matches!(
+            self.options.view.mode,
+            Mode::Json(_)
+                | Mode::Details(details::Options {
+                    delimiter: Some(_),
+                    ..
+                })
+        );
+        let Some(options) = self.options.view.summary.filter(|_| !is_export) else {
+            return self.render_files(dir, files, depth, None);
+        };
+
+        // A directory’s entries are added up from the files already read for
+        // it, and a tree’s as it gets drawn. Nothing gets added up for files
+        // listed on the command line.
+        let summary = Mutex::new(Summary::default());
+        let is_tree = self
+            .options
+            .dir_action
+            .recurse_options()
+            .is_some_and(|r| r.tree);
+        let tree_summary = match dir {
+            Some(dir) => {
+                let filter = &self.options.filter;
+                summary.lock().unwrap().add_dir(dir, &files, filter, true);
+                None
+            }
+            None if is_tree && !files.is_empty() => Some(&summary),
+            None => return self.render_files(dir, files, depth, None),
+        };
+
+        self.render_files(dir, files, depth, tree_summary)?;
+        writeln!(
+            &mut self.writer,
+            "{}",
+            summary.into_inner().unwrap().describe(&options)
+        )
+    }
+
+    /// Renders the list of files using whichever view is selected. The depth
+    /// is how far the files are below the arguments, for the JSON view, and
+    /// the entries of a tree get added to the summary as it’s drawn.
+    fn render_files(
+        &mut self,
+        dir: Option<&Dir>,
+        mut files: Vec<File<'_>>,
+        depth: usize,
+        summary: Option<&Mutex<Summary>>,
+    ) -> io::Result<()> {
         if files.is_empty() {
             return Ok(());
         }
@@ -415,6 +522,7 @@ impl Exa<'_> {
                     opts,
                     console_width,
                     filter,
+                    git: self.git.as_ref(),
                 };
                 r.render(&mut self.writer)
             }
@@ -428,6 +536,7 @@ impl Exa<'_> {
                     opts,
                     console_width: 80,
                     filter,
+                    git: self.git.as_ref(),
                 };
                 r.render(&mut self.writer)
             }
@@ -439,6 +548,21 @@ impl Exa<'_> {
                     theme,
                     file_style,
                     filter,
+                    git: self.git.as_ref(),
+                };
+                r.render(&mut self.writer)
+            }
+
+            (Mode::Json(_), _) => {
+                let r = json::Render {
+                    dir,
+                    files,
+                    depth,
+                    recurse: self.options.dir_action.recurse_options(),
+                    filter: &self.options.filter,
+                    git_ignoring: self.options.filter.git_ignore == GitIgnore::CheckAndIgnore,
+                    git: self.git.as_ref(),
+                    follow_links: self.options.view.follow_links,
                 };
                 r.render(&mut self.writer)
             }
@@ -461,8 +585,18 @@ impl Exa<'_> {
                     git_ignoring,
                     git,
                     git_repos,
+                    summary,
                 };
-                r.render(&mut self.writer)
+
+                // Delimited output is one table no matter how many
+                // directories get listed, so it only needs one header row.
+                let Some(delimiter) = opts.delimiter else {
+                    return r.render(&mut self.writer);
+                };
+                let header = opts.header && !self.header_written;
+                r.render_delimited(&mut self.writer, delimiter, header)?;
+                self.header_written = true;
+                Ok(())
             }
 
             (Mode::GridDetails(opts), Some(console_width)) => {
@@ -509,6 +643,7 @@ impl Exa<'_> {
                     git_ignoring,
                     git,
                     git_repos,
+                    summary: None,
                 };
                 r.render(&mut self.writer)
             }
@@ -4,15 +4,19 @@
 // SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
+use crate::options::{OptionsError, Vars, vars};
 use crate::theme::ThemeFileType as FileType;
 use crate::theme::{
     FileKinds, FileNameStyle, Git, GitRepo, IconStyle, Links, Permissions, SELinuxContext,
     SecurityContext, Size, UiStyles, Users,
 };
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command, Id};
 use nu_ansi_term::{Color, Style};
 use serde::{Deserialize, Deserializer, Serialize};
-use serde_norway;
+use serde_norway::{self, Mapping, Value};
 use std::collections::HashMap;
+use std::ffi::OsString;
 use std::path::PathBuf;
 
 #[derive(Debug, Eq, PartialEq)]
@@ -434,13 +438,19 @@ impl FromOverride<LinksOverride> for Links {
 #[rustfmt::skip]
 #[derive(Clone, Copy, Debug,Eq, PartialEq, Serialize, Deserialize)]
 pub struct GitOverride {
-    pub new: Option<StyleOverride>,         // ga
-    pub modified: Option<StyleOverride>,    // gm
-    pub deleted: Option<StyleOverride>,     // gd
-    pub renamed: Option<StyleOverride>,     // gv
-    pub typechange: Option<StyleOverride>,  // gt
-    pub ignored: Option<StyleOverride>,     // gi
-    pub conflicted: Option<StyleOverride>,  // gc
+    pub new: Option<StyleOverride>,             // ga
+    pub modified: Option<StyleOverride>,        // gm
+    pub deleted: Option<StyleOverride>,         // gd
+    pub renamed: Option<StyleOverride>,         // gv
+    pub typechange: Option<StyleOverride>,      // gt
+    pub ignored: Option<StyleOverride>,         // gi
+    pub conflicted: Option<StyleOverride>,      // gc
+    pub submodule: Option<StyleOverride>,       // gs
+    pub submodule_moved: Option<StyleOverride>, // gS
+    pub worktree: Option<StyleOverride>,        // gW
+    pub lfs_pointer: Option<StyleOverride>,     // gL
+    pub commit_author: Option<StyleOverride>,   // gA
+    pub commit_hash: Option<StyleOverride>,     // gH
 }
 
 impl FromOverride<GitOverride> for Git {
@@ -453,6 +463,12 @@ impl FromOverride<GitOverride> for Git {
             typechange: FromOverride::from(value.typechange, default.typechange),
             ignored: FromOverride::from(value.ignored, default.ignored),
             conflicted: FromOverride::from(value.conflicted, default.conflicted),
+            submodule: FromOverride::from(value.submodule, default.submodule),
+            submodule_moved: FromOverride::from(value.submodule_moved, default.submodule_moved),
+            worktree: FromOverride::from(value.worktree, default.worktree),
+            lfs_pointer: FromOverride::from(value.lfs_pointer, default.lfs_pointer),
+            commit_author: FromOverride::from(value.commit_author, default.commit_author),
+            commit_hash: FromOverride::from(value.commit_hash, default.commit_hash),
         }
     }
 }
@@ -464,6 +480,10 @@ pub struct GitRepoOverride {
     pub branch_other: Option<StyleOverride>, //Go
     pub git_clean: Option<StyleOverride>,    //Gc
     pub git_dirty: Option<StyleOverride>,    //Gd
+    pub detached: Option<StyleOverride>,     //Gh
+    pub ahead_behind: Option<StyleOverride>, //Ga
+    pub stash: Option<StyleOverride>,        //Gs
+    pub operation: Option<StyleOverride>,    //Gp
 }
 
 impl FromOverride<GitRepoOverride> for GitRepo {
@@ -473,6 +493,10 @@ impl FromOverride<GitRepoOverride> for GitRepo {
             branch_other: FromOverride::from(value.branch_other, default.branch_other),
             git_clean: FromOverride::from(value.git_clean, default.git_clean),
             git_dirty: FromOverride::from(value.git_dirty, default.git_dirty),
+            detached: FromOverride::from(value.detached, default.detached),
+            ahead_behind: FromOverride::from(value.ahead_behind, default.ahead_behind),
+            stash: FromOverride::from(value.stash, default.stash),
+            operation: FromOverride::from(value.operation, default.operation),
         }
     }
 }
@@ -616,6 +640,265 @@ impl FromOverride<UiStylesOverride> for UiStyles {
         }
     }
 }
+/// The main config file, `config.yml`, maps the long names of command-line
+/// options to values that are used as their defaults:
+///
+/// ```yaml
+/// group-directories-first: true
+/// icons: auto
+/// sort: modified
+/// time-style: long-iso
+/// ```
+///
+/// Anything given on the command line wins over the file, including options
+/// that replace or conflict with a setting, such as `--tsv` over `csv: true`.
+///
+/// Named bundles of settings can be kept under `profiles`, and are layered on
+/// top of the rest of the file when selected with `--profile` or
+/// `EZA_PROFILE`:
+///
+/// ```yaml
+/// profiles:
+///   review:
+///     git: true
+///     git-ignore: true
+///     tree: true
+///     level: 3
+/// ```
+#[derive(Debug, Eq, PartialEq)]
+pub struct Config {
+    location: PathBuf,
+}
+
+impl Config {
+    /// Finds the config file in `EZA_CONFIG_DIR`, or in the `eza` directory
+    /// of the user’s config directory, if there is one.
+    pub fn deduce<V: Vars>(vars: &V) -> Option<Self> {
+        let dir = match vars.get(vars::EZA_CONFIG_DIR) {
+            Some(dir) => PathBuf::from(dir),
+            None => dirs::config_dir()?.join("eza"),
+        };
+
+        ["config.yml", "config.yaml"]
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.exists())
+            .map(Self::from_path)
+    }
+
+    #[must_use]
+    pub fn from_path(location: PathBuf) -> Self {
+        Self { location }
+    }
+
+    /// Determines which profile to use, if any: the one given with
+    /// `--profile`, or else the one named in `EZA_PROFILE`. The environment
+    /// variable is ignored along with the rest of the config when
+    /// `--no-config` is given, but the option conflicts with it.
+    pub fn deduce_profile<V: Vars>(
+        matches: &ArgMatches,
+        vars: &V,
+    ) -> Result<Option<String>, OptionsError> {
+        let no_config = matches.get_flag("no-config");
+
+        if let Some(name) = matches.get_one::<String>("profile") {
+            if no_config {
+                return Err(OptionsError::Conflict("profile", "no-config"));
+            }
+            return Ok(Some(name.clone()));
+        }
+
+        if no_config {
+            return Ok(None);
+        }
+
+        Ok(vars
+            .get(vars::EZA_PROFILE)
+            .map(|name| name.to_string_lossy().into_owned())
+            .filter(|name| !name.is_empty()))
+    }
+
+    /// Reads the config file and merges its settings, followed by those of
+    /// the selected profile, into the command-line arguments, for every
+    /// option that wasn’t given on the command line.
+    pub fn apply(
+        &self,
+        command: Command,
+        cli: &ArgMatches,
+        args: Vec<OsString>,
+        profile: Option<&str>,
+    ) -> Result<ArgMatches, OptionsError> {
+        let error = |e: String| OptionsError::BadConfig(self.location.clone(), e);
+
+        let file = std::fs::File::open(&self.location).map_err(|e| error(e.to_string()))?;
+        let settings: Mapping =
+            serde_norway::from_reader(file).map_err(|e| error(e.to_string()))?;
+
+        let settings = with_profile(settings, profile)?;
+        let settings = setting_args(&command, &settings).map_err(error)?;
+        Ok(merge_settings(command, &settings, cli, args))
+    }
+}
+
+/// Takes the `profiles` out of the settings, and overrides the remaining
+/// settings with those of the selected profile.
+fn with_profile(mut settings: Mapping, profile: Option<&str>) -> Result<Mapping, OptionsError> {
+    let profiles = settings.remove("profiles");
+
+    let Some(name) = profile else {
+        return Ok(settings);
+    };
+
+    let unknown = || OptionsError::UnknownProfile(name.into());
+    let profile = profiles
+        .as_ref()
+        .and_then(|p| p.get(name))
+        .ok_or_else(unknown)?;
+    let Value::Mapping(profile) = profile else {
+        return Err(unknown());
+    };
+
+    for (key, value) in profile {
+        settings.insert(key.clone(), value.clone());
+    }
+
+    Ok(settings)
+}
+
+/// The arguments that would set each option in the settings on the command
+/// line, along with the ID of the option.
+type SettingArgs = Vec<(Id, Vec<String>)>;
+
+/// Turns the settings into command-line arguments, checking that each one
+/// names an option and has a value it accepts.
+fn setting_args(command: &Command, settings: &Mapping) -> Result<SettingArgs, String> {
+    let mut setting_args = Vec::new();
+
+    for (key, value) in settings {
+        let Some(name) = key.as_str() else {
+            return Err(format!("Option name {key:?} is not a string"));
+        };
+
+        let Some(arg) = command.get_arguments().find(|arg| {
+            arg.get_long() == Some(name)
+                || arg
+                    .get_all_aliases()
+                    .is_some_and(|aliases| aliases.contains(&name))
+        }) else {
+            return Err(format!("Unknown option {name:?}"));
+        };
+
+        if matches!(name, "profile" | "no-config") {
+            return Err(format!("Option {name:?} can’t be set in the config file"));
+        }
+
+        let Some(args) = arg_values(arg, value) else {
+            return Err(format!("Option {name:?} has an unsupported value"));
+        };
+
+        setting_args.push((arg.get_id().clone(), args));
+    }
+
+    // Parse the settings on their own, so a bad value gets blamed on the
+    // config file rather than on the command line.
+    let args = setting_args
+        .iter()
+        .flat_map(|(_, args)| args.iter().cloned());
+    if let Err(e) = command
+        .clone()
+        .try_get_matches_from(std::iter::once(command.get_name().to_string()).chain(args))
+    {
+        let message = e.to_string();
+        let message = message.lines().next().unwrap_or_default();
+        return Err(message.trim_start_matches("error: ").to_string());
+    }
+
+    Ok(setting_args)
+}
+
+/// Parses the command-line arguments with the settings’ arguments put in
+/// front of them, leaving out any setting that the command line replaces:
+/// one for an option that was given, that conflicts with one that was given,
+/// or that shares a group of alternatives with one that was given.
+fn merge_settings(
+    command: Command,
+    settings: &SettingArgs,
+    cli: &ArgMatches,
+    args: Vec<OsString>,
+) -> ArgMatches {
+    let given = |id: &Id| cli.value_source(id.as_str()) == Some(ValueSource::CommandLine);
+    let conflicts = |arg: &Arg, other: &Arg| {
+        command
+            .get_arg_conflicts_with(arg)
+            .into_iter()
+            .any(|conflict| conflict.get_id() == other.get_id())
+    };
+
+    let mut args = args.into_iter();
+    let mut merged = args.next().into_iter().collect::<Vec<_>>();
+
+    for (id, setting) in settings {
+        let Some(arg) = command.get_arguments().find(|arg| arg.get_id() == id) else {
+            continue;
+        };
+
+        let replaced = given(id)
+            || command
+                .get_arguments()
+                .filter(|other| given(other.get_id()))
+                .any(|other| conflicts(arg, other) || conflicts(other, arg))
+            || command
+                .get_groups()
+                .filter(|group| group.get_args().any(|member| member == id))
+                .flat_map(ArgGroup::get_args)
+                .any(given);
+
+        if !replaced {
+            merged.extend(setting.iter().map(OsString::from));
+        }
+    }
+
+    merged.extend(args);
+    command.get_matches_from(merged)
+}
+
+/// Turns a setting into the arguments that would be given on the command
+/// line. Flags are given once when `true`, or as many times as a number says
+/// for those that can be repeated.
+fn arg_values(arg: &Arg, value: &Value) -> Option<Vec<String>> {
+    let long = arg.get_long()?;
+
+    if arg.get_action().takes_values() {
+        return Some(vec![format!("--{long}={}", setting_value(value)?)]);
+    }
+
+    let times = match value {
+        Value::Bool(flag) => usize::from(*flag),
+        Value::Number(n) if matches!(arg.get_action(), ArgAction::Count) => {
+            usize::try_from(n.as_u64()?).ok()?
+        }
+        _ => return None,
+    };
+    Some(vec![format!("--{long}"); times])
+}
+
+/// Turns a setting into the value that would be given to an option on the
+/// command line. Lists are joined with commas, as taken by options such as
+/// `--columns`.
+fn setting_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.clone()),
+        Value::Sequence(values) => values
+            .iter()
+            .map(setting_value)
+            .collect::<Option<Vec<_>>>()
+            .map(|values| values.join(",")),
+        _ => None,
+    }
+}
+
 impl ThemeConfig {
     #[must_use]
     pub fn from_path(path: PathBuf) -> Self {
@@ -634,6 +917,179 @@ impl ThemeConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fs::filter::{SortCase, SortField, SortKey};
+    use crate::options::parser::{TimeArgs, get_command};
+    use crate::options::vars::test::MockVars;
+    use crate::output::Mode;
+    use crate::output::details::Delimiter;
+
+    fn settings(yaml: &str) -> Mapping {
+        serde_norway::from_str(yaml).unwrap()
+    }
+
+    fn merge(settings: &Mapping, args: Vec<&str>) -> ArgMatches {
+        let command = get_command();
+        let args = std::iter::once("eza")
+            .chain(args)
+            .map(OsString::from)
+            .collect::<Vec<_>>();
+        let cli = command.clone().get_matches_from(&args);
+        let settings = setting_args(&command, settings).unwrap();
+        merge_settings(command, &settings, &cli, args)
+    }
+
+    #[test]
+    fn config_values_are_used() {
+        let matches = merge(
+            &settings("group-directories-first: true\nsort: size\nlevel: 2\nall: 2\n"),
+            vec![],
+        );
+        assert!(matches.get_flag("dirs-first"));
+        assert_eq!(
+            matches.get_one::<SortKey>("sort").map(|key| key.field),
+            Some(SortField::Size)
+        );
+        assert_eq!(matches.get_one::<usize>("level"), Some(&2));
+        assert_eq!(matches.get_count("all"), 2);
+    }
+
+    #[test]
+    fn command_line_wins_over_config() {
+        let matches = merge(&settings("sort: size\n"), vec!["--sort", "inode"]);
+        assert_eq!(
+            matches.get_one::<SortKey>("sort").map(|key| key.field),
+            Some(SortField::FileInode)
+        );
+    }
+
+    #[test]
+    fn command_line_replaces_config_alternative() {
+        let matches = merge(&settings("csv: true\n"), vec!["--tsv"]);
+        assert!(matches.get_flag("tsv"));
+        assert!(!matches.get_flag("csv"));
+        let mode = Mode::deduce(&matches, &MockVars::default(), false, false);
+        assert!(matches!(mode, Ok(Mode::Details(opts)) if opts.delimiter == Some(Delimiter::Tab)));
+
+        let matches = merge(&settings("json: true\n"), vec!["--csv"]);
+        assert!(!matches.get_flag("json"));
+
+        let matches = merge(&settings("long: true\n"), vec!["--oneline"]);
+        assert!(!matches.get_flag("long"));
+        assert!(matches.get_flag("oneline"));
+    }
+
+    #[test]
+    fn command_line_drops_conflicting_config() {
+        let matches = merge(&settings("time: accessed\n"), vec!["--modified"]);
+        assert_eq!(matches.get_one::<TimeArgs>("time"), None);
+        assert!(matches.get_flag("modified"));
+
+        let matches = merge(&settings("modified: true\n"), vec!["--time", "accessed"]);
+        assert!(!matches.get_flag("modified"));
+    }
+
+    #[test]
+    fn config_lists_are_joined() {
+        let matches = merge(&settings("columns: [size, name]\n"), vec![]);
+        assert_eq!(
+            matches.get_one::<String>("columns").map(String::as_str),
+            Some("size,name")
+        );
+    }
+
+    #[test]
+    fn config_aliases_are_accepted() {
+        assert!(setting_args(&get_command(), &settings("colour: never\n")).is_ok());
+    }
+
+    #[test]
+    fn config_bad_value() {
+        assert!(setting_args(&get_command(), &settings("sort: sideways\n")).is_err());
+        assert!(setting_args(&get_command(), &settings("long: sometimes\n")).is_err());
+    }
+
+    #[test]
+    fn config_unknown_option() {
+        assert_eq!(
+            setting_args(&get_command(), &settings("sorting: size\n")).err(),
+            Some("Unknown option \"sorting\"".into())
+        );
+    }
+
+    #[test]
+    fn profile_overrides_settings() {
+        let settings =
+            settings("sort: size\nprofiles:\n  review:\n    sort: name\n    tree: true\n");
+        let settings = with_profile(settings, Some("review")).unwrap();
+        let matches = merge(&settings, vec![]);
+        assert_eq!(
+            matches.get_one::<SortKey>("sort").map(|key| key.field),
+            Some(SortField::Name(SortCase::AaBbCc))
+        );
+        assert!(matches.get_flag("tree"));
+    }
+
+    #[test]
+    fn profiles_unused_without_selection() {
+        let settings = settings("profiles:\n  review:\n    tree: true\n");
+        let settings = with_profile(settings, None).unwrap();
+        let matches = merge(&settings, vec![]);
+        assert!(!matches.get_flag("tree"));
+    }
+
+    #[test]
+    fn unknown_profile() {
+        let settings = settings("profiles:\n  review:\n    tree: true\n");
+        assert_eq!(
+            with_profile(settings, Some("media")).err(),
+            Some(OptionsError::UnknownProfile("media".into()))
+        );
+    }
+
+    #[test]
+    fn profile_cannot_select_profile() {
+        assert!(setting_args(&get_command(), &settings("profile: review\n")).is_err());
+    }
+
+    #[test]
+    fn profile_from_env() {
+        let matches = get_command().get_matches_from(vec!["eza"]);
+        let mut vars = MockVars::default();
+        vars.set(vars::EZA_PROFILE, &"logs".into());
+        assert_eq!(
+            Config::deduce_profile(&matches, &vars),
+            Ok(Some("logs".into()))
+        );
+    }
+
+    #[test]
+    fn profile_option_beats_env() {
+        let matches = get_command().get_matches_from(vec!["eza", "--profile", "review"]);
+        let mut vars = MockVars::default();
+        vars.set(vars::EZA_PROFILE, &"logs".into());
+        assert_eq!(
+            Config::deduce_profile(&matches, &vars),
+            Ok(Some("review".into()))
+        );
+    }
+
+    #[test]
+    fn profile_conflicts_with_no_config() {
+        let matches =
+            get_command().get_matches_from(vec!["eza", "--profile", "review", "--no-config"]);
+        assert_eq!(
+            Config::deduce_profile(&matches, &MockVars::default()),
+            Err(OptionsError::Conflict("profile", "no-config"))
+        );
+    }
+
+    #[test]
+    fn no_config_ignores_env_profile() {
+        let matches = get_command().get_matches_from(vec!["eza", "--no-config"]);
+        let mut vars = MockVars::default();
+        vars.set(vars::EZA_PROFILE, &"logs".into());
+        assert_eq!(Config::deduce_profile(&matches, &vars), Ok(None));
+    }
 
     #[test]
     fn parse_none_color_from_string() {
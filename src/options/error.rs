@@ -7,6 +7,7 @@
 use std::ffi::OsString;
 use std::fmt;
 use std::num::ParseIntError;
+use std::path::PathBuf;
 
 /// Something wrong with the combination of options the user has picked.
 #[derive(PartialEq, Eq, Debug)]
@@ -39,6 +40,19 @@ pub enum OptionsError {
 
     /// A glob ignore was given that failed to be parsed as a pattern.
     FailedGlobPattern(String),
+
+    /// A column was named that doesn’t exist.
+    UnknownColumn(String),
+
+    /// The config file could not be read, or holds a setting that doesn’t
+    /// correspond to any option.
+    BadConfig(PathBuf, String),
+
+    /// A profile was selected that isn’t defined in the config file.
+    UnknownProfile(String),
+
+    /// A size or time limit was given that failed to be parsed.
+    BadLimit(&'static str, String),
 }
 
 /// The source of a string that failed to be parsed as a number.
@@ -81,6 +95,10 @@ impl fmt::Display for OptionsError {
             Self::TreeAllAll                 => write!(f, "Option --tree is useless given --all --all"),
             Self::FailedParse(s, n, e)       => write!(f, "Value {s:?} not valid for {n}: {e}"),
             Self::FailedGlobPattern(e)       => write!(f, "Failed to parse glob pattern: {e}"),
+            Self::UnknownColumn(c)           => write!(f, "Unknown column {c:?} for option --columns"),
+            Self::BadConfig(path, e)         => write!(f, "Config file {}: {e}", path.display()),
+            Self::UnknownProfile(p)          => write!(f, "Unknown profile {p:?} for option --profile"),
+            Self::BadLimit(a, s)             => write!(f, "Value {s:?} not valid for option --{a}"),
         };
     }
 }
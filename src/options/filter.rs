@@ -6,14 +6,18 @@
 // SPDX-License-Identifier: MIT
 //! Parsing the options for `FileFilter`.
 
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone};
 use clap::ArgMatches;
 
 use crate::fs::DotFilter;
 use crate::fs::filter::{
-    FileFilter, FileFilterFlags, GitIgnore, IgnorePatterns, SortCase, SortField,
+    EntryCount, FileFilter, FileFilterFlags, FilterCache, GitFilter, GitIgnore, IgnorePatterns,
+    MetadataLimits, SortCase, SortField, SortKey,
 };
+use crate::output::table::TimeType;
 
 use crate::options::OptionsError;
+use crate::options::parser::{EntriesArgs, TimeArgs};
 
 impl FileFilter {
     /// Determines which of all the file filter options to use.
@@ -29,20 +33,35 @@ impl FileFilter {
             ("show-symlinks", FFF::ShowSymlinks),
             ("dirs-last", FFF::ListDirsLast),
             ("dirs-first", FFF::ListDirsFirst),
+            ("prune-dirs", FFF::PruneDirs),
         ] {
             if matches.get_flag(flag) {
                 filter_flags.push(filter_flag.clone());
             }
         }
 
+        let only_patterns = IgnorePatterns::deduce_only(matches)?;
+        if strict && only_patterns.is_empty() && matches.get_flag("prune-dirs") {
+            return Err(OptionsError::Useless("prune-dirs", false, "only-glob"));
+        }
+
         Ok(Self {
             no_symlinks: matches.get_flag("no-symlinks"),
             show_symlinks: matches.get_flag("show-symlinks"),
             flags: filter_flags,
-            sort_field: *matches.get_one("sort").unwrap(),
+            sort_keys: matches
+                .get_many::<SortKey>("sort")
+                .unwrap()
+                .copied()
+                .collect(),
             dot_filter: DotFilter::deduce(matches, strict)?,
             ignore_patterns: IgnorePatterns::deduce(matches)?,
+            only_patterns,
             git_ignore: GitIgnore::deduce(matches),
+            git_filter: GitFilter::deduce(matches),
+            limits: MetadataLimits::deduce(matches, &Local::now())?,
+            entry_count: EntryCount::deduce(matches),
+            cache: FilterCache::default(),
         })
     }
 }
@@ -124,9 +143,19 @@ impl IgnorePatterns {
     /// `--ignore-glob` argument’s value. This is a list of strings
     /// separated by pipe (`|`) characters, given in any order.
     pub fn deduce(matches: &ArgMatches) -> Result<Self, OptionsError> {
+        Self::deduce_from(matches, "ignore-glob")
+    }
+
+    /// Determines the set of glob patterns to include based on the
+    /// `--only-glob` argument’s value, in the same format.
+    pub fn deduce_only(matches: &ArgMatches) -> Result<Self, OptionsError> {
+        Self::deduce_from(matches, "only-glob")
+    }
+
+    fn deduce_from(matches: &ArgMatches, id: &str) -> Result<Self, OptionsError> {
         // If there are no inputs, we return a set of patterns that doesn’t
         // match anything, rather than, say, `None`.
-        let Some(inputs) = matches.get_one::<String>("ignore-glob") else {
+        let Some(inputs) = matches.get_one::<String>(id) else {
             return Ok(Self::empty());
         };
 
@@ -143,6 +172,127 @@ impl IgnorePatterns {
     }
 }
 
+impl MetadataLimits {
+    /// Determines the size and time bounds from the `--min-size`,
+    /// `--max-size`, `--newer-than`, and `--older-than` arguments. Durations
+    /// are counted back from `now`, and dates are in the time zone of `now`.
+    /// Both become UTC, like the timestamps they get compared with.
+    pub fn deduce<Tz: TimeZone>(
+        matches: &ArgMatches,
+        now: &DateTime<Tz>,
+    ) -> Result<Self, OptionsError> {
+        let size = |name: &'static str| {
+            matches
+                .get_one::<String>(name)
+                .map(|input| {
+                    parse_size(input).ok_or_else(|| OptionsError::BadLimit(name, input.clone()))
+                })
+                .transpose()
+        };
+
+        let time = |name: &'static str| {
+            matches
+                .get_one::<String>(name)
+                .map(|input| {
+                    parse_time(input, now)
+                        .ok_or_else(|| OptionsError::BadLimit(name, input.clone()))
+                })
+                .transpose()
+        };
+
+        let time_type = match matches.get_one::<TimeArgs>("filter-time") {
+            None | Some(TimeArgs::Modified) => TimeType::Modified,
+            Some(TimeArgs::Changed) => TimeType::Changed,
+            Some(TimeArgs::Accessed) => TimeType::Accessed,
+            Some(TimeArgs::Created) => TimeType::Created,
+        };
+
+        Ok(Self {
+            min_size: size("min-size")?,
+            max_size: size("max-size")?,
+            time_type,
+            newer_than: time("newer-than")?,
+            older_than: time("older-than")?,
+        })
+    }
+}
+
+/// Parses a size such as `512`, `10k`, `1.5M`, or `2GiB` into a number of
+/// bytes. Plain suffixes are decimal, and suffixes with an `i` are binary.
+fn parse_size(input: &str) -> Option<u64> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(input.len());
+    let (number, suffix) = input.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let suffix = suffix.strip_suffix(['b', 'B']).unwrap_or(suffix);
+    let (prefix, base) = match suffix.strip_suffix('i') {
+        Some(prefix) if !prefix.is_empty() => (prefix, 1024_f64),
+        _ => (suffix, 1000_f64),
+    };
+
+    let power = match prefix.to_ascii_lowercase().as_str() {
+        "" => 0,
+        "k" => 1,
+        "m" => 2,
+        "g" => 3,
+        "t" => 4,
+        "p" => 5,
+        _ => return None,
+    };
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    Some((number * base.powi(power)).round() as u64)
+}
+
+/// Parses either a duration before `now`, such as `30s`, `15min`, `2h`,
+/// `3d`, `1w`, or `1y`, or a date and optional time in the time zone of
+/// `now`, such as `2024-01-01` or `2024-01-01 12:30`, into a UTC time.
+fn parse_time<Tz: TimeZone>(input: &str, now: &DateTime<Tz>) -> Option<NaiveDateTime> {
+    let input = input.trim();
+    let utc = |local: NaiveDateTime| {
+        now.timezone()
+            .from_local_datetime(&local)
+            .single()
+            .map(|time| time.naive_utc())
+    };
+
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return utc(date.and_hms_opt(0, 0, 0)?);
+    }
+
+    for format in [
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+    ] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(input, format) {
+            return utc(time);
+        }
+    }
+
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .filter(|&split| split > 0)?;
+    let (number, unit) = input.split_at(split);
+    let number: i64 = number.parse().ok()?;
+
+    let delta = match unit {
+        "s" | "sec" => TimeDelta::try_seconds(number),
+        "m" | "min" => TimeDelta::try_minutes(number),
+        "h" => TimeDelta::try_hours(number),
+        "d" => TimeDelta::try_days(number),
+        "w" => TimeDelta::try_weeks(number),
+        "y" => TimeDelta::try_days(number.checked_mul(365)?),
+        _ => None,
+    }?;
+
+    now.naive_utc().checked_sub_signed(delta)
+}
+
 impl GitIgnore {
     pub fn deduce(matches: &ArgMatches) -> Self {
         if matches.get_flag("git-ignore") {
@@ -153,13 +303,70 @@ impl GitIgnore {
     }
 }
 
+impl GitFilter {
+    pub fn deduce(matches: &ArgMatches) -> Self {
+        // Untracked files are a kind of change, so asking for both is the
+        // same as just asking for changes.
+        if matches.get_flag("git-changed") {
+            Self::Changed
+        } else if matches.get_flag("git-untracked") {
+            Self::Untracked
+        } else {
+            Self::Off
+        }
+    }
+}
+
+impl EntryCount {
+    pub fn deduce(matches: &ArgMatches) -> Self {
+        match matches.get_one::<EntriesArgs>("entries") {
+            Some(EntriesArgs::Recursive) => Self::Recursive,
+            Some(EntriesArgs::Direct) | None => Self::Direct,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::ffi::OsString;
 
+    use chrono::{FixedOffset, Utc};
+
     use super::*;
     use crate::options::parser::test::{mock_cli, mock_cli_try};
 
+    #[test]
+    fn deduce_git_filter_off() {
+        assert_eq!(GitFilter::deduce(&mock_cli(vec![""])), GitFilter::Off);
+    }
+
+    #[test]
+    fn deduce_git_filter_untracked() {
+        assert_eq!(
+            GitFilter::deduce(&mock_cli(vec!["--git-untracked"])),
+            GitFilter::Untracked
+        );
+    }
+
+    #[test]
+    fn deduce_git_filter_changed_includes_untracked() {
+        assert_eq!(
+            GitFilter::deduce(&mock_cli(vec!["--git-untracked", "--git-changed"])),
+            GitFilter::Changed
+        );
+    }
+
+    #[test]
+    fn deduce_entry_count() {
+        let deduce = |args| EntryCount::deduce(&mock_cli(args));
+        assert_eq!(deduce(vec![""]), EntryCount::Direct);
+        assert_eq!(deduce(vec!["--entries"]), EntryCount::Direct);
+        assert_eq!(deduce(vec!["--entries=recursive"]), EntryCount::Recursive);
+        // A value has to be joined with ‘=’, so a path after the flag isn’t
+        // taken as one.
+        assert_eq!(deduce(vec!["--entries", "recursive"]), EntryCount::Direct);
+    }
+
     #[test]
     fn deduce_git_ignore_off() {
         assert_eq!(GitIgnore::deduce(&mock_cli(vec![""])), GitIgnore::Off);
@@ -202,6 +409,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deduce_only_patterns() {
+        let (res, _) = IgnorePatterns::parse_from_iter("*.rs|*.toml".split('|'));
+
+        assert_eq!(
+            IgnorePatterns::deduce_only(&mock_cli(vec!["--only-glob", "*.rs|*.toml"])),
+            Ok(res)
+        );
+    }
+
+    #[test]
+    fn deduce_only_patterns_error() {
+        let (_, mut e) = IgnorePatterns::parse_from_iter(vec!["["]);
+        assert_eq!(
+            IgnorePatterns::deduce_only(&mock_cli(vec!["--only-glob", "["])),
+            Err(e.pop().unwrap().into())
+        );
+    }
+
+    #[test]
+    fn deduce_prune_dirs_useless_without_only_glob() {
+        assert_eq!(
+            FileFilter::deduce(&mock_cli(vec!["--prune-dirs"]), true),
+            Err(OptionsError::Useless("prune-dirs", false, "only-glob"))
+        );
+    }
+
     #[test]
     fn deduce_dot_filter_just_files() {
         assert_eq!(
@@ -250,126 +484,196 @@ mod tests {
         );
     }
 
+    fn sort_field(args: Vec<&str>) -> Option<SortField> {
+        mock_cli(args)
+            .get_one::<SortKey>("sort")
+            .map(|key| key.field)
+    }
+
     #[test]
     fn deduce_sort_field_default() {
-        assert_eq!(
-            mock_cli(vec![""]).get_one::<SortField>("sort"),
-            Some(&SortField::default())
-        );
+        assert_eq!(sort_field(vec![""]), Some(SortField::default()));
     }
 
     #[test]
     fn deduce_sort_field_name() {
         assert_eq!(
-            mock_cli(vec!["--sort", "name"]).get_one::<SortField>("sort"),
-            Some(&SortField::Name(SortCase::AaBbCc))
+            sort_field(vec!["--sort", "name"]),
+            Some(SortField::Name(SortCase::AaBbCc))
         );
     }
 
     #[test]
     fn deduce_sort_field_name_case() {
         assert_eq!(
-            mock_cli(vec!["--sort", "Name"]).get_one::<SortField>("sort"),
-            Some(&SortField::Name(SortCase::ABCabc))
+            sort_field(vec!["--sort", "Name"]),
+            Some(SortField::Name(SortCase::ABCabc))
         );
     }
 
     #[test]
     fn deduce_sort_field_name_mix_hidden() {
         assert_eq!(
-            mock_cli(vec!["--sort", ".name"]).get_one::<SortField>("sort"),
-            Some(&SortField::NameMixHidden(SortCase::AaBbCc))
+            sort_field(vec!["--sort", ".name"]),
+            Some(SortField::NameMixHidden(SortCase::AaBbCc))
         );
     }
 
     #[test]
     fn deduce_sort_field_name_mix_hidden_case() {
         assert_eq!(
-            mock_cli(vec!["--sort", ".Name"]).get_one::<SortField>("sort"),
-            Some(&SortField::NameMixHidden(SortCase::ABCabc))
+            sort_field(vec!["--sort", ".Name"]),
+            Some(SortField::NameMixHidden(SortCase::ABCabc))
         );
     }
 
     #[test]
     fn deduce_sort_field_size() {
+        assert_eq!(sort_field(vec!["--sort", "size"]), Some(SortField::Size));
+        assert_eq!(sort_field(vec!["--sort", "usage"]), Some(SortField::Size));
+    }
+
+    #[test]
+    fn deduce_sort_field_entries() {
+        assert_eq!(
+            sort_field(vec!["--sort", "entries"]),
+            Some(SortField::Entries)
+        );
         assert_eq!(
-            mock_cli(vec!["--sort", "size"]).get_one::<SortField>("sort"),
-            Some(&SortField::Size)
+            sort_field(vec!["--sort", "count"]),
+            Some(SortField::Entries)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn deduce_sort_field_commit() {
+        assert_eq!(
+            sort_field(vec!["--sort", "commit-date"]),
+            Some(SortField::CommitDate)
+        );
+        assert_eq!(
+            sort_field(vec!["--sort", "author"]),
+            Some(SortField::CommitAuthor)
         );
     }
 
     #[test]
     fn deduce_sort_field_extension() {
         assert_eq!(
-            mock_cli(vec!["--sort", "ext"]).get_one::<SortField>("sort"),
-            Some(&SortField::Extension(SortCase::AaBbCc))
+            sort_field(vec!["--sort", "ext"]),
+            Some(SortField::Extension(SortCase::AaBbCc))
         );
     }
 
     #[test]
     fn deduce_sort_field_extension_case() {
         assert_eq!(
-            mock_cli(vec!["--sort", "Ext"]).get_one::<SortField>("sort"),
-            Some(&SortField::Extension(SortCase::ABCabc))
+            sort_field(vec!["--sort", "Ext"]),
+            Some(SortField::Extension(SortCase::ABCabc))
         );
     }
 
     #[test]
     fn deduce_sort_field_date() {
         assert_eq!(
-            mock_cli(vec!["--sort", "date"]).get_one::<SortField>("sort"),
-            Some(&SortField::ModifiedDate)
+            sort_field(vec!["--sort", "date"]),
+            Some(SortField::ModifiedDate)
         );
     }
 
     #[test]
     fn deduce_sort_field_time() {
         assert_eq!(
-            mock_cli(vec!["--sort", "time"]).get_one::<SortField>("sort"),
-            Some(&SortField::ModifiedDate)
+            sort_field(vec!["--sort", "time"]),
+            Some(SortField::ModifiedDate)
         );
     }
 
     #[test]
     fn deduce_sort_field_age() {
         assert_eq!(
-            mock_cli(vec!["--sort", "age"]).get_one::<SortField>("sort"),
-            Some(&SortField::ModifiedAge)
+            sort_field(vec!["--sort", "age"]),
+            Some(SortField::ModifiedAge)
         );
     }
 
     #[test]
     fn deduce_sort_field_old() {
         assert_eq!(
-            mock_cli(vec!["--sort", "old"]).get_one::<SortField>("sort"),
-            Some(&SortField::ModifiedAge)
+            sort_field(vec!["--sort", "old"]),
+            Some(SortField::ModifiedAge)
         );
     }
 
     #[test]
     fn deduce_sort_field_ch() {
         assert_eq!(
-            mock_cli(vec!["--sort", "ch"]).get_one::<SortField>("sort"),
-            Some(&SortField::ChangedDate)
+            sort_field(vec!["--sort", "ch"]),
+            Some(SortField::ChangedDate)
         );
     }
 
     #[test]
     fn deduce_sort_field_acc() {
         assert_eq!(
-            mock_cli(vec!["--sort", "acc"]).get_one::<SortField>("sort"),
-            Some(&SortField::AccessedDate)
+            sort_field(vec!["--sort", "acc"]),
+            Some(SortField::AccessedDate)
         );
     }
 
     #[test]
     fn deduce_sort_field_cr() {
         assert_eq!(
-            mock_cli(vec!["--sort", "cr"]).get_one::<SortField>("sort"),
-            Some(&SortField::CreatedDate)
+            sort_field(vec!["--sort", "cr"]),
+            Some(SortField::CreatedDate)
+        );
+    }
+
+    #[test]
+    fn deduce_sort_keys() {
+        assert_eq!(
+            FileFilter::deduce(&mock_cli(vec!["--sort", "type,-size,name"]), false)
+                .map(|filter| filter.sort_keys),
+            Ok(vec![
+                SortField::FileType.into(),
+                SortKey {
+                    field: SortField::Size,
+                    descending: true
+                },
+                SortField::Name(SortCase::AaBbCc).into(),
+            ])
+        );
+    }
+
+    #[test]
+    fn deduce_sort_key_descending_on_its_own() {
+        assert_eq!(
+            mock_cli(vec!["--sort", "-size"]).get_one::<SortKey>("sort"),
+            Some(&SortKey {
+                field: SortField::Size,
+                descending: true
+            })
+        );
+    }
+
+    #[test]
+    fn deduce_sort_keys_last_option_wins() {
+        assert_eq!(
+            FileFilter::deduce(
+                &mock_cli(vec!["--sort", "size,name", "--sort", "ext"]),
+                false
+            )
+            .map(|filter| filter.sort_keys),
+            Ok(vec![SortField::Extension(SortCase::AaBbCc).into()])
         );
     }
 
+    #[test]
+    fn deduce_sort_keys_err() {
+        assert!(mock_cli_try(vec!["--sort", "size,foo"]).is_err());
+    }
+
     #[test]
     fn deduce_sort_field_err() {
         assert!(mock_cli_try(vec!["--sort", "foo"]).is_err());
@@ -381,12 +685,17 @@ mod tests {
             FileFilter::deduce(&mock_cli(vec![""]), false),
             Ok(FileFilter {
                 flags: vec![],
-                sort_field: SortField::default(),
+                sort_keys: vec![SortField::default().into()],
                 dot_filter: DotFilter::JustFiles,
                 ignore_patterns: IgnorePatterns::empty(),
+                only_patterns: IgnorePatterns::empty(),
                 git_ignore: GitIgnore::Off,
+                git_filter: GitFilter::Off,
+                limits: MetadataLimits::empty(),
                 no_symlinks: false,
                 show_symlinks: false,
+                entry_count: EntryCount::Direct,
+                cache: FilterCache::default(),
             })
         );
     }
@@ -397,12 +706,17 @@ mod tests {
             FileFilter::deduce(&mock_cli(vec!["--reverse"]), false),
             Ok(FileFilter {
                 flags: vec![FileFilterFlags::Reverse],
-                sort_field: SortField::default(),
+                sort_keys: vec![SortField::default().into()],
                 dot_filter: DotFilter::JustFiles,
                 ignore_patterns: IgnorePatterns::empty(),
+                only_patterns: IgnorePatterns::empty(),
                 git_ignore: GitIgnore::Off,
+                git_filter: GitFilter::Off,
+                limits: MetadataLimits::empty(),
                 no_symlinks: false,
                 show_symlinks: false,
+                entry_count: EntryCount::Direct,
+                cache: FilterCache::default(),
             })
         );
     }
@@ -413,12 +727,17 @@ mod tests {
             FileFilter::deduce(&mock_cli(vec!["--only-dirs"]), false),
             Ok(FileFilter {
                 flags: vec![FileFilterFlags::OnlyDirs],
-                sort_field: SortField::default(),
+                sort_keys: vec![SortField::default().into()],
                 dot_filter: DotFilter::JustFiles,
                 ignore_patterns: IgnorePatterns::empty(),
+                only_patterns: IgnorePatterns::empty(),
                 git_ignore: GitIgnore::Off,
+                git_filter: GitFilter::Off,
+                limits: MetadataLimits::empty(),
                 no_symlinks: false,
                 show_symlinks: false,
+                entry_count: EntryCount::Direct,
+                cache: FilterCache::default(),
             })
         );
     }
@@ -429,13 +748,128 @@ mod tests {
             FileFilter::deduce(&mock_cli(vec!["--only-files"]), false),
             Ok(FileFilter {
                 flags: vec![FileFilterFlags::OnlyFiles],
-                sort_field: SortField::default(),
+                sort_keys: vec![SortField::default().into()],
                 dot_filter: DotFilter::JustFiles,
                 ignore_patterns: IgnorePatterns::empty(),
+                only_patterns: IgnorePatterns::empty(),
                 git_ignore: GitIgnore::Off,
+                git_filter: GitFilter::Off,
+                limits: MetadataLimits::empty(),
                 no_symlinks: false,
                 show_symlinks: false,
+                entry_count: EntryCount::Direct,
+                cache: FilterCache::default(),
+            })
+        );
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn parse_sizes() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("10k"), Some(10_000));
+        assert_eq!(parse_size("10KB"), Some(10_000));
+        assert_eq!(parse_size("1.5M"), Some(1_500_000));
+        assert_eq!(parse_size("2Gi"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("1KiB"), Some(1024));
+        assert_eq!(parse_size("ten"), None);
+        assert_eq!(parse_size("10x"), None);
+    }
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(
+            parse_time("2d", &now()),
+            now().naive_utc().checked_sub_signed(TimeDelta::days(2))
+        );
+        assert_eq!(
+            parse_time("90min", &now()),
+            now().naive_utc().checked_sub_signed(TimeDelta::minutes(90))
+        );
+        assert_eq!(parse_time("d", &now()), None);
+        assert_eq!(parse_time("2 fortnights", &now()), None);
+    }
+
+    #[test]
+    fn parse_dates() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        assert_eq!(parse_time("2024-01-01", &now()), date.and_hms_opt(0, 0, 0));
+        assert_eq!(
+            parse_time("2024-01-01 08:30", &now()),
+            date.and_hms_opt(8, 30, 0)
+        );
+        assert_eq!(
+            parse_time("2024-01-01T08:30:15", &now()),
+            date.and_hms_opt(8, 30, 15)
+        );
+        assert_eq!(parse_time("2024-13-01", &now()), None);
+    }
+
+    #[test]
+    fn parse_dates_in_local_time() {
+        let now = FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 6, 15, 12, 0, 0)
+            .unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        assert_eq!(
+            parse_time("2024-01-01 08:30", &now),
+            date.and_hms_opt(6, 30, 0)
+        );
+        assert_eq!(
+            parse_time("2d", &now),
+            NaiveDate::from_ymd_opt(2024, 6, 13)
+                .unwrap()
+                .and_hms_opt(10, 0, 0)
+        );
+
+        let matches = mock_cli(vec!["--newer-than", "2024-06-15"]);
+        assert_eq!(
+            MetadataLimits::deduce(&matches, &now).map(|limits| limits.newer_than),
+            Ok(NaiveDate::from_ymd_opt(2024, 6, 14)
+                .unwrap()
+                .and_hms_opt(22, 0, 0))
+        );
+    }
+
+    #[test]
+    fn deduce_limits_empty() {
+        assert_eq!(
+            MetadataLimits::deduce(&mock_cli(vec![""]), &now()),
+            Ok(MetadataLimits::empty())
+        );
+    }
+
+    #[test]
+    fn deduce_limits() {
+        let matches = mock_cli(vec![
+            "--min-size",
+            "1k",
+            "--newer-than",
+            "1d",
+            "--filter-time",
+            "accessed",
+        ]);
+        assert_eq!(
+            MetadataLimits::deduce(&matches, &now()),
+            Ok(MetadataLimits {
+                min_size: Some(1000),
+                max_size: None,
+                time_type: TimeType::Accessed,
+                newer_than: now().naive_utc().checked_sub_signed(TimeDelta::days(1)),
+                older_than: None,
             })
         );
     }
+
+    #[test]
+    fn deduce_limits_error() {
+        assert_eq!(
+            MetadataLimits::deduce(&mock_cli(vec!["--older-than", "soon"]), &now()),
+            Err(OptionsError::BadLimit("older-than", "soon".into()))
+        );
+    }
 }
@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! Parsing the options for `GitOptions`.
+
+use clap::ArgMatches;
+
+use crate::fs::feature::GitOptions;
+use crate::options::{OptionsError, Vars, vars};
+
+impl GitOptions {
+    /// Determine what the Git cache should compare files against, and
+    /// whether it should report deleted files. In strict mode, a base
+    /// revision is an error unless the Git column or deleted files are shown.
+    pub fn deduce<V: Vars>(
+        matches: &ArgMatches,
+        vars: &V,
+        strict: bool,
+    ) -> Result<Self, OptionsError> {
+        let no_git_env = vars
+            .get_with_fallback(vars::EXA_OVERRIDE_GIT, vars::EZA_OVERRIDE_GIT)
+            .is_some();
+
+        let base = matches.get_one::<String>("git-base").cloned();
+        let deleted = matches.get_flag("git-deleted") && !matches.get_flag("no-git") && !no_git_env;
+
+        if strict && base.is_some() && !matches.get_flag("git") && !deleted {
+            return Err(OptionsError::Useless("git-base", false, "git"));
+        }
+
+        Ok(Self { base, deleted })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::options::parser::test::mock_cli;
+    use crate::options::vars::test::MockVars;
+
+    fn deduce(args: Vec<&str>, strict: bool) -> Result<GitOptions, OptionsError> {
+        GitOptions::deduce(&mock_cli(args), &MockVars::default(), strict)
+    }
+
+    #[test]
+    fn deduce_base_none() {
+        assert_eq!(deduce(vec!["--git"], true), Ok(GitOptions::default()));
+    }
+
+    #[test]
+    fn deduce_base() {
+        assert_eq!(
+            deduce(vec!["--git", "--git-base", "origin/main"], true),
+            Ok(GitOptions {
+                base: Some(String::from("origin/main")),
+                deleted: false,
+            })
+        );
+    }
+
+    #[test]
+    fn deduce_base_without_git() {
+        assert_eq!(
+            deduce(vec!["--git-base", "main"], false),
+            Ok(GitOptions {
+                base: Some(String::from("main")),
+                deleted: false,
+            })
+        );
+        assert_eq!(
+            deduce(vec!["--git-base", "main"], true),
+            Err(OptionsError::Useless("git-base", false, "git"))
+        );
+        assert!(deduce(vec!["--git-base", "main", "--git-deleted"], true).is_ok());
+    }
+
+    #[test]
+    fn deduce_deleted() {
+        assert_eq!(
+            deduce(vec!["--git-deleted"], true),
+            Ok(GitOptions {
+                base: None,
+                deleted: true,
+            })
+        );
+        assert_eq!(
+            deduce(vec!["--git-deleted", "--no-git"], true),
+            Ok(GitOptions::default())
+        );
+    }
+}
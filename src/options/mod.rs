@@ -77,7 +77,10 @@
 use clap::ArgMatches;
 
 use crate::fs::dir_action::DirAction;
-use crate::fs::filter::{FileFilter, GitIgnore};
+use crate::fs::feature::GitOptions;
+#[cfg(any(feature = "git", feature = "gix"))]
+use crate::fs::filter::SortField;
+use crate::fs::filter::{FileFilter, GitFilter};
 use crate::options::stdin::FilesInput;
 use crate::output::{Mode, View, details, grid_details};
 use crate::theme::Options as ThemeOptions;
@@ -86,6 +89,7 @@ mod dir_action;
 mod error;
 mod file_name;
 mod filter;
+mod git;
 #[rustfmt::skip] // this module becomes unreadable with rustfmt
 mod theme;
 mod view;
@@ -120,15 +124,39 @@ pub struct Options {
 
     /// Whether to read file names from stdin instead of the command-line
     pub stdin: FilesInput,
+
+    /// What the Git cache should compare files against.
+    pub git: GitOptions,
 }
 
 impl Options {
     /// Whether the View specified in this set of options includes a Git
-    /// status column. It’s only worth trying to discover a repository if the
-    /// results will end up being displayed.
+    /// status column, or deleted files are being listed. It’s only worth
+    /// trying to discover a repository if the results will end up being
+    /// displayed.
     #[must_use]
     pub fn should_scan_for_git(&self) -> bool {
-        if self.filter.git_ignore == GitIgnore::CheckAndIgnore {
+        if self.git.deleted || self.filter.git_filter != GitFilter::Off {
+            return true;
+        }
+
+        #[cfg(any(feature = "git", feature = "gix"))]
+        if self
+            .filter
+            .sort_keys
+            .iter()
+            .any(|key| matches!(key.field, SortField::GitStatus))
+        {
+            return true;
+        }
+
+        #[cfg(feature = "git")]
+        if self
+            .filter
+            .sort_keys
+            .iter()
+            .any(|key| matches!(key.field, SortField::CommitDate | SortField::CommitAuthor))
+        {
             return true;
         }
 
@@ -144,7 +172,8 @@ impl Options {
                         ..
                     },
                 ..
-            }) => table.columns.git,
+            }) => table.columns.git || table.columns.git_commit || table.columns.git_diffstat,
+            Mode::Json(opts) => opts.git,
             _ => false,
         }
     }
@@ -152,10 +181,39 @@ impl Options {
     /// Determines the complete set of options based on the given command-line
     /// arguments, after they’ve been parsed.
     pub fn deduce<V: Vars>(matches: &ArgMatches, vars: &V) -> Result<Self, OptionsError> {
-        if cfg!(not(feature = "git")) && (matches.get_flag("git") || matches.get_flag("git-ignore"))
-        {
+        if cfg!(not(any(feature = "git", feature = "gix"))) && matches.get_flag("git") {
+            return Err(OptionsError::Unsupported(String::from(
+                "Option --git can't be used because `git` and `gix` features were disabled in this build of exa",
+            )));
+        }
+        if cfg!(not(feature = "git")) && matches.contains_id("git-base") {
+            return Err(OptionsError::Unsupported(String::from(
+                "Option --git-base can't be used because `git` feature was disabled in this build of exa",
+            )));
+        }
+        if cfg!(not(any(feature = "git", feature = "gix"))) && matches.get_flag("git-deleted") {
+            return Err(OptionsError::Unsupported(String::from(
+                "Option --git-deleted can't be used because `git` and `gix` features were disabled in this build of exa",
+            )));
+        }
+        if cfg!(not(feature = "git")) && matches.get_flag("git-diffstat") {
+            return Err(OptionsError::Unsupported(String::from(
+                "Option --git-diffstat can't be used because `git` feature was disabled in this build of exa",
+            )));
+        }
+        if cfg!(not(feature = "git")) && matches.get_flag("git-commit") {
+            return Err(OptionsError::Unsupported(String::from(
+                "Option --git-commit can't be used because `git` feature was disabled in this build of exa",
+            )));
+        }
+        if cfg!(not(any(feature = "git", feature = "gix"))) && matches.get_flag("git-changed") {
+            return Err(OptionsError::Unsupported(String::from(
+                "Option --git-changed can't be used because `git` and `gix` features were disabled in this build of exa",
+            )));
+        }
+        if cfg!(not(any(feature = "git", feature = "gix"))) && matches.get_flag("git-untracked") {
             return Err(OptionsError::Unsupported(String::from(
-                "Options --git and --git-ignore can't be used because `git` feature was disabled in this build of exa",
+                "Option --git-untracked can't be used because `git` and `gix` features were disabled in this build of exa",
             )));
         }
         let strict = vars
@@ -163,10 +221,15 @@ impl Options {
             .is_some();
 
         let view = View::deduce(matches, vars, strict)?;
-        let dir_action = DirAction::deduce(matches, matches!(view.mode, Mode::Details(_)), strict)?;
+        let dir_action = DirAction::deduce(
+            matches,
+            matches!(view.mode, Mode::Details(_) | Mode::Json(_)),
+            strict,
+        )?;
         let filter = FileFilter::deduce(matches, strict)?;
         let theme = ThemeOptions::deduce(matches, vars);
         let stdin = FilesInput::deduce(matches, vars);
+        let git = GitOptions::deduce(matches, vars, strict)?;
 
         Ok(Self {
             dir_action,
@@ -174,6 +237,7 @@ impl Options {
             view,
             theme,
             stdin,
+            git,
         })
     }
 }
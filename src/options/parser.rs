@@ -6,21 +6,28 @@
 // SPDX-License-Identifier: MIT
 use std::ffi::OsString;
 
-use clap::{Error, ValueEnum, arg, builder::PossibleValue, value_parser};
+use clap::{ArgGroup, Error, ValueEnum, arg, builder::PossibleValue, value_parser};
 
 use crate::{
-    fs::filter::{SortCase, SortField},
+    fs::filter::{SortCase, SortField, SortKey},
     output::{file_name::Absolute, time::TimeFormat},
 };
 
-const SORT_FIELDS_HELP: &str = "[default: name] [possible values:
+const SORT_FIELDS_HELP: &str = "[default: name] [comma-separated list,
+  with a leading '-' for descending order, of:
   name, Name, .name, .Name, ext, ext, created,
   date, age, accessed, changed,
-  size, inode, type, none]";
+  size, inode, type, version, git,
+  committed, author, none]";
 
 const TIME_FIELDS_HELP: &str = "[possible values:
   mod|modified, acc|accessed, ch|changed, cr|created]";
 
+const COLUMNS_HELP: &str = "[comma-separated list of:
+  inode, octal, perms, links, size, usage, entries, blocksize, user, group,
+  flags, context, mtime, ctime, atime, btime, git, repo,
+  repo-no-status, diffstat, committed, author, commit, name]";
+
 const FORMAT_STYLE_FIELDS_HELP: &str = "[possible values:
   default, iso, long-iso, full-iso, relative, \"+<CUSTOM_FORMAT>\"]";
 
@@ -37,6 +44,8 @@ pub fn get_command() -> clap::Command {
 
         .next_help_heading("META OPTIONS")
         .arg(arg!(--stdin "read file names from stdin"))
+        .arg(arg!(--"no-config" "ignore the config file"))
+        .arg(arg!(--profile <NAME> "use the named profile from the config file"))
         .arg(arg!(-'?' --help "Print help").action(clap::ArgAction::HelpShort))
         .arg(arg!(-v --version "Print help").action(clap::ArgAction::Version))
 
@@ -47,6 +56,11 @@ pub fn get_command() -> clap::Command {
         .arg(arg!(-x --across "sort the grid across, rather than downwards"))
         .arg(arg!(-R --recurse "recurse into directories"))
         .arg(arg!(-T --tree "recurse into directories as a tree"))
+        .arg(arg!(--json "display each entry as a JSON object, one per line"))
+        .arg(arg!(--csv "export the long view as comma-separated values")
+            .overrides_with("tsv"))
+        .arg(arg!(--tsv "export the long view as tab-separated values")
+            .overrides_with("csv"))
         .arg(arg!(-L --level <DEPTH> "limit the depth of recursion")
             .value_parser(value_parser!(usize)))
         .arg(arg!(--"follow-symlinks" "drill down into symbolic links that point to directories"))
@@ -90,6 +104,7 @@ pub fn get_command() -> clap::Command {
             .value_parser(value_parser!(ShowWhen))
             .default_missing_value("auto"))
         .arg(arg!(--"no-quotes" "don't quote file names with spaces"))
+        .arg(arg!(--summary "add up the entries of each listed directory in a footer"))
 
         .next_help_heading("FILTERING OPTIONS")
         .arg(arg!(-a --all... "show hidden files. Use this twice to also show the '.' and '..' directories"))
@@ -102,14 +117,26 @@ pub fn get_command() -> clap::Command {
         .arg(arg!(--"show-symlinks" "explicitly show symbolic links (with --only-dirs and --only-files)"))
         .arg(arg!(--"no-symlinks" "do not show symbolic links"))
         .arg(arg!(-I --"ignore-glob" <GLOBS> "glob patterns (pipe-separated) of files to ignore"))
-        .arg(arg!(--"git-ignore" "ignore files mentioned in '.gitignore'"))
+        .arg(arg!(--"only-glob" <GLOBS> "glob patterns (pipe-separated) of the only files to show"))
+        .arg(arg!(--"prune-dirs" "hide directories without files matching --only-glob when recursing"))
+        .arg(arg!(--"git-ignore" "ignore files mentioned in '.gitignore', '.ignore', and '.fdignore'"))
+        .arg(arg!(--"git-changed" "only show files with staged or unstaged Git changes"))
+        .arg(arg!(--"git-untracked" "only show files that Git doesn't track yet"))
+        .arg(arg!(--"min-size" <SIZE> "hide files smaller than SIZE (e.g. 512, 10k, 1.5M, 2Gi)"))
+        .arg(arg!(--"max-size" <SIZE> "hide files larger than SIZE"))
+        .arg(arg!(--"newer-than" <WHEN> "hide files older than a duration (30min, 2d, 1w) or a date (2024-01-01)"))
+        .arg(arg!(--"older-than" <WHEN> "hide files newer than a duration or a date"))
+        .arg(arg!(--"filter-time" <FIELD> "which timestamp --newer-than and --older-than compare (default: modified)")
+            .value_parser(value_parser!(TimeArgs)))
 
         .next_help_heading("SORTING OPTIONS")
         .arg(arg!(--"group-directories-first" "list directories before other files").id("dirs-first"))
         .arg(arg!(--"group-directories-last" "list directories after other files").id("dirs-last"))
-        .arg(arg!(-s --sort <FIELD>)
-            .help(format!("which field to sort by {SORT_FIELDS_HELP}"))
-            .value_parser(value_parser!(SortField))
+        .arg(arg!(-s --sort <FIELDS>)
+            .help(format!("which fields to sort by {SORT_FIELDS_HELP}"))
+            .value_parser(parse_sort_key)
+            .value_delimiter(',')
+            .allow_hyphen_values(true)
             .default_value("name")
             .hide_default_value(true)
             .hide_possible_values(true))
@@ -122,8 +149,25 @@ pub fn get_command() -> clap::Command {
         .arg(arg!(-H --links "list each file's number of hard links"))
         .arg(arg!(-b --binary "show file sizes with binary prefixes"))
         .arg(arg!(-B --bytes "show file sizes in bytes, without any prefixes"))
+        .arg(arg!(--"size-unit" <UNIT> "show file sizes in a fixed unit (B, k, M, G, T, P, E, or Ki, Mi, ...)")
+            .value_parser(parse_size_unit))
+        .arg(arg!(--"size-precision" <DIGITS> "show file sizes with this many decimal places")
+            .value_parser(value_parser!(usize)))
+        .arg(arg!(--"size-suffix" <STYLE> "how to spell size units (short, si, iec)")
+            .value_parser(value_parser!(SizeSuffixArgs))
+            .hide_possible_values(true))
+        .arg(arg!(--"size-separators" <WHEN> "when to group the digits of file sizes into thousands")
+            .value_parser(value_parser!(ShowWhen)))
         .arg(arg!(--"total-size" "show the size of a directory as the one of its content (unix only)"))
+        .arg(arg!(--"one-file-system" "don't count other mounted filesystems in --total-size"))
         .arg(arg!(-S --blocksize "list size of allocated file system blocks"))
+        .arg(arg!(--usage "show each file's share of its directory's size, with a bar"))
+        .arg(arg!(--entries <WHAT> "show how many entries each directory has (direct, recursive)")
+            .num_args(0..=1)
+            .require_equals(true)
+            .value_parser(value_parser!(EntriesArgs))
+            .default_missing_value("direct")
+            .hide_possible_values(true))
         .arg(arg!(-g --group "list each file's group"))
         .arg(arg!(--"smart-group" "only show group if it has a different name from owner"))
         .arg(arg!(-n --numeric "show user and group as their numeric IDs"))
@@ -142,6 +186,10 @@ pub fn get_command() -> clap::Command {
         .arg(arg!(-O --flags "list file flags (Mac, BSD, and Windows only)").id("file-flags"))
         .arg(arg!(-Z --context "list each file's security context").id("security-context"))
         .arg(arg!(--git "list each file's Git status, if tracked or ignored"))
+        .arg(arg!(--"git-base" <REV> "compare Git statuses against REV instead of HEAD"))
+        .arg(arg!(--"git-deleted" "list tracked files that have been deleted from the working tree"))
+        .arg(arg!(--"git-diffstat" "list the number of lines added and removed in each file"))
+        .arg(arg!(--"git-commit" "list the date, author and hash of each file's last commit"))
         .arg(arg!(--"git-repos" "list root of git-tree status"))
         .arg(arg!(--"git-repos-no-status" "list each git-repos branch name (much faster)"))
         .arg(arg!(-M --mounts "show mount details (Linux and macOS only)"))
@@ -150,7 +198,14 @@ pub fn get_command() -> clap::Command {
         .arg(arg!(--"no-filesize" "suppress the filesize field"))
         .arg(arg!(--"no-user" "suppress the user field"))
         .arg(arg!(--"no-time" "suppress the time field"))
-        .arg(arg!(--"no-git" "suppress Git fields (overrides --git, --git-repos, --git-repos-no-status)"))
+        .arg(arg!(--"no-git" "suppress Git fields (overrides --git, --git-commit, --git-deleted, --git-diffstat, --git-repos, --git-repos-no-status)"))
+        .arg(arg!(--columns <COLUMNS>).help(format!("which columns to show, in order {COLUMNS_HELP}"))
+            .value_parser(value_parser!(String)))
+
+        // Options that pick one of several alternatives, so one given on the
+        // command line replaces the others when they come from the config file.
+        .group(ArgGroup::new("layout").args(["oneline", "long"]).multiple(true))
+        .group(ArgGroup::new("export").args(["json", "csv", "tsv"]).multiple(true))
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -192,6 +247,13 @@ pub enum ColorScaleArgs {
     All,
     Age,
     Size,
+    Entries,
+}
+
+#[derive(Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum EntriesArgs {
+    Direct,
+    Recursive,
 }
 
 #[derive(Clone, Debug, ValueEnum, PartialEq, Eq)]
@@ -208,6 +270,7 @@ impl ValueEnum for SortField {
             Self::NameMixHidden(SortCase::AaBbCc),
             Self::NameMixHidden(SortCase::ABCabc),
             Self::Size,
+            Self::Entries,
             Self::Extension(SortCase::AaBbCc),
             Self::Extension(SortCase::ABCabc),
             Self::ModifiedDate,
@@ -218,6 +281,13 @@ impl ValueEnum for SortField {
             #[cfg(unix)]
             Self::FileInode,
             Self::FileType,
+            Self::Version,
+            #[cfg(any(feature = "git", feature = "gix"))]
+            Self::GitStatus,
+            #[cfg(feature = "git")]
+            Self::CommitDate,
+            #[cfg(feature = "git")]
+            Self::CommitAuthor,
             Self::Unsorted,
         ]
     }
@@ -228,7 +298,8 @@ impl ValueEnum for SortField {
             Self::Name(SortCase::ABCabc) => PossibleValue::new("Name").alias("Filename"),
             Self::NameMixHidden(SortCase::AaBbCc) => PossibleValue::new(".name").alias(".filename"),
             Self::NameMixHidden(SortCase::ABCabc) => PossibleValue::new(".Name").alias(".Filename"),
-            Self::Size => PossibleValue::new("size"),
+            Self::Size => PossibleValue::new("size").alias("usage"),
+            Self::Entries => PossibleValue::new("entries").alias("count"),
             Self::Extension(SortCase::AaBbCc) => PossibleValue::new("ext").alias("extension"),
             Self::Extension(SortCase::ABCabc) => PossibleValue::new("Ext").alias("Extension"),
             // “new” sorts oldest at the top and newest at the bottom; “old” sorts newest at the
@@ -246,11 +317,78 @@ impl ValueEnum for SortField {
             #[cfg(unix)]
             Self::FileInode => PossibleValue::new("inode"),
             Self::FileType => PossibleValue::new("type"),
+            Self::Version => PossibleValue::new("version").alias("v"),
+            #[cfg(any(feature = "git", feature = "gix"))]
+            Self::GitStatus => PossibleValue::new("git").alias("git-status"),
+            #[cfg(feature = "git")]
+            Self::CommitDate => PossibleValue::new("committed").alias("commit-date"),
+            #[cfg(feature = "git")]
+            Self::CommitAuthor => PossibleValue::new("author"),
             Self::Unsorted => PossibleValue::new("none"),
         })
     }
 }
 
+/// Parses one key of the `--sort` list: a sort field, optionally preceded by
+/// a `-` to sort by it in descending order.
+fn parse_sort_key(input: &str) -> Result<SortKey, String> {
+    let (name, descending) = match input.strip_prefix('-') {
+        Some(name) => (name, true),
+        None => (input, false),
+    };
+
+    let field =
+        SortField::from_str(name, false).map_err(|_| format!("unknown sort field {name:?}"))?;
+    Ok(SortKey { field, descending })
+}
+
+/// A unit given to `--size-unit`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizeUnitArgs {
+    /// Plain bytes, without a prefix.
+    Bytes,
+
+    /// The prefix for 1000 or 1024 to the given power. It’s only known to be
+    /// binary if it was spelt with an `i`, such as `Mi`.
+    Prefix { power: u8, binary: bool },
+}
+
+/// Parses a `--size-unit`: either `B`, or a prefix letter from `k` to `E`
+/// that may be followed by an `i` for the binary prefix, and then by a `B`.
+fn parse_size_unit(input: &str) -> Result<SizeUnitArgs, String> {
+    let unknown = || format!("unknown size unit {input:?}");
+
+    if input == "B" {
+        return Ok(SizeUnitArgs::Bytes);
+    }
+
+    let mut chars = input.chars();
+    let letter = chars.next().ok_or_else(unknown)?;
+    let power = "KMGTPE"
+        .find(letter.to_ascii_uppercase())
+        .ok_or_else(unknown)?;
+
+    let rest = chars.as_str();
+    let rest = rest.strip_suffix('B').unwrap_or(rest);
+    let binary = match rest {
+        "" => false,
+        "i" => true,
+        _ => return Err(unknown()),
+    };
+
+    Ok(SizeUnitArgs::Prefix {
+        power: power as u8 + 1,
+        binary,
+    })
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+pub enum SizeSuffixArgs {
+    Short,
+    Si,
+    Iec,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TimeArgs {
     Modified,
@@ -346,4 +484,41 @@ pub mod test {
             ["file1", "file2"]
         );
     }
+
+    #[test]
+    fn parse_size_units() {
+        use SizeUnitArgs::*;
+        assert_eq!(parse_size_unit("B"), Ok(Bytes));
+        assert_eq!(
+            parse_size_unit("k"),
+            Ok(Prefix {
+                power: 1,
+                binary: false
+            })
+        );
+        assert_eq!(
+            parse_size_unit("MB"),
+            Ok(Prefix {
+                power: 2,
+                binary: false
+            })
+        );
+        assert_eq!(
+            parse_size_unit("Gi"),
+            Ok(Prefix {
+                power: 3,
+                binary: true
+            })
+        );
+        assert_eq!(
+            parse_size_unit("EiB"),
+            Ok(Prefix {
+                power: 6,
+                binary: true
+            })
+        );
+        assert!(parse_size_unit("").is_err());
+        assert!(parse_size_unit("X").is_err());
+        assert!(parse_size_unit("Mx").is_err());
+    }
 }
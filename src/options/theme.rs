@@ -37,7 +37,7 @@ impl Options {
 
 impl ThemeConfig {
     fn deduce<V: Vars>(vars: &V) -> Option<Self> {
-        if let Some(path) = vars.get("EZA_CONFIG_DIR") {
+        if let Some(path) = vars.get(vars::EZA_CONFIG_DIR) {
             let path = PathBuf::from(path);
             let theme = path.join("theme.yml");
             if theme.exists() {
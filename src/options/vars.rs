@@ -72,6 +72,14 @@ pub static EZA_ICONS_AUTO: &str = "EZA_ICONS_AUTO";
 
 pub static EZA_STDIN_SEPARATOR: &str = "EZA_STDIN_SEPARATOR";
 
+/// Environment variable used to choose the directory holding the `config.yml`
+/// and `theme.yml` files, instead of `$XDG_CONFIG_HOME/eza`.
+pub static EZA_CONFIG_DIR: &str = "EZA_CONFIG_DIR";
+
+/// Environment variable used to select a profile from the config file when
+/// `--profile` isn’t given.
+pub static EZA_PROFILE: &str = "EZA_PROFILE";
+
 /// Environment variable used to choose how windows attributes are displayed.
 /// Short will display a single character for each set attribute, long will
 /// display a comma separated list of descriptions.
@@ -120,6 +128,8 @@ pub mod test {
         pub luminance: OsString,
         pub icons: OsString,
         pub time: OsString,
+        pub override_git: OsString,
+        pub profile: OsString,
     }
 
     impl Vars for MockVars {
@@ -143,6 +153,10 @@ pub mod test {
                 "COLUMNS" if !self.columns.is_empty() => Some(self.columns.clone()),
                 "NO_COLOR" if !self.no_colors.is_empty() => Some(self.no_colors.clone()),
                 "TIME_STYLE" if !self.time.is_empty() => Some(self.time.clone()),
+                "EXA_OVERRIDE_GIT" | "EZA_OVERRIDE_GIT" if !self.override_git.is_empty() => {
+                    Some(self.override_git.clone())
+                }
+                "EZA_PROFILE" if !self.profile.is_empty() => Some(self.profile.clone()),
                 _ => None,
             }
         }
@@ -161,6 +175,8 @@ pub mod test {
                 "COLUMNS" => self.columns = value.clone(),
                 "NO_COLOR" => self.no_colors = value.clone(),
                 "TIME_STYLE" => self.time = value.clone(),
+                "EXA_OVERRIDE_GIT" | "EZA_OVERRIDE_GIT" => self.override_git = value.clone(),
+                "EZA_PROFILE" => self.profile = value.clone(),
                 _ => (),
             };
         }
@@ -9,19 +9,25 @@ use clap::{ArgMatches, ValueEnum};
 use crate::output::TerminalWidth::Automatic;
 
 use crate::fs::feature::xattr;
+use crate::fs::recursive_size::TotalSize;
 use crate::options::parser::ColorScaleModeArgs;
 use crate::options::{NumberSource, OptionsError, Vars, vars};
 use crate::output::TerminalWidth::Set;
 use crate::output::color_scale::{ColorScaleMode, ColorScaleOptions};
+use crate::output::details::Delimiter;
 use crate::output::file_name::Options as FileStyle;
 use crate::output::grid_details::{self, RowThreshold};
+use crate::output::render::prefix_for_power;
+#[cfg(feature = "git")]
+use crate::output::table::CommitField;
 use crate::output::table::{
-    Columns, FlagsFormat, GroupFormat, Options as TableOptions, SizeFormat, TimeTypes, UserFormat,
+    Column, Columns, FlagsFormat, GroupFormat, Options as TableOptions, SizeFormat, SizeOptions,
+    SizeSuffix, ThousandsSeparators, TimeType, TimeTypes, UserFormat,
 };
 use crate::output::time::TimeFormat;
-use crate::output::{Mode, TerminalWidth, View, details, grid};
+use crate::output::{Mode, TerminalWidth, View, details, grid, json, summary};
 
-use super::parser::{ColorScaleArgs, TimeArgs};
+use super::parser::{ColorScaleArgs, ShowWhen, SizeSuffixArgs, SizeUnitArgs, TimeArgs};
 
 impl View {
     pub fn deduce<V: Vars>(
@@ -34,8 +40,11 @@ impl View {
         let mode = Mode::deduce(matches, vars, is_tty, strict)?;
         let deref_links = matches.get_flag("dereference");
         let follow_links = matches.get_flag("follow-symlinks");
-        let total_size = matches.get_flag("total-size");
+        let total_size = TotalSize::deduce(matches, mode.has_usage(), strict)?;
         let file_style = FileStyle::deduce(matches, vars, is_tty)?;
+        let summary = matches.get_flag("summary").then(|| summary::Options {
+            size: SizeOptions::deduce(matches),
+        });
         Ok(Self {
             mode,
             width,
@@ -43,6 +52,44 @@ impl View {
             deref_links,
             follow_links,
             total_size,
+            summary,
+        })
+    }
+}
+
+impl Mode {
+    /// Whether the usage column is being shown.
+    fn has_usage(&self) -> bool {
+        let details = match self {
+            Self::Details(details) => details,
+            Self::GridDetails(grid_details) => &grid_details.details,
+            _ => return false,
+        };
+
+        details.table.as_ref().is_some_and(|t| t.columns.usage)
+    }
+}
+
+impl TotalSize {
+    /// The usage column turns this on, as a file’s share of its directory
+    /// would leave out the directories listed alongside it otherwise.
+    fn deduce(matches: &ArgMatches, usage: bool, strict: bool) -> Result<Self, OptionsError> {
+        let one_file_system = matches.get_flag("one-file-system");
+        if !matches.get_flag("total-size") && !usage {
+            if strict && one_file_system {
+                return Err(OptionsError::Useless(
+                    "one-file-system",
+                    false,
+                    "total-size",
+                ));
+            }
+            return Ok(Self::Off);
+        }
+
+        Ok(if one_file_system {
+            Self::OneFileSystem
+        } else {
+            Self::All
         })
     }
 }
@@ -67,6 +114,19 @@ impl Mode {
         let grid = matches.get_flag("grid");
         let tree = matches.get_flag("tree");
 
+        if matches.get_flag("json") {
+            return Ok(Self::Json(json::Options::deduce(matches, vars)));
+        }
+
+        if let Some(delimiter) = Delimiter::deduce(matches) {
+            let details = details::Options::deduce_long(matches, vars, strict)?;
+            return Ok(Self::Details(details::Options {
+                header: true,
+                delimiter: Some(delimiter),
+                ..details
+            }));
+        }
+
         if !long && strict {
             Self::strict_check_long_flags(matches)?;
         }
@@ -123,6 +183,7 @@ impl Mode {
             "group",
             "numeric",
             "mounts",
+            "columns",
         ] {
             if matches.contains_id(flag) {
                 return Err(OptionsError::Useless(flag, false, "long"));
@@ -131,6 +192,12 @@ impl Mode {
 
         if matches.get_flag("git") && !matches.get_flag("no-git") {
             return Err(OptionsError::Useless("git", false, "long"));
+        } else if matches.get_flag("git-commit") && !matches.get_flag("no-git") {
+            return Err(OptionsError::Useless("git-commit", false, "long"));
+        } else if matches.get_flag("git-diffstat") && !matches.get_flag("no-git") {
+            return Err(OptionsError::Useless("git-diffstat", false, "long"));
+        } else if matches.get_flag("usage") {
+            return Err(OptionsError::Useless("usage", false, "long"));
         } else if matches.contains_id("level")
             && !matches.get_flag("recurse")
             && !matches.get_flag("tree")
@@ -142,6 +209,30 @@ impl Mode {
     }
 }
 
+impl json::Options {
+    fn deduce<V: Vars>(matches: &ArgMatches, vars: &V) -> Self {
+        let no_git_env = vars
+            .get_with_fallback(vars::EXA_OVERRIDE_GIT, vars::EZA_OVERRIDE_GIT)
+            .is_some();
+
+        json::Options {
+            git: matches.get_flag("git") && !matches.get_flag("no-git") && !no_git_env,
+        }
+    }
+}
+
+impl Delimiter {
+    fn deduce(matches: &ArgMatches) -> Option<Self> {
+        if matches.get_flag("csv") {
+            Some(Self::Comma)
+        } else if matches.get_flag("tsv") {
+            Some(Self::Tab)
+        } else {
+            None
+        }
+    }
+}
+
 impl grid::Options {
     fn deduce(matches: &ArgMatches) -> Self {
         grid::Options {
@@ -158,8 +249,12 @@ impl details::Options {
             xattr: xattr::ENABLED && matches.get_flag("extended"),
             secattr: xattr::ENABLED && matches.get_flag("security-context"),
             mounts: matches.get_flag("mounts"),
-            color_scale: ColorScaleOptions::deduce(matches, vars),
+            color_scale: ColorScaleOptions {
+                entries: false,
+                ..ColorScaleOptions::deduce(matches, vars)
+            },
             follow_links: matches.get_flag("follow-symlinks"),
+            delimiter: None,
         }
     }
 
@@ -176,14 +271,22 @@ impl details::Options {
             }
         }
 
+        let table = TableOptions::deduce(matches, vars)?;
+
+        // Scaling the entry counts means counting the entries of every
+        // directory, so it’s only done when they’re being shown.
+        let mut color_scale = ColorScaleOptions::deduce(matches, vars);
+        color_scale.entries &= table.columns.entries;
+
         Ok(details::Options {
-            table: Some(TableOptions::deduce(matches, vars)?),
+            table: Some(table),
             header: matches.get_flag("header"),
             xattr: xattr::ENABLED && matches.get_flag("extended"),
             secattr: xattr::ENABLED && matches.get_flag("security-context"),
             mounts: matches.get_flag("mounts"),
-            color_scale: ColorScaleOptions::deduce(matches, vars),
+            color_scale,
             follow_links: matches.get_flag("follow-symlinks"),
+            delimiter: None,
         })
     }
 }
@@ -236,12 +339,12 @@ impl TableOptions {
     fn deduce<V: Vars>(matches: &ArgMatches, vars: &V) -> Result<Self, OptionsError> {
         let time_format = TimeFormat::deduce(matches, vars);
         let flags_format = FlagsFormat::deduce(vars);
-        let size_format = SizeFormat::deduce(matches);
+        let size = SizeOptions::deduce(matches);
         let user_format = UserFormat::deduce(matches);
         let group_format = GroupFormat::deduce(matches);
         let columns = Columns::deduce(matches, vars)?;
         Ok(Self {
-            size_format,
+            size,
             time_format,
             user_format,
             group_format,
@@ -266,8 +369,14 @@ impl Columns {
             && matches.get_flag("git-repos-no-status")
             && !matches.get_flag("no-git")
             && !no_git_env;
+        let git_commit =
+            matches.get_flag("git-commit") && !matches.get_flag("no-git") && !no_git_env;
+        let git_diffstat =
+            matches.get_flag("git-diffstat") && !matches.get_flag("no-git") && !no_git_env;
 
         let file_flags = matches.get_flag("file-flags");
+        let usage = matches.get_flag("usage");
+        let entries = matches.contains_id("entries");
         let blocksize = matches.get_flag("blocksize");
         let group = matches.get_flag("group");
         let inode = matches.get_flag("inode");
@@ -279,6 +388,68 @@ impl Columns {
         let filesize = !matches.get_flag("no-filesize");
         let user = !matches.get_flag("no-user");
 
+        if let Some(list) = matches.get_one::<String>("columns") {
+            let order = Self::deduce_order(list)?;
+            let has = |column| order.contains(&column);
+            let git_allowed = !matches.get_flag("no-git") && !no_git_env;
+            #[cfg(feature = "git")]
+            let has_commit = order.iter().any(|c| matches!(c, Column::LastCommit(_)));
+            #[cfg(not(feature = "git"))]
+            let has_commit = false;
+            #[cfg(feature = "git")]
+            let has_diffstat = has(Column::GitDiffStat);
+            #[cfg(not(feature = "git"))]
+            let has_diffstat = false;
+
+            return Ok(Self {
+                time_types: TimeTypes {
+                    modified: has(Column::Timestamp(TimeType::Modified)),
+                    changed: has(Column::Timestamp(TimeType::Changed)),
+                    accessed: has(Column::Timestamp(TimeType::Accessed)),
+                    created: has(Column::Timestamp(TimeType::Created)),
+                },
+                #[cfg(unix)]
+                inode: has(Column::Inode),
+                #[cfg(unix)]
+                links: has(Column::HardLinks),
+                #[cfg(unix)]
+                blocksize: has(Column::Blocksize),
+                #[cfg(unix)]
+                group: has(Column::Group),
+                #[cfg(windows)]
+                inode,
+                #[cfg(windows)]
+                links,
+                #[cfg(windows)]
+                blocksize,
+                #[cfg(windows)]
+                group,
+                git: git_allowed && has(Column::GitStatus),
+                subdir_git_repos: git_allowed && has(Column::SubdirGitRepo(true)),
+                subdir_git_repos_no_stat: git_allowed && has(Column::SubdirGitRepo(false)),
+                git_commit: git_allowed && has_commit,
+                git_diffstat: git_allowed && has_diffstat,
+                #[cfg(unix)]
+                octal: has(Column::Octal),
+                #[cfg(windows)]
+                octal,
+                #[cfg(unix)]
+                security_context: has(Column::SecurityContext),
+                #[cfg(windows)]
+                security_context,
+                file_flags: has(Column::FileFlags),
+                usage: has(Column::Usage),
+                entries: has(Column::Entries),
+                permissions: has(Column::Permissions),
+                filesize: has(Column::FileSize),
+                #[cfg(unix)]
+                user: has(Column::User),
+                #[cfg(windows)]
+                user,
+                order: Some(order),
+            });
+        }
+
         Ok(Self {
             time_types,
             inode,
@@ -288,14 +459,77 @@ impl Columns {
             git,
             subdir_git_repos,
             subdir_git_repos_no_stat,
+            git_commit,
+            git_diffstat,
             octal,
             security_context,
             file_flags,
+            usage,
+            entries,
             permissions,
             filesize,
             user,
+            order: None,
         })
     }
+
+    /// Determine which columns to show, and in which order, from the
+    /// comma-separated list given to `--columns`.
+    ///
+    /// The file name is always drawn last, so `name` is only accepted as the
+    /// final entry. Time columns can be listed more than once.
+    fn deduce_order(list: &str) -> Result<Vec<Column>, OptionsError> {
+        let names = list.split(',').map(str::trim).collect::<Vec<_>>();
+        let mut columns = Vec::with_capacity(names.len());
+
+        for (index, &name) in names.iter().enumerate() {
+            #[rustfmt::skip]
+            let column = match name {
+                "name" if index + 1 == names.len() => continue,
+                "name" => return Err(OptionsError::Unsupported(
+                    "The name column can only come last in --columns".into()
+                )),
+                #[cfg(unix)]
+                "inode"                        => Column::Inode,
+                #[cfg(unix)]
+                "octal"                        => Column::Octal,
+                "perms" | "permissions"        => Column::Permissions,
+                #[cfg(unix)]
+                "links"                        => Column::HardLinks,
+                "size"                         => Column::FileSize,
+                "usage"                        => Column::Usage,
+                "entries"                      => Column::Entries,
+                #[cfg(unix)]
+                "blocksize"                    => Column::Blocksize,
+                #[cfg(unix)]
+                "user"                         => Column::User,
+                #[cfg(unix)]
+                "group"                        => Column::Group,
+                "flags"                        => Column::FileFlags,
+                #[cfg(unix)]
+                "context"                      => Column::SecurityContext,
+                "mtime" | "modified"           => Column::Timestamp(TimeType::Modified),
+                "ctime" | "changed"            => Column::Timestamp(TimeType::Changed),
+                "atime" | "accessed"           => Column::Timestamp(TimeType::Accessed),
+                "btime" | "created"            => Column::Timestamp(TimeType::Created),
+                "git"                          => Column::GitStatus,
+                "repo"                         => Column::SubdirGitRepo(true),
+                "repo-no-status"               => Column::SubdirGitRepo(false),
+                #[cfg(feature = "git")]
+                "diffstat"                     => Column::GitDiffStat,
+                #[cfg(feature = "git")]
+                "committed" | "commit-date"    => Column::LastCommit(CommitField::Time),
+                #[cfg(feature = "git")]
+                "author"                       => Column::LastCommit(CommitField::Author),
+                #[cfg(feature = "git")]
+                "commit" | "hash"              => Column::LastCommit(CommitField::Hash),
+                _ => return Err(OptionsError::UnknownColumn(name.to_string())),
+            };
+            columns.push(column);
+        }
+
+        Ok(columns)
+    }
 }
 
 impl SizeFormat {
@@ -319,6 +553,57 @@ impl SizeFormat {
     }
 }
 
+impl SizeOptions {
+    /// Determine how to display file sizes. The `si` and `iec` suffixes
+    /// pick decimal or binary prefixes as well as spelling them out, and a
+    /// fixed unit overrides `--bytes`, using the binary prefix when it’s
+    /// spelt with an `i` or when one of the other options asks for it.
+    fn deduce(matches: &ArgMatches) -> Self {
+        let suffix_args = matches.get_one::<SizeSuffixArgs>("size-suffix");
+        let binary = match suffix_args {
+            Some(SizeSuffixArgs::Si) => false,
+            Some(SizeSuffixArgs::Iec) => true,
+            Some(SizeSuffixArgs::Short) | None => matches.get_flag("binary"),
+        };
+
+        let mut format = match SizeFormat::deduce(matches) {
+            SizeFormat::JustBytes => SizeFormat::JustBytes,
+            _ if binary => SizeFormat::BinaryBytes,
+            _ => SizeFormat::DecimalBytes,
+        };
+
+        let unit = match matches.get_one::<SizeUnitArgs>("size-unit") {
+            Some(SizeUnitArgs::Bytes) => {
+                format = SizeFormat::JustBytes;
+                None
+            }
+            Some(&SizeUnitArgs::Prefix { power, binary: i }) => {
+                prefix_for_power(power, i || binary)
+            }
+            None => None,
+        };
+
+        let suffix = match suffix_args {
+            Some(SizeSuffixArgs::Si | SizeSuffixArgs::Iec) => SizeSuffix::Long,
+            Some(SizeSuffixArgs::Short) | None => SizeSuffix::Short,
+        };
+
+        let separators = match matches.get_one::<ShowWhen>("size-separators") {
+            Some(ShowWhen::Always) => ThousandsSeparators::Always,
+            Some(ShowWhen::Never) => ThousandsSeparators::Never,
+            Some(ShowWhen::Auto) | None => ThousandsSeparators::Locale,
+        };
+
+        Self {
+            format,
+            unit,
+            precision: matches.get_one::<usize>("size-precision").copied(),
+            suffix,
+            separators,
+        }
+    }
+}
+
 const FORMAT_STYLE_FIELDS: [&str; 6] = [
     "default",
     "iso",
@@ -519,6 +804,7 @@ impl ColorScaleOptions {
             min_luminance,
             size: false,
             age: false,
+            entries: false,
         };
 
         let Some(words) = matches.get_many("color-scale") else {
@@ -530,6 +816,7 @@ impl ColorScaleOptions {
                 ColorScaleArgs::All => {
                     options.size = true;
                     options.age = true;
+                    options.entries = true;
                 }
                 ColorScaleArgs::Age => {
                     options.age = true;
@@ -537,6 +824,9 @@ impl ColorScaleOptions {
                 ColorScaleArgs::Size => {
                     options.size = true;
                 }
+                ColorScaleArgs::Entries => {
+                    options.entries = true;
+                }
             }
         }
 
@@ -550,9 +840,147 @@ mod tests {
     use crate::options::vars::test::MockVars;
     use std::ffi::OsString;
     use std::num::ParseIntError;
+    use unit_prefix::Prefix;
 
     use super::*;
 
+    #[test]
+    fn deduce_columns_in_given_order() {
+        let columns = Columns::deduce(
+            &mock_cli(vec!["--columns", "size,mtime,perms,mtime,name"]),
+            &MockVars::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            columns.collect(false, false),
+            vec![
+                Column::FileSize,
+                Column::Timestamp(TimeType::Modified),
+                Column::Permissions,
+                Column::Timestamp(TimeType::Modified),
+            ]
+        );
+    }
+
+    #[test]
+    fn deduce_columns_git_needs_repo() {
+        let cli = mock_cli(vec!["--columns", "git,size"]);
+        let columns = Columns::deduce(&cli, &MockVars::default()).unwrap();
+        assert!(columns.git);
+        assert_eq!(columns.collect(false, false), vec![Column::FileSize]);
+
+        let cli = mock_cli(vec!["--columns", "git,size", "--no-git"]);
+        let columns = Columns::deduce(&cli, &MockVars::default()).unwrap();
+        assert!(!columns.git);
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn deduce_columns_git_commit() {
+        let cli = mock_cli(vec!["--git-commit"]);
+        let columns = Columns::deduce(&cli, &MockVars::default()).unwrap();
+        assert!(columns.git_commit);
+        assert!(
+            !columns
+                .collect(false, false)
+                .contains(&Column::LastCommit(CommitField::Author))
+        );
+        assert!(columns.collect(true, false).ends_with(&[
+            Column::LastCommit(CommitField::Time),
+            Column::LastCommit(CommitField::Author),
+            Column::LastCommit(CommitField::Hash),
+        ]));
+
+        let cli = mock_cli(vec!["--columns", "commit,author,size"]);
+        let columns = Columns::deduce(&cli, &MockVars::default()).unwrap();
+        assert_eq!(
+            columns.collect(true, false),
+            vec![
+                Column::LastCommit(CommitField::Hash),
+                Column::LastCommit(CommitField::Author),
+                Column::FileSize,
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn deduce_columns_git_diffstat() {
+        let cli = mock_cli(vec!["--git-diffstat"]);
+        let columns = Columns::deduce(&cli, &MockVars::default()).unwrap();
+        assert!(columns.git_diffstat);
+        assert!(columns.collect(true, false).contains(&Column::GitDiffStat));
+
+        let cli = mock_cli(vec!["--columns", "diffstat,size", "--no-git"]);
+        let columns = Columns::deduce(&cli, &MockVars::default()).unwrap();
+        assert_eq!(columns.collect(true, false), vec![Column::FileSize]);
+    }
+
+    #[test]
+    fn deduce_columns_usage() {
+        let cli = mock_cli(vec!["--usage", "--no-user"]);
+        let columns = Columns::deduce(&cli, &MockVars::default()).unwrap();
+        assert_eq!(
+            columns.collect(false, false),
+            vec![
+                Column::Permissions,
+                Column::FileSize,
+                Column::Usage,
+                Column::Timestamp(TimeType::Modified),
+            ]
+        );
+
+        let cli = mock_cli(vec!["--columns", "usage,size"]);
+        let columns = Columns::deduce(&cli, &MockVars::default()).unwrap();
+        assert!(columns.usage);
+        assert_eq!(
+            columns.collect(false, false),
+            vec![Column::Usage, Column::FileSize]
+        );
+    }
+
+    #[test]
+    fn deduce_columns_entries() {
+        let cli = mock_cli(vec!["--entries=recursive", "--no-user", "--no-time"]);
+        let columns = Columns::deduce(&cli, &MockVars::default()).unwrap();
+        assert_eq!(
+            columns.collect(false, false),
+            vec![Column::Permissions, Column::FileSize, Column::Entries]
+        );
+
+        let cli = mock_cli(vec!["--columns", "name"]);
+        assert!(!Columns::deduce(&cli, &MockVars::default()).unwrap().entries);
+
+        let cli = mock_cli(vec!["--columns", "entries,size"]);
+        let columns = Columns::deduce(&cli, &MockVars::default()).unwrap();
+        assert_eq!(
+            columns.collect(false, false),
+            vec![Column::Entries, Column::FileSize]
+        );
+    }
+
+    #[test]
+    fn deduce_columns_unknown() {
+        assert_eq!(
+            Columns::deduce(
+                &mock_cli(vec!["--columns", "size,colour"]),
+                &MockVars::default()
+            ),
+            Err(OptionsError::UnknownColumn("colour".into()))
+        );
+    }
+
+    #[test]
+    fn deduce_columns_name_not_last() {
+        assert!(
+            Columns::deduce(
+                &mock_cli(vec!["--columns", "name,size"]),
+                &MockVars::default()
+            )
+            .is_err()
+        );
+    }
+
     #[test]
     fn deduce_time_types_no_time() {
         assert_eq!(
@@ -598,7 +1026,7 @@ mod tests {
     #[test]
     fn deduce_time_types_changed_word() {
         assert_eq!(
-            TimeTypes::deduce(&&mock_cli(vec!["--time", "changed"])),
+            TimeTypes::deduce(&mock_cli(vec!["--time", "changed"])),
             Ok(TimeTypes {
                 modified: false,
                 changed: true,
@@ -719,6 +1147,75 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deduce_size_options_default() {
+        assert_eq!(
+            SizeOptions::deduce(&mock_cli(vec![""])),
+            SizeOptions::default()
+        );
+    }
+
+    #[test]
+    fn deduce_size_options_unit() {
+        let unit = |args| SizeOptions::deduce(&mock_cli(args)).unit;
+        assert_eq!(unit(vec!["--size-unit=M"]), Some(Prefix::Mega));
+        assert_eq!(unit(vec!["--size-unit=Mi"]), Some(Prefix::Mebi));
+        assert_eq!(unit(vec!["--size-unit=M", "--binary"]), Some(Prefix::Mebi));
+        assert_eq!(
+            unit(vec!["--size-unit=M", "--size-suffix=iec"]),
+            Some(Prefix::Mebi)
+        );
+        assert_eq!(
+            unit(vec!["--size-unit=k", "--binary", "--size-suffix=si"]),
+            Some(Prefix::Kilo)
+        );
+
+        let options = SizeOptions::deduce(&mock_cli(vec!["--size-unit=B"]));
+        assert_eq!(options.format, SizeFormat::JustBytes);
+        assert_eq!(options.unit, None);
+    }
+
+    #[test]
+    fn deduce_size_options_suffix() {
+        assert_eq!(
+            SizeOptions::deduce(&mock_cli(vec!["--size-suffix=iec"])),
+            SizeOptions {
+                format: SizeFormat::BinaryBytes,
+                suffix: SizeSuffix::Long,
+                ..SizeOptions::default()
+            }
+        );
+        assert_eq!(
+            SizeOptions::deduce(&mock_cli(vec!["--size-suffix=si", "--binary"])),
+            SizeOptions {
+                format: SizeFormat::DecimalBytes,
+                suffix: SizeSuffix::Long,
+                ..SizeOptions::default()
+            }
+        );
+        assert_eq!(
+            SizeOptions::deduce(&mock_cli(vec!["--size-suffix=iec", "--bytes"])).format,
+            SizeFormat::JustBytes
+        );
+    }
+
+    #[test]
+    fn deduce_size_options_precision_and_separators() {
+        assert_eq!(
+            SizeOptions::deduce(&mock_cli(vec![
+                "--size-precision",
+                "2",
+                "--size-separators",
+                "never"
+            ])),
+            SizeOptions {
+                precision: Some(2),
+                separators: ThousandsSeparators::Never,
+                ..SizeOptions::default()
+            }
+        );
+    }
+
     #[test]
     fn deduce_grid_options() {
         assert_eq!(
@@ -856,6 +1353,7 @@ mod tests {
                 min_luminance: 40,
                 size: true,
                 age: true,
+                entries: false,
             }
         );
     }
@@ -871,6 +1369,7 @@ mod tests {
                 min_luminance: 60,
                 size: true,
                 age: false,
+                entries: false,
             }
         );
     }
@@ -889,6 +1388,7 @@ mod tests {
                 min_luminance: 60,
                 size: false,
                 age: true,
+                entries: false,
             }
         );
     }
@@ -912,10 +1412,42 @@ mod tests {
                 min_luminance: 99,
                 size: true,
                 age: true,
+                entries: false,
+            }
+        );
+    }
+
+    #[test]
+    fn deduce_color_scale_all_includes_entries() {
+        assert_eq!(
+            ColorScaleOptions::deduce(&mock_cli(vec!["--color-scale"]), &MockVars::default()),
+            ColorScaleOptions {
+                mode: ColorScaleMode::Gradient,
+                min_luminance: 40,
+                size: true,
+                age: true,
+                entries: true,
             }
         );
     }
 
+    #[test]
+    fn deduce_color_scale_entries_needs_column() {
+        let entries = |args| match Mode::deduce(&mock_cli(args), &MockVars::default(), false, false)
+        {
+            Ok(Mode::Details(details)) => details.color_scale.entries,
+            _ => panic!("not a details view"),
+        };
+        assert!(!entries(vec!["--long", "--color-scale"]));
+        assert!(!entries(vec!["--tree", "--color-scale=entries"]));
+        assert!(entries(vec!["--long", "--entries", "--color-scale"]));
+        assert!(entries(vec![
+            "--long",
+            "--columns=entries,name",
+            "--color-scale=all"
+        ]));
+    }
+
     #[test]
     fn deduce_mode_grid() {
         assert_eq!(
@@ -941,6 +1473,43 @@ mod tests {
             Ok(Mode::Grid(grid::Options { across: true }))
         );
     }
+
+    #[test]
+    fn deduce_mode_json_over_long() {
+        assert_eq!(
+            Mode::deduce(
+                &mock_cli(vec!["--long", "--json"]),
+                &MockVars::default(),
+                false,
+                false
+            ),
+            Ok(Mode::Json(json::Options { git: false }))
+        );
+    }
+
+    #[test]
+    fn deduce_mode_csv_always_has_header() {
+        let Ok(Mode::Details(opts)) = Mode::deduce(
+            &mock_cli(vec!["--tsv", "--csv"]),
+            &MockVars::default(),
+            false,
+            false,
+        ) else {
+            panic!("--csv should produce a details view");
+        };
+        assert!(opts.header);
+        assert_eq!(opts.delimiter, Some(Delimiter::Comma));
+    }
+
+    #[test]
+    fn deduce_mode_json_git_overridden() {
+        let mut vars = MockVars::default();
+        vars.set(vars::EZA_OVERRIDE_GIT, &OsString::from("1"));
+        assert_eq!(
+            Mode::deduce(&mock_cli(vec!["--json", "--git"]), &vars, false, true),
+            Ok(Mode::Json(json::Options { git: false }))
+        );
+    }
     #[test]
     fn deduce_details_options_tree() {
         let cli = mock_cli(vec!["--tree"]);
@@ -954,6 +1523,7 @@ mod tests {
                 mounts: false,
                 color_scale: ColorScaleOptions::deduce(&cli, &MockVars::default()),
                 follow_links: false,
+                delimiter: None,
             }
         );
     }
@@ -971,6 +1541,7 @@ mod tests {
                 mounts: true,
                 color_scale: ColorScaleOptions::deduce(&cli, &MockVars::default()),
                 follow_links: false,
+                delimiter: None,
             }
         );
     }
@@ -988,6 +1559,7 @@ mod tests {
                 mounts: false,
                 color_scale: ColorScaleOptions::deduce(&cli, &MockVars::default()),
                 follow_links: false,
+                delimiter: None,
             }
         );
     }
@@ -1005,6 +1577,7 @@ mod tests {
                 mounts: false,
                 color_scale: ColorScaleOptions::deduce(&cli, &MockVars::default()),
                 follow_links: false,
+                delimiter: None,
             }
         );
     }
@@ -1033,6 +1606,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deduce_summary() {
+        let summary = |args| {
+            View::deduce(&mock_cli(args), &MockVars::default(), false)
+                .unwrap()
+                .summary
+        };
+        assert_eq!(summary(vec![]), None);
+        assert_eq!(
+            summary(vec!["--summary", "--binary"]),
+            Some(summary::Options {
+                size: SizeFormat::BinaryBytes.into()
+            })
+        );
+    }
+
+    #[test]
+    fn deduce_total_size() {
+        let deduce = |args, strict| TotalSize::deduce(&mock_cli(args), false, strict);
+        assert_eq!(deduce(vec![], true), Ok(TotalSize::Off));
+        assert_eq!(deduce(vec!["--total-size"], true), Ok(TotalSize::All));
+        assert_eq!(
+            deduce(vec!["--total-size", "--one-file-system"], true),
+            Ok(TotalSize::OneFileSystem)
+        );
+        assert_eq!(deduce(vec!["--one-file-system"], false), Ok(TotalSize::Off));
+        assert_eq!(
+            deduce(vec!["--one-file-system"], true),
+            Err(OptionsError::Useless(
+                "one-file-system",
+                false,
+                "total-size"
+            ))
+        );
+    }
+
+    #[test]
+    fn usage_turns_on_total_size() {
+        let total_size = |args| {
+            View::deduce(&mock_cli(args), &MockVars::default(), true)
+                .unwrap()
+                .total_size
+        };
+        assert_eq!(total_size(vec!["-l"]), TotalSize::Off);
+        assert_eq!(total_size(vec!["-l", "--usage"]), TotalSize::All);
+        assert_eq!(total_size(vec!["-l", "--columns", "usage"]), TotalSize::All);
+        assert_eq!(
+            total_size(vec!["-l", "--usage", "--one-file-system"]),
+            TotalSize::OneFileSystem
+        );
+    }
+
     #[test]
     fn deduce_terminal_width_automatic() {
         assert_eq!(
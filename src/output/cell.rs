@@ -159,6 +159,11 @@ impl TextCellContents {
         ANSIStrings(&self.0)
     }
 
+    /// Joins the unformatted strings of this cell, dropping their styles.
+    pub fn plain(&self) -> String {
+        self.0.iter().map(|anstr| anstr.as_str()).collect()
+    }
+
     /// Calculates the width that a cell with these contents would take up, by
     /// counting the number of characters in each unformatted ANSI string.
     pub fn width(&self) -> DisplayWidth {
@@ -9,8 +9,14 @@ use nu_ansi_term::{Color as Colour, Style};
 use palette::{FromColor, LinSrgb, Oklab, Srgb};
 
 use crate::{
-    fs::{DotFilter, File, dir_action::RecurseOptions, feature::git::GitCache, fields::Size},
-    output::{table::TimeType, tree::TreeDepth},
+    fs::{
+        File, dir_action::RecurseOptions, feature::git::GitCache, fields::Size, filter::FileFilter,
+        recursive_size::TotalSize,
+    },
+    output::{
+        table::{TimeType, usage, usage_total},
+        tree::TreeDepth,
+    },
 };
 
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
@@ -19,6 +25,7 @@ pub struct ColorScaleOptions {
     pub min_luminance: isize,
     pub size: bool,
     pub age: bool,
+    pub entries: bool,
 }
 
 impl Default for ColorScaleOptions {
@@ -28,6 +35,7 @@ impl Default for ColorScaleOptions {
             min_luminance: 50,
             size: false,
             age: false,
+            entries: false,
         }
     }
 }
@@ -48,41 +56,46 @@ pub struct ColorScaleInformation {
     pub modified: Option<Extremes>,
 
     pub size: Option<Extremes>,
+    pub usage: Option<Extremes>,
+
+    pub entries: Option<Extremes>,
 }
 
 impl ColorScaleInformation {
+    /// Measures the ranges of values in the files being listed. The fixed
+    /// scale doesn’t depend on them, so it only passes the options along.
     pub fn from_color_scale(
         color_scale: ColorScaleOptions,
         files: &[File<'_>],
-        dot_filter: DotFilter,
+        filter: &FileFilter,
         git: Option<&GitCache>,
         git_ignoring: bool,
         r: Option<RecurseOptions>,
     ) -> Option<Self> {
-        if color_scale.mode == ColorScaleMode::Fixed {
-            None
-        } else {
-            let mut information = Self {
-                options: color_scale,
-                accessed: None,
-                changed: None,
-                created: None,
-                modified: None,
-                size: None,
-            };
+        let mut information = Self {
+            options: color_scale,
+            accessed: None,
+            changed: None,
+            created: None,
+            modified: None,
+            size: None,
+            usage: None,
+            entries: None,
+        };
 
+        if color_scale.mode == ColorScaleMode::Gradient {
             update_information_recursively(
                 &mut information,
                 files,
-                dot_filter,
+                filter,
                 git,
                 git_ignoring,
                 TreeDepth::root(),
                 r,
             );
-
-            Some(information)
         }
+
+        Some(information)
     }
 
     #[must_use]
@@ -122,12 +135,15 @@ impl ColorScaleInformation {
 fn update_information_recursively(
     information: &mut ColorScaleInformation,
     files: &[File<'_>],
-    dot_filter: DotFilter,
+    filter: &FileFilter,
     git: Option<&GitCache>,
     git_ignoring: bool,
     depth: TreeDepth,
     r: Option<RecurseOptions>,
 ) {
+    // Each file’s share of the usage column is relative to its siblings.
+    let total = usage_total(files);
+
     for file in files {
         if information.options.age {
             Extremes::update(
@@ -158,6 +174,14 @@ fn update_information_recursively(
                 _ => None,
             };
             Extremes::update(size, &mut information.size);
+
+            let ratio = usage(file, total).ratio().map(|ratio| ratio as f32);
+            Extremes::update(ratio, &mut information.usage);
+        }
+
+        if information.options.entries {
+            let count = filter.count_entries(file, git).map(|count| count as f32);
+            Extremes::update(count, &mut information.entries);
         }
 
         // We don't want to recurse into . and .., but still want to list them, therefore bypass
@@ -170,13 +194,13 @@ fn update_information_recursively(
             match file.read_dir() {
                 Ok(dir) => {
                     let files: Vec<File<'_>> = dir
-                        .files(dot_filter, git, git_ignoring, false, false)
+                        .files(filter.dot_filter, git, git_ignoring, false, TotalSize::Off)
                         .collect();
 
                     update_information_recursively(
                         information,
                         &files,
-                        dot_filter,
+                        filter,
                         git,
                         git_ignoring,
                         depth.deeper(),
@@ -65,8 +65,10 @@
 //! means that we must wait until every row has been added to the table before it
 //! can be displayed, in order to make sure that every column is wide enough.
 
+use std::borrow::Cow;
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::sync::Mutex;
 use std::vec::IntoIter as VecIntoIter;
 
 use nu_ansi_term::Style;
@@ -78,12 +80,13 @@ use crate::fs::dir_action::RecurseOptions;
 use crate::fs::feature::git::GitCache;
 use crate::fs::feature::xattr::Attribute;
 use crate::fs::fields::SecurityContextType;
-use crate::fs::filter::FileFilter;
+use crate::fs::filter::{FileFilter, Recursion};
 use crate::fs::{Dir, File};
 use crate::output::cell::TextCell;
 use crate::output::color_scale::{ColorScaleInformation, ColorScaleOptions};
 use crate::output::file_name::Options as FileStyle;
-use crate::output::table::{Options as TableOptions, Row as TableRow, Table};
+use crate::output::summary::Summary;
+use crate::output::table::{self, Options as TableOptions, Row as TableRow, Table};
 use crate::output::tree::{TreeDepth, TreeParams, TreeTrunk};
 use crate::theme::Theme;
 
@@ -124,6 +127,63 @@ pub struct Options {
 
     /// Whether to drill down into symbolic links that point to directories
     pub follow_links: bool,
+
+    /// Whether to export the table as delimited text instead of drawing it.
+    pub delimiter: Option<Delimiter>,
+}
+
+/// How to separate the fields of each row when exporting the table with
+/// `--csv` or `--tsv`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Delimiter {
+    /// Comma-separated values, quoted as in RFC 4180.
+    Comma,
+
+    /// Tab-separated values, with tabs, newlines and backslashes escaped.
+    Tab,
+}
+
+impl Delimiter {
+    fn separator(self) -> &'static str {
+        match self {
+            Self::Comma => ",",
+            Self::Tab => "\t",
+        }
+    }
+
+    /// Escapes a single field so that it can’t be mistaken for the end of
+    /// the field or the row.
+    fn escape(self, field: &str) -> Cow<'_, str> {
+        match self {
+            Self::Comma if field.contains([',', '"', '\r', '\n']) => {
+                Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+            }
+            Self::Tab if field.contains(['\t', '\r', '\n', '\\']) => Cow::Owned(
+                field
+                    .replace('\\', "\\\\")
+                    .replace('\t', "\\t")
+                    .replace('\r', "\\r")
+                    .replace('\n', "\\n"),
+            ),
+            _ => Cow::Borrowed(field),
+        }
+    }
+
+    fn write_row<'f, W: Write>(
+        self,
+        w: &mut W,
+        fields: impl IntoIterator<Item = &'f str>,
+    ) -> io::Result<()> {
+        let mut first = true;
+        for field in fields {
+            if !first {
+                write!(w, "{}", self.separator())?;
+            }
+            first = false;
+            write!(w, "{}", self.escape(field))?;
+        }
+        writeln!(w)
+    }
 }
 
 pub struct Render<'a> {
@@ -147,6 +207,10 @@ pub struct Render<'a> {
     pub git: Option<&'a GitCache>,
 
     pub git_repos: bool,
+
+    /// The totals for the summary footer, which the entries of a tree get
+    /// added to as its directories are read.
+    pub summary: Option<&'a Mutex<Summary>>,
 }
 
 #[rustfmt::skip]
@@ -166,12 +230,17 @@ impl<'a> AsRef<File<'a>> for Egg<'a> {
 
 impl<'a> Render<'a> {
     pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+        if let Some(delimiter) = self.opts.delimiter {
+            let header = self.opts.header;
+            return self.render_delimited(w, delimiter, header);
+        }
+
         let mut rows = Vec::new();
 
         let color_scale_info = ColorScaleInformation::from_color_scale(
             self.opts.color_scale,
             &self.files,
-            self.filter.dot_filter,
+            self.filter,
             self.git,
             self.git_ignoring,
             self.recurse,
@@ -192,7 +261,7 @@ impl<'a> Render<'a> {
                 (None, _) => { /* Keep Git how it is */ }
             }
 
-            let mut table = Table::new(table, self.git, self.theme, self.git_repos);
+            let mut table = Table::new(table, self.git, self.filter, self.theme, self.git_repos);
 
             if self.opts.header {
                 let header = table.header_row();
@@ -231,6 +300,97 @@ impl<'a> Render<'a> {
         Ok(())
     }
 
+    /// Writes the table as delimited text: a header row if asked for, then
+    /// one row per file holding the raw value of each column, followed by its
+    /// path. Nothing gets padded or styled, but files are still sorted,
+    /// filtered and recursed into just like the drawn table.
+    pub fn render_delimited<W: Write>(
+        mut self,
+        w: &mut W,
+        delimiter: Delimiter,
+        header: bool,
+    ) -> io::Result<()> {
+        let Some(ref table) = self.opts.table else {
+            return Ok(());
+        };
+
+        if let (Some(g), Some(d)) = (self.git, self.dir)
+            && !g.has_anything_for(&d.path)
+        {
+            self.git = None;
+        }
+
+        let table = Table::new(table, self.git, self.filter, self.theme, self.git_repos);
+
+        if header {
+            delimiter.write_row(w, table.raw_header().chain(["Path"]))?;
+        }
+
+        let mut files = std::mem::take(&mut self.files);
+        self.add_files_to_delimited(w, &table, &mut files, TreeDepth::root(), delimiter)
+    }
+
+    fn add_files_to_delimited<W: Write>(
+        &self,
+        w: &mut W,
+        table: &Table<'_>,
+        files: &mut [File<'_>],
+        depth: TreeDepth,
+        delimiter: Delimiter,
+    ) -> io::Result<()> {
+        self.filter.sort_files(files, self.git);
+        let usage_total = table::usage_total(files);
+
+        for file in files.iter() {
+            let mut cells = table.raw_row_for_file(file, usage_total);
+            let path = file.path.strip_prefix(".").unwrap_or(&file.path);
+            cells.push(if path.as_os_str().is_empty() {
+                String::from(".")
+            } else {
+                path.display().to_string()
+            });
+            delimiter.write_row(w, cells.iter().map(String::as_str))?;
+
+            let is_dir = if self.opts.follow_links {
+                file.points_to_directory()
+            } else {
+                file.is_directory()
+            };
+
+            if let Some(r) = self.recurse
+                && r.tree
+                && is_dir
+                && !r.is_too_deep(depth.0)
+            {
+                let dir = match file.read_dir() {
+                    Ok(dir) => dir,
+                    Err(e) => {
+                        eprintln!("{}: {}", file.path.display(), e);
+                        continue;
+                    }
+                };
+
+                let mut children = dir
+                    .files(
+                        self.filter.dot_filter,
+                        self.git,
+                        self.git_ignoring,
+                        file.deref_links,
+                        file.total_size(),
+                    )
+                    .collect::<Vec<_>>();
+                self.filter.filter_child_files(
+                    Recursion::at(self.recurse, depth.0 + 1),
+                    &mut children,
+                    self.git,
+                );
+                self.add_files_to_delimited(w, table, &mut children, depth.deeper(), delimiter)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Whether to show the extended attribute hint
     pub fn show_xattr_hint(&self, file: &File<'_>) -> bool {
         // Do not show the hint '@' if the only extended attribute is the security
@@ -256,6 +416,7 @@ impl<'a> Render<'a> {
     ) {
         use crate::fs::feature::xattr;
 
+        let usage_total = table::usage_total(src);
         let mut file_eggs: Vec<_> = src
             .par_iter()
             .map(|file| {
@@ -289,9 +450,14 @@ impl<'a> Render<'a> {
                     &[]
                 };
 
-                let table_row = table
-                    .as_ref()
-                    .map(|t| t.row_for_file(file, self.show_xattr_hint(file), color_scale_info));
+                let table_row = table.as_ref().map(|t| {
+                    t.row_for_file(
+                        file,
+                        self.show_xattr_hint(file),
+                        color_scale_info,
+                        usage_total,
+                    )
+                });
 
                 let mut dir = None;
                 let follow_links = self.opts.follow_links;
@@ -326,12 +492,25 @@ impl<'a> Render<'a> {
             .collect();
 
         // this is safe because all entries have been initialized above
-        self.filter.sort_files(&mut file_eggs);
+        self.filter.sort_files(&mut file_eggs, self.git);
 
         for (tree_params, egg) in depth.iterate_over(file_eggs.into_iter()) {
             let mut files = Vec::new();
             let errors = egg.errors;
 
+            // A root that gets expanded isn’t counted itself, only what’s
+            // inside it.
+            if let Some(summary) = self.summary
+                && depth.0 == 0
+            {
+                let mut summary = summary.lock().unwrap();
+                if egg.dir.is_some() {
+                    summary.add_tree_root(egg.file);
+                } else {
+                    summary.add_files(std::slice::from_ref(egg.file), true);
+                }
+            }
+
             if let (Some(ref mut t), Some(row)) = (table.as_mut(), egg.table_row.as_ref()) {
                 t.add_widths(row);
             }
@@ -360,13 +539,24 @@ impl<'a> Render<'a> {
                     self.git,
                     self.git_ignoring,
                     egg.file.deref_links,
-                    egg.file.is_recursive_size(),
+                    egg.file.total_size(),
                 ) {
                     files.push(file_to_add);
                 }
 
-                self.filter
-                    .filter_child_files(self.recurse.is_some(), &mut files);
+                self.filter.filter_child_files(
+                    Recursion::at(self.recurse, depth.0 + 1),
+                    &mut files,
+                    self.git,
+                );
+
+                if let Some(summary) = self.summary {
+                    let count_size = !egg.file.is_recursive_size();
+                    summary
+                        .lock()
+                        .unwrap()
+                        .add_dir(dir, &files, self.filter, count_size);
+                }
 
                 if !files.is_empty() {
                     for xattr in egg.xattrs {
@@ -432,9 +622,15 @@ impl<'a> Render<'a> {
     }
 
     fn render_xattr(&self, xattr: &Attribute, tree: TreeParams) -> Row {
+        let size = self
+            .opts
+            .table
+            .as_ref()
+            .map(|table| table.size)
+            .unwrap_or_default();
         let name = TextCell::paint(
             self.theme.ui.perms.unwrap_or_default().attribute(),
-            format!("{xattr}"),
+            xattr.describe(|length| size.text(length as u64)),
         );
         Row {
             cells: None,
@@ -536,3 +732,28 @@ impl Iterator for Iter {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Delimiter;
+
+    #[test]
+    fn csv_quotes_when_needed() {
+        assert_eq!(Delimiter::Comma.escape("plain"), "plain");
+        assert_eq!(Delimiter::Comma.escape("a,b"), "\"a,b\"");
+        assert_eq!(Delimiter::Comma.escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn tsv_escapes_control_characters() {
+        assert_eq!(Delimiter::Tab.escape("a,b"), "a,b");
+        assert_eq!(Delimiter::Tab.escape("a\tb\nc\\d"), "a\\tb\\nc\\\\d");
+    }
+
+    #[test]
+    fn write_row_separates_fields() {
+        let mut out = Vec::new();
+        Delimiter::Tab.write_row(&mut out, ["x", "", "z"]).unwrap();
+        assert_eq!(out, b"x\t\tz\n");
+    }
+}
@@ -483,6 +483,7 @@ impl<C: Colours> FileName<'_, '_, C> {
 
         #[rustfmt::skip]
         return match self.file {
+            f if f.is_ghost              => self.colours.git_deleted(),
             f if f.is_mount_point()      => self.colours.mount_point(),
             f if f.is_directory()        => self.colours.directory(),
             #[cfg(unix)]
@@ -538,6 +539,9 @@ pub trait Colours: FiletypeColours {
     /// The style to paint a directory that has a filesystem mounted on it.
     fn mount_point(&self) -> Style;
 
+    /// The style to paint a file that Git tracks but that has been deleted.
+    fn git_deleted(&self) -> Style;
+
     fn colour_file(&self, file: &File<'_>) -> Style;
 
     fn style_override(&self, file: &File<'_>) -> Option<FileNameStyle>;
@@ -9,6 +9,7 @@ use std::io::{self, Write};
 use term_grid::{Direction, Filling, Grid, GridOptions};
 
 use crate::fs::File;
+use crate::fs::feature::git::GitCache;
 use crate::fs::filter::FileFilter;
 use crate::output::file_name::Options as FileStyle;
 use crate::theme::Theme;
@@ -36,11 +37,12 @@ pub struct Render<'a> {
     pub opts: &'a Options,
     pub console_width: usize,
     pub filter: &'a FileFilter,
+    pub git: Option<&'a GitCache>,
 }
 
 impl Render<'_> {
     pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
-        self.filter.sort_files(&mut self.files);
+        self.filter.sort_files(&mut self.files, self.git);
 
         let cells = self
             .files
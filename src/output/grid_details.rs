@@ -19,7 +19,7 @@ use crate::output::cell::TextCell;
 use crate::output::color_scale::ColorScaleInformation;
 use crate::output::details::{Options as DetailsOptions, Render as DetailsRender};
 use crate::output::file_name::Options as FileStyle;
-use crate::output::table::{Options as TableOptions, Table};
+use crate::output::table::{self, Options as TableOptions, Table};
 use crate::theme::Theme;
 
 #[derive(PartialEq, Eq, Debug)]
@@ -110,6 +110,7 @@ impl<'a> Render<'a> {
             git_ignoring:  self.git_ignoring,
             git:           self.git,
             git_repos:     self.git_repos,
+            summary:       None,
         };
     }
 
@@ -128,7 +129,7 @@ impl<'a> Render<'a> {
         let color_scale_info = ColorScaleInformation::from_color_scale(
             self.details.color_scale,
             &self.files,
-            self.filter.dot_filter,
+            self.filter,
             self.git,
             self.git_ignoring,
             None,
@@ -139,11 +140,17 @@ impl<'a> Render<'a> {
         // It is important to collect all these rows _before_ turning them into
         // cells, because the width calculations need to consider all rows
         // before each row is turned into a string.
+        let usage_total = table::usage_total(&self.files);
         let rows: Vec<_> = self
             .files
             .iter()
             .map(|file| {
-                let row = table.row_for_file(file, drender.show_xattr_hint(file), color_scale_info);
+                let row = table.row_for_file(
+                    file,
+                    drender.show_xattr_hint(file),
+                    color_scale_info,
+                    usage_total,
+                );
                 table.add_widths(&row);
                 row
             })
@@ -217,6 +224,7 @@ impl<'a> Render<'a> {
                 git_ignoring,
                 git,
                 git_repos,
+                summary: None,
             };
             return r.render(w);
         }
@@ -256,7 +264,7 @@ impl<'a> Render<'a> {
             (None, _) => { /* Keep Git how it is */ }
         }
 
-        let mut table = Table::new(options, self.git, self.theme, self.git_repos);
+        let mut table = Table::new(options, self.git, self.filter, self.theme, self.git_repos);
 
         // The header row will be printed separately, but it should be
         // considered for the width calculations.
@@ -0,0 +1,438 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! The **JSON** output view writes one JSON object per file, one per line.
+//!
+//! It’s meant for scripts: every value is raw (bytes, ids, UTC timestamps)
+//! rather than humanised and styled, so the output doesn’t change shape when
+//! column widths, time styles or colours do.
+//!
+//! ```text
+//!     {"name":"Cargo.toml","path":"./Cargo.toml","parent":".","depth":0,"type":"file",...}
+//!     {"name":"src","path":"./src","parent":".","depth":0,"type":"directory",...}
+//!     {"name":"main.rs","path":"./src/main.rs","parent":"./src","depth":1,"type":"file",...}
+//! ```
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use log::trace;
+use serde::Serialize;
+
+use crate::fs::dir_action::RecurseOptions;
+use crate::fs::feature::git::GitCache;
+use crate::fs::feature::xattr;
+use crate::fs::fields as f;
+use crate::fs::filter::{FileFilter, Recursion};
+use crate::fs::recursive_size::RecursiveSize;
+use crate::fs::{Dir, File};
+use crate::output::time::rfc3339;
+
+/// Options for the JSON view.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Options {
+    /// Whether to include each file’s Git status.
+    pub git: bool,
+}
+
+pub struct Render<'a> {
+    pub dir: Option<&'a Dir>,
+    pub files: Vec<File<'a>>,
+
+    /// How deep the files being listed are, when recursing with `--recurse`.
+    /// Entries found while descending with `--tree` are one deeper than their
+    /// parent.
+    pub depth: usize,
+
+    /// Whether to recurse through directories with a tree view, and if so,
+    /// which options to use.
+    pub recurse: Option<RecurseOptions>,
+
+    /// How to sort and filter the files after getting their details.
+    pub filter: &'a FileFilter,
+
+    /// Whether we are skipping Git-ignored files.
+    pub git_ignoring: bool,
+
+    pub git: Option<&'a GitCache>,
+
+    /// Whether to drill down into symbolic links that point to directories.
+    pub follow_links: bool,
+}
+
+/// Everything known about a single file, in the order it gets serialised.
+#[derive(Serialize)]
+struct Entry<'f> {
+    name: &'f str,
+    path: String,
+    parent: Option<String>,
+    depth: usize,
+    #[serde(rename = "type")]
+    file_type: &'static str,
+    permissions: Option<String>,
+    mode: Option<String>,
+    size: Option<u64>,
+    device: Option<Device>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recursive_size: Option<Option<Blocks>>,
+    blocksize: Option<u64>,
+    uid: Option<u32>,
+    user: Option<String>,
+    gid: Option<u32>,
+    group: Option<String>,
+    modified: Option<String>,
+    changed: Option<String>,
+    accessed: Option<String>,
+    created: Option<String>,
+    inode: Option<u64>,
+    links: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git: Option<Git>,
+    xattrs: Vec<Xattr>,
+    mount: Option<Mount>,
+    target: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Device {
+    major: u32,
+    minor: u32,
+}
+
+#[derive(Serialize)]
+struct Blocks {
+    bytes: u64,
+    blocks: u64,
+}
+
+#[derive(Serialize)]
+struct Git {
+    staged: &'static str,
+    unstaged: &'static str,
+}
+
+#[derive(Serialize)]
+struct Xattr {
+    name: String,
+    size: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct Mount {
+    source: String,
+    fstype: String,
+}
+
+impl<'a> Render<'a> {
+    pub fn render<W: Write>(mut self, w: &mut W) -> io::Result<()> {
+        if let (Some(g), Some(d)) = (self.git, self.dir)
+            && !g.has_anything_for(&d.path)
+        {
+            self.git = None;
+        }
+
+        let parent = self.dir.map(|d| d.path.as_path());
+        let mut files = std::mem::take(&mut self.files);
+        self.render_files(w, &mut files, parent, self.depth)
+    }
+
+    fn render_files<W: Write>(
+        &self,
+        w: &mut W,
+        files: &mut [File<'_>],
+        parent: Option<&Path>,
+        depth: usize,
+    ) -> io::Result<()> {
+        self.filter.sort_files(files, self.git);
+
+        for file in files.iter() {
+            let entry = self.entry_for_file(file, parent, depth);
+            serde_json::to_writer(&mut *w, &entry)?;
+            writeln!(w)?;
+
+            let is_dir = if self.follow_links {
+                file.points_to_directory()
+            } else {
+                file.is_directory()
+            };
+
+            if let Some(r) = self.recurse
+                && r.tree
+                && is_dir
+                && !file.is_all_all
+                && !r.is_too_deep(depth + 1)
+            {
+                trace!("matching on read_dir");
+                let dir = match file.read_dir() {
+                    Ok(dir) => dir,
+                    Err(e) => {
+                        eprintln!("{}: {}", file.path.display(), e);
+                        continue;
+                    }
+                };
+
+                let mut children = dir
+                    .files(
+                        self.filter.dot_filter,
+                        self.git,
+                        self.git_ignoring,
+                        file.deref_links,
+                        file.total_size(),
+                    )
+                    .collect::<Vec<_>>();
+                self.filter.filter_child_files(
+                    Recursion::at(self.recurse, depth + 2),
+                    &mut children,
+                    self.git,
+                );
+                self.render_files(w, &mut children, Some(&file.path), depth + 1)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn entry_for_file<'f>(
+        &self,
+        file: &'f File<'_>,
+        parent: Option<&Path>,
+        depth: usize,
+    ) -> Entry<'f> {
+        let (size, device) = match file.size() {
+            f::Size::Some(bytes) => (Some(bytes), None),
+            f::Size::None => (None, None),
+            f::Size::DeviceIDs(ids) => (
+                None,
+                Some(Device {
+                    major: ids.major,
+                    minor: ids.minor,
+                }),
+            ),
+        };
+
+        let recursive_size = match file.recursive_size() {
+            RecursiveSize::None => None,
+            RecursiveSize::Unknown => Some(None),
+            RecursiveSize::Some(bytes, blocks) => Some(Some(Blocks { bytes, blocks })),
+        };
+
+        let git = self.git.map(|g| {
+            let status = g.get(&file.path, file.is_directory());
+            Git {
+                staged: git_status_name(status.staged),
+                unstaged: git_status_name(status.unstaged),
+            }
+        });
+
+        let xattrs = if xattr::ENABLED {
+            file.extended_attributes()
+                .iter()
+                .map(|a| Xattr {
+                    name: a.name.clone(),
+                    size: a.value.as_ref().map(Vec::len),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mount = if file.is_mount_point() {
+            file.mount_point_info().map(|m| Mount {
+                source: m.source.clone(),
+                fstype: m.fstype.clone(),
+            })
+        } else {
+            None
+        };
+
+        let target = if file.is_link() {
+            std::fs::read_link(&file.path)
+                .ok()
+                .map(|p| p.display().to_string())
+        } else {
+            None
+        };
+
+        let mut entry = Entry {
+            name: &file.name,
+            path: file.path.display().to_string(),
+            parent: parent
+                .or_else(|| file.path.parent())
+                .filter(|p| !p.as_os_str().is_empty())
+                .map(|p| p.display().to_string()),
+            depth,
+            file_type: type_name(file.type_char()),
+            permissions: None,
+            mode: None,
+            size,
+            device,
+            recursive_size,
+            blocksize: None,
+            uid: None,
+            user: None,
+            gid: None,
+            group: None,
+            modified: file.modified_time().map(rfc3339),
+            changed: file.changed_time().map(rfc3339),
+            accessed: file.accessed_time().map(rfc3339),
+            created: file.created_time().map(rfc3339),
+            inode: None,
+            links: None,
+            git,
+            xattrs,
+            mount,
+            target,
+        };
+
+        #[cfg(unix)]
+        Self::add_unix_fields(&mut entry, file);
+
+        entry
+    }
+
+    #[cfg(unix)]
+    fn add_unix_fields(entry: &mut Entry<'_>, file: &File<'_>) {
+        use uzers::{get_group_by_gid, get_user_by_uid};
+
+        if let Some(p) = file.permissions() {
+            entry.permissions = Some(permissions_string(p));
+            entry.mode = Some(format!("{:04o}", permissions_bits(p)));
+        }
+
+        if let f::Blocksize::Some(blocks) = file.blocksize() {
+            entry.blocksize = Some(blocks);
+        }
+
+        if let Some(f::User(uid)) = file.user() {
+            entry.uid = Some(uid);
+            entry.user = get_user_by_uid(uid).map(|u| u.name().to_string_lossy().into_owned());
+        }
+
+        if let Some(f::Group(gid)) = file.group() {
+            entry.gid = Some(gid);
+            entry.group = get_group_by_gid(gid).map(|g| g.name().to_string_lossy().into_owned());
+        }
+
+        #[allow(clippy::useless_conversion)]
+        {
+            entry.inode = Some(u64::from(file.inode().0));
+            entry.links = Some(u64::from(file.links().count));
+        }
+    }
+}
+
+fn type_name(file_type: f::Type) -> &'static str {
+    #[rustfmt::skip]
+    return match file_type {
+        f::Type::Directory    => "directory",
+        f::Type::File         => "file",
+        f::Type::Link         => "link",
+        f::Type::Pipe         => "pipe",
+        f::Type::Socket       => "socket",
+        f::Type::CharDevice   => "char_device",
+        f::Type::BlockDevice  => "block_device",
+        f::Type::Special      => "special",
+    };
+}
+
+fn git_status_name(status: f::GitStatus) -> &'static str {
+    #[rustfmt::skip]
+    return match status {
+        f::GitStatus::NotModified  => "unmodified",
+        f::GitStatus::New          => "new",
+        f::GitStatus::Modified     => "modified",
+        f::GitStatus::Deleted      => "deleted",
+        f::GitStatus::Renamed      => "renamed",
+        f::GitStatus::TypeChange   => "typechange",
+        f::GitStatus::Ignored      => "ignored",
+        f::GitStatus::Conflicted   => "conflicted",
+    };
+}
+
+#[cfg(unix)]
+fn permissions_bits(p: f::Permissions) -> u32 {
+    [
+        p.setuid,
+        p.setgid,
+        p.sticky,
+        p.user_read,
+        p.user_write,
+        p.user_execute,
+        p.group_read,
+        p.group_write,
+        p.group_execute,
+        p.other_read,
+        p.other_write,
+        p.other_execute,
+    ]
+    .iter()
+    .fold(0, |bits, &bit| (bits << 1) | u32::from(bit))
+}
+
+/// The permission bits in the same layout as `ls -l`, without the file type.
+#[cfg(unix)]
+fn permissions_string(p: f::Permissions) -> String {
+    let bit = |set, c| if set { c } else { '-' };
+    let special = |special, execute, lower, upper| match (special, execute) {
+        (true, true) => lower,
+        (true, false) => upper,
+        (false, true) => 'x',
+        (false, false) => '-',
+    };
+
+    [
+        bit(p.user_read, 'r'),
+        bit(p.user_write, 'w'),
+        special(p.setuid, p.user_execute, 's', 'S'),
+        bit(p.group_read, 'r'),
+        bit(p.group_write, 'w'),
+        special(p.setgid, p.group_execute, 's', 'S'),
+        bit(p.other_read, 'r'),
+        bit(p.other_write, 'w'),
+        special(p.sticky, p.other_execute, 't', 'T'),
+    ]
+    .iter()
+    .collect()
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod test {
+    use super::*;
+
+    fn perms(bits: u32) -> f::Permissions {
+        let has = |b: u32| bits & b == b;
+        f::Permissions {
+            user_read: has(0o400),
+            user_write: has(0o200),
+            user_execute: has(0o100),
+            group_read: has(0o040),
+            group_write: has(0o020),
+            group_execute: has(0o010),
+            other_read: has(0o004),
+            other_write: has(0o002),
+            other_execute: has(0o001),
+            sticky: has(0o1000),
+            setgid: has(0o2000),
+            setuid: has(0o4000),
+        }
+    }
+
+    #[test]
+    fn mode_round_trips() {
+        for bits in [0o644, 0o755, 0o1777, 0o4755, 0o2750, 0o000] {
+            assert_eq!(permissions_bits(perms(bits)), bits);
+        }
+    }
+
+    #[test]
+    fn permissions_like_ls() {
+        assert_eq!(permissions_string(perms(0o644)), "rw-r--r--");
+        assert_eq!(permissions_string(perms(0o1777)), "rwxrwxrwt");
+        assert_eq!(permissions_string(perms(0o4644)), "rwSr--r--");
+        assert_eq!(permissions_string(perms(0o2750)), "rwxr-s---");
+    }
+}
@@ -8,14 +8,18 @@
 pub use self::cell::TextCell;
 pub use self::escape::escape;
 
+use crate::fs::recursive_size::TotalSize;
+
 pub mod color_scale;
 pub mod details;
 pub mod file_name;
 pub mod grid;
 pub mod grid_details;
 pub mod icons;
+pub mod json;
 pub mod lines;
 pub mod render;
+pub mod summary;
 pub mod table;
 pub mod time;
 
@@ -31,7 +35,8 @@ pub struct View {
     pub file_style: file_name::Options,
     pub deref_links: bool,
     pub follow_links: bool,
-    pub total_size: bool,
+    pub total_size: TotalSize,
+    pub summary: Option<summary::Options>,
 }
 
 /// The **mode** is the “type” of output.
@@ -41,6 +46,7 @@ pub enum Mode {
     Grid(grid::Options),
     Details(details::Options),
     GridDetails(grid_details::Options),
+    Json(json::Options),
     Lines,
 }
 
@@ -10,59 +10,37 @@ use unit_prefix::Prefix;
 
 use crate::fs::fields as f;
 use crate::output::cell::{DisplayWidth, TextCell};
-use crate::output::table::SizeFormat;
+use crate::output::render::size::FormattedSize;
+use crate::output::table::SizeOptions;
 
 impl f::Blocksize {
     pub fn render<C: Colours>(
         self,
         colours: &C,
-        size_format: SizeFormat,
+        size_options: SizeOptions,
         numerics: &NumericLocale,
     ) -> TextCell {
-        use unit_prefix::NumberPrefix;
-
         let size = match self {
             Self::Some(s) => s,
             Self::None => return TextCell::blank(colours.no_blocksize()),
         };
 
-        let result = match size_format {
-            SizeFormat::DecimalBytes => NumberPrefix::decimal(size as f64),
-            SizeFormat::BinaryBytes => NumberPrefix::binary(size as f64),
-            SizeFormat::JustBytes => {
-                // Use the binary prefix to select a style.
-                let prefix = match NumberPrefix::binary(size as f64) {
-                    NumberPrefix::Standalone(_) => None,
-                    NumberPrefix::Prefixed(p, _) => Some(p),
-                };
-
-                // But format the number directly using the locale.
-                let string = numerics.format_int(size);
-
-                return TextCell::paint(colours.blocksize(prefix), string);
-            }
-        };
-
-        let (prefix, n) = match result {
-            NumberPrefix::Standalone(b) => {
-                return TextCell::paint(colours.blocksize(None), numerics.format_int(b));
-            }
-            NumberPrefix::Prefixed(p, n) => (p, n),
-        };
+        let FormattedSize {
+            prefix,
+            number,
+            unit,
+        } = size_options.format(size, numerics);
 
-        let symbol = prefix.symbol();
-        let number = if n < 10_f64 {
-            numerics.format_float(n, 1)
-        } else {
-            numerics.format_int(n.round() as isize)
-        };
+        if unit.is_empty() {
+            return TextCell::paint(colours.blocksize(prefix), number);
+        }
 
         TextCell {
-            // symbol is guaranteed to be ASCII since unit prefixes are hardcoded.
-            width: DisplayWidth::from(&*number) + symbol.len(),
+            // unit is guaranteed to be ASCII since unit prefixes are hardcoded.
+            width: DisplayWidth::from(&*number) + unit.len(),
             contents: vec![
-                colours.blocksize(Some(prefix)).paint(number),
-                colours.unit(Some(prefix)).paint(symbol),
+                colours.blocksize(prefix).paint(number),
+                colours.unit(prefix).paint(unit),
             ]
             .into(),
         }
@@ -106,7 +84,7 @@ pub mod test {
             expected,
             directory.render(
                 &TestColours,
-                SizeFormat::JustBytes,
+                SizeFormat::JustBytes.into(),
                 &NumericLocale::english()
             )
         );
@@ -124,7 +102,7 @@ pub mod test {
             expected,
             directory.render(
                 &TestColours,
-                SizeFormat::DecimalBytes,
+                SizeFormat::DecimalBytes.into(),
                 &NumericLocale::english()
             )
         );
@@ -142,7 +120,7 @@ pub mod test {
             expected,
             directory.render(
                 &TestColours,
-                SizeFormat::BinaryBytes,
+                SizeFormat::BinaryBytes.into(),
                 &NumericLocale::english()
             )
         );
@@ -160,7 +138,7 @@ pub mod test {
             expected,
             directory.render(
                 &TestColours,
-                SizeFormat::JustBytes,
+                SizeFormat::JustBytes.into(),
                 &NumericLocale::english()
             )
         );
@@ -11,9 +11,48 @@ use crate::output::cell::{DisplayWidth, TextCell};
 
 impl f::Git {
     pub fn render(self, colours: &dyn Colours) -> TextCell {
-        TextCell {
+        let mut cell = TextCell {
             width: DisplayWidth::from(2),
             contents: vec![self.staged.render(colours), self.unstaged.render(colours)].into(),
+        };
+
+        if let Some(marker) = self.marker {
+            cell.push(marker.render(colours), 1);
+        }
+
+        cell
+    }
+}
+
+impl f::GitMarker {
+    #[rustfmt::skip]
+    fn render(self, colours: &dyn Colours) -> ANSIString<'static> {
+        match self {
+            Self::Submodule { moved: false }  => colours.submodule().paint("S"),
+            Self::Submodule { moved: true }   => colours.submodule_moved().paint("S"),
+            Self::Worktree                    => colours.worktree().paint("W"),
+            Self::LfsPointer                  => colours.lfs_pointer().paint("L"),
+        }
+    }
+}
+
+#[cfg(feature = "git")]
+impl f::GitDiffStat {
+    pub fn render(self, colours: &dyn Colours) -> TextCell {
+        if self == Self::default() {
+            return TextCell::paint_str(colours.not_modified(), "-");
+        }
+
+        let added = format!("+{}", self.added);
+        let removed = format!("-{}", self.removed);
+        TextCell {
+            width: DisplayWidth::from(added.len() + 1 + removed.len()),
+            contents: vec![
+                colours.new().paint(added),
+                Style::default().paint(" "),
+                colours.deleted().paint(removed),
+            ]
+            .into(),
         }
     }
 }
@@ -46,34 +85,88 @@ pub trait Colours {
     fn type_change(&self) -> Style;
     fn ignored(&self) -> Style;
     fn conflicted(&self) -> Style;
+    fn submodule(&self) -> Style;
+    fn submodule_moved(&self) -> Style;
+    fn worktree(&self) -> Style;
+    fn lfs_pointer(&self) -> Style;
 }
 
 impl f::SubdirGitRepo {
     pub fn render(self, colours: &dyn RepoColours) -> TextCell {
-        let branch_name = match self.branch {
-            Some(name) => match name.as_ref() {
-                "main" | "master" => colours.branch_main().paint(name),
-                _ => colours.branch_other().paint(name),
-            },
-            None => colours.no_repo().paint("-"),
-        };
+        let mut cell = TextCell::default();
 
         if let Some(status) = self.status {
-            TextCell {
-                width: DisplayWidth::from(2) + DisplayWidth::from(branch_name.as_str()),
-                contents: vec![
-                    status.render(colours),
-                    Style::default().paint(" "),
-                    branch_name,
-                ]
-                .into(),
+            cell.push(status.render(colours), 1);
+            cell.add_spaces(1);
+        }
+
+        #[cfg(any(feature = "git", feature = "gix"))]
+        match self.head {
+            Some(f::SubdirGitHead::Branch(name)) => {
+                let style = match name.as_ref() {
+                    "main" | "master" => colours.branch_main(),
+                    _ => colours.branch_other(),
+                };
+                let width = DisplayWidth::from(name.as_str());
+                cell.push(style.paint(name), *width);
+            }
+            Some(f::SubdirGitHead::Tag(name) | f::SubdirGitHead::Detached(name)) => {
+                let name = format!("({name})");
+                let width = DisplayWidth::from(name.as_str());
+                cell.push(colours.detached().paint(name), *width);
+            }
+            None => cell.push(colours.no_repo().paint("-"), 1),
+        }
+
+        #[cfg(not(any(feature = "git", feature = "gix")))]
+        cell.push(colours.no_repo().paint("-"), 1);
+
+        #[cfg(any(feature = "git", feature = "gix"))]
+        if let Some(operation) = self.operation {
+            let name = operation.name();
+            cell.push(colours.no_repo().paint("|"), 1);
+            cell.push(colours.operation().paint(name), name.len());
+        }
+
+        if let Some((ahead, behind)) = self.ahead_behind {
+            if ahead > 0 {
+                let count = format!(" ↑{ahead}");
+                let width = DisplayWidth::from(count.as_str());
+                cell.push(colours.ahead_behind().paint(count), *width);
             }
-        } else {
-            TextCell {
-                width: DisplayWidth::from(branch_name.as_str()),
-                contents: vec![branch_name].into(),
+            if behind > 0 {
+                let count = format!(" ↓{behind}");
+                let width = DisplayWidth::from(count.as_str());
+                cell.push(colours.ahead_behind().paint(count), *width);
             }
         }
+
+        if self.dirty > 0 {
+            let count = format!(" ~{}", self.dirty);
+            cell.push(colours.git_dirty().paint(count.clone()), count.len());
+        }
+
+        if self.stashes > 0 {
+            let count = format!(" ${}", self.stashes);
+            cell.push(colours.stash().paint(count.clone()), count.len());
+        }
+
+        cell
+    }
+}
+
+#[cfg(any(feature = "git", feature = "gix"))]
+impl f::SubdirGitOperation {
+    /// The marker shown for this operation, in the style of Git’s own prompt.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Merge => "MERGING",
+            Self::Rebase => "REBASE",
+            Self::CherryPick => "CHERRY-PICKING",
+            Self::Revert => "REVERTING",
+            Self::Bisect => "BISECTING",
+            Self::ApplyMailbox => "AM",
+        }
     }
 }
 
@@ -87,17 +180,24 @@ impl f::SubdirGitRepoStatus {
     }
 }
 
+// The branch and operation styles go unused when there’s no repository to
+// read them from.
+#[cfg_attr(not(any(feature = "git", feature = "gix")), allow(dead_code))]
 pub trait RepoColours {
     fn branch_main(&self) -> Style;
     fn branch_other(&self) -> Style;
     fn no_repo(&self) -> Style;
     fn git_clean(&self) -> Style;
     fn git_dirty(&self) -> Style;
+    fn detached(&self) -> Style;
+    fn ahead_behind(&self) -> Style;
+    fn stash(&self) -> Style;
+    fn operation(&self) -> Style;
 }
 
 #[cfg(test)]
 pub mod test {
-    use super::Colours;
+    use super::{Colours, RepoColours};
     use crate::fs::fields as f;
     use crate::output::cell::{DisplayWidth, TextCell};
 
@@ -131,6 +231,48 @@ pub mod test {
         fn conflicted(&self) -> Style {
             Fixed(97).normal()
         }
+        fn submodule(&self) -> Style {
+            Fixed(98).normal()
+        }
+        fn submodule_moved(&self) -> Style {
+            Fixed(99).normal()
+        }
+        fn worktree(&self) -> Style {
+            Fixed(100).normal()
+        }
+        fn lfs_pointer(&self) -> Style {
+            Fixed(101).normal()
+        }
+    }
+
+    impl RepoColours for TestColours {
+        fn branch_main(&self) -> Style {
+            Fixed(80).normal()
+        }
+        fn branch_other(&self) -> Style {
+            Fixed(81).normal()
+        }
+        fn no_repo(&self) -> Style {
+            Fixed(82).normal()
+        }
+        fn git_clean(&self) -> Style {
+            Fixed(83).normal()
+        }
+        fn git_dirty(&self) -> Style {
+            Fixed(84).normal()
+        }
+        fn detached(&self) -> Style {
+            Fixed(85).normal()
+        }
+        fn ahead_behind(&self) -> Style {
+            Fixed(86).normal()
+        }
+        fn stash(&self) -> Style {
+            Fixed(87).normal()
+        }
+        fn operation(&self) -> Style {
+            Fixed(88).normal()
+        }
     }
 
     #[test]
@@ -138,6 +280,7 @@ pub mod test {
         let stati = f::Git {
             staged: f::GitStatus::NotModified,
             unstaged: f::GitStatus::NotModified,
+            marker: None,
         };
 
         let expected = TextCell {
@@ -153,6 +296,7 @@ pub mod test {
         let stati = f::Git {
             staged: f::GitStatus::New,
             unstaged: f::GitStatus::Modified,
+            marker: None,
         };
 
         let expected = TextCell {
@@ -162,4 +306,106 @@ pub mod test {
 
         assert_eq!(expected, stati.render(&TestColours));
     }
+
+    #[test]
+    fn git_moved_submodule() {
+        let stati = f::Git {
+            staged: f::GitStatus::NotModified,
+            unstaged: f::GitStatus::Modified,
+            marker: Some(f::GitMarker::Submodule { moved: true }),
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(3),
+            contents: vec![
+                Fixed(90).paint("-"),
+                Fixed(92).paint("M"),
+                Fixed(99).paint("S"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(expected, stati.render(&TestColours));
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn diff_stat_unchanged() {
+        let expected = TextCell {
+            width: DisplayWidth::from(1),
+            contents: vec![Fixed(90).paint("-")].into(),
+        };
+
+        assert_eq!(expected, f::GitDiffStat::default().render(&TestColours));
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn diff_stat_changed() {
+        let stat = f::GitDiffStat {
+            added: 12,
+            removed: 0,
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(6),
+            contents: vec![
+                Fixed(91).paint("+12"),
+                Style::default().paint(" "),
+                Fixed(93).paint("-0"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(expected, stat.render(&TestColours));
+    }
+
+    #[test]
+    #[cfg(any(feature = "git", feature = "gix"))]
+    fn repo_clean_branch() {
+        let repo = f::SubdirGitRepo {
+            status: Some(f::SubdirGitRepoStatus::GitClean),
+            head: Some(f::SubdirGitHead::Branch("main".into())),
+            ..f::SubdirGitRepo::default()
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(6),
+            contents: vec![
+                Fixed(83).paint("|"),
+                Style::default().paint(" "),
+                Fixed(80).paint("main"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(expected, repo.render(&TestColours));
+    }
+
+    #[test]
+    #[cfg(any(feature = "git", feature = "gix"))]
+    fn repo_detached_everything() {
+        let repo = f::SubdirGitRepo {
+            status: None,
+            head: Some(f::SubdirGitHead::Tag("v1.0".into())),
+            ahead_behind: Some((0, 3)),
+            dirty: 0,
+            stashes: 2,
+            operation: Some(f::SubdirGitOperation::Rebase),
+        };
+
+        let expected = TextCell {
+            width: DisplayWidth::from(19),
+            contents: vec![
+                Fixed(85).paint("(v1.0)"),
+                Fixed(82).paint("|"),
+                Fixed(88).paint("REBASE"),
+                Fixed(86).paint(" ↓3"),
+                Fixed(87).paint(" $2"),
+            ]
+            .into(),
+        };
+
+        assert_eq!(expected, repo.render(&TestColours));
+    }
 }
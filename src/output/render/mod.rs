@@ -36,7 +36,9 @@ mod permissions_unix;
 mod permissions_windows;
 
 mod size;
-pub use self::size::Colours as SizeColours;
+pub use self::size::{Colours as SizeColours, prefix_for_power};
+
+mod usage;
 
 mod times;
 pub use self::times::Render as TimeRender;
@@ -4,105 +4,227 @@
 // SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
 // SPDX-FileCopyrightText: 2014 Benjamin Sago
 // SPDX-License-Identifier: MIT
+use std::fmt;
+
 use locale::Numeric as NumericLocale;
 use nu_ansi_term::Style;
-use unit_prefix::Prefix;
+use unit_prefix::{NumberPrefix, Prefix};
 
 use crate::fs::fields as f;
 use crate::output::cell::{DisplayWidth, TextCell};
 use crate::output::color_scale::{ColorScaleInformation, ColorScaleMode};
-use crate::output::table::SizeFormat;
+use crate::output::table::{SizeFormat, SizeOptions, SizeSuffix, ThousandsSeparators};
 
 impl f::Size {
     pub fn render<C: Colours>(
         self,
         colours: &C,
-        size_format: SizeFormat,
+        size_options: SizeOptions,
         numerics: &NumericLocale,
         color_scale_info: Option<ColorScaleInformation>,
     ) -> TextCell {
-        use unit_prefix::NumberPrefix;
-
         let size = match self {
             Self::Some(s) => s,
             Self::None => return TextCell::blank(colours.no_size()),
             Self::DeviceIDs(ref ids) => return ids.render(colours),
         };
 
-        let is_gradient_mode =
-            color_scale_info.is_some_and(|csi| csi.options.mode == ColorScaleMode::Gradient);
+        let FormattedSize {
+            prefix,
+            number,
+            unit,
+        } = size_options.format(size, numerics);
+
+        let (number_style, unit_style) = match color_scale_info {
+            Some(csi) if csi.options.mode == ColorScaleMode::Gradient => {
+                let style = csi.adjust_style(colours.size(prefix), size as f32, csi.size);
+                (style, style)
+            }
+            _ => (colours.size(prefix), colours.unit(prefix)),
+        };
+
+        if unit.is_empty() {
+            return TextCell::paint(number_style, number);
+        }
+
+        TextCell {
+            // unit is guaranteed to be ASCII since unit prefixes are hardcoded.
+            width: DisplayWidth::from(&*number) + unit.len(),
+            contents: vec![number_style.paint(number), unit_style.paint(unit)].into(),
+        }
+    }
+}
+
+/// A number of bytes, formatted for display.
+#[derive(PartialEq, Eq, Debug)]
+pub struct FormattedSize {
+    /// The prefix that fits the size, which picks the colour to use. With
+    /// bytes or a fixed unit, this isn’t necessarily the one shown.
+    pub prefix: Option<Prefix>,
+
+    /// The number, formatted for the locale.
+    pub number: String,
+
+    /// The unit shown after the number, which may be empty.
+    pub unit: String,
+}
+
+impl fmt::Display for FormattedSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.number, self.unit)
+    }
+}
+
+impl SizeOptions {
+    /// Formats a number of bytes with the chosen prefixes, unit, precision,
+    /// suffixes and digit grouping.
+    #[must_use]
+    pub fn format(self, size: u64, numerics: &NumericLocale) -> FormattedSize {
+        let bytes = size as f64;
+        let binary = match self.unit {
+            Some(unit) => is_binary(unit),
+            None => self.format == SizeFormat::BinaryBytes,
+        };
+
+        let fitting = if binary {
+            NumberPrefix::binary(bytes)
+        } else {
+            NumberPrefix::decimal(bytes)
+        };
+        let prefix = match fitting {
+            NumberPrefix::Standalone(_) => None,
+            NumberPrefix::Prefixed(p, _) => Some(p),
+        };
 
-        #[rustfmt::skip]
-        let result = match size_format {
-            SizeFormat::DecimalBytes  => NumberPrefix::decimal(size as f64),
-            SizeFormat::BinaryBytes   => NumberPrefix::binary(size as f64),
-            SizeFormat::JustBytes     => {
-                // Use the binary prefix to select a style.
-                let prefix = match NumberPrefix::binary(size as f64) {
+        let shown = match self.unit {
+            Some(unit) => NumberPrefix::Prefixed(unit, bytes / amount(unit)),
+            None if self.format == SizeFormat::JustBytes => {
+                // Use the binary prefix to select a style, but show all the
+                // digits.
+                let prefix = match NumberPrefix::binary(bytes) {
                     NumberPrefix::Standalone(_) => None,
                     NumberPrefix::Prefixed(p, _) => Some(p),
                 };
+                return FormattedSize {
+                    prefix,
+                    number: self.group_digits(&size.to_string(), numerics),
+                    unit: String::new(),
+                };
+            }
+            None => fitting,
+        };
 
-                // But format the number directly using the locale.
-                let string = numerics.format_int(size);
-
-                return if is_gradient_mode {
-                    let csi = color_scale_info.unwrap();
-                    TextCell::paint(
-                        csi.adjust_style(colours.size(prefix), size as f32, csi.size),
-                        string,
-                    )
+        let long = self.suffix == SizeSuffix::Long;
+        match shown {
+            NumberPrefix::Standalone(_) => FormattedSize {
+                prefix,
+                number: self.group_digits(&size.to_string(), numerics),
+                unit: if long { "B" } else { "" }.into(),
+            },
+            NumberPrefix::Prefixed(unit, n) => {
+                let decimals = match self.precision {
+                    Some(decimals) => decimals,
+                    None if n < 10_f64 => 1,
+                    None => 0,
+                };
+                let number = if decimals == 0 {
+                    (n.round() as u64).to_string()
                 } else {
-                    TextCell::paint(colours.size(prefix), string)
+                    format!("{n:.decimals$}")
+                };
+                let number = match number.split_once('.') {
+                    Some((whole, fraction)) => format!(
+                        "{}{}{fraction}",
+                        self.group_digits(whole, numerics),
+                        numerics.decimal_sep
+                    ),
+                    None => self.group_digits(&number, numerics),
+                };
+
+                let mut symbol = String::from(unit.symbol());
+                if long {
+                    symbol.push('B');
+                }
+
+                FormattedSize {
+                    prefix,
+                    number,
+                    unit: symbol,
                 }
             }
-        };
+        }
+    }
 
-        #[rustfmt::skip]
-        let (prefix, n) = match result {
-            NumberPrefix::Standalone(b) => {
-                return if is_gradient_mode {
-                    let csi = color_scale_info.unwrap();
-                    TextCell::paint(
-                        csi.adjust_style(colours.size(None), size as f32, csi.size),
-                        numerics.format_int(b),
-                    )
+    /// Groups a string of digits into thousands, using the separator picked
+    /// by the options.
+    fn group_digits(self, digits: &str, numerics: &NumericLocale) -> String {
+        let separator = match self.separators {
+            ThousandsSeparators::Never => "",
+            ThousandsSeparators::Always if numerics.thousands_sep.is_empty() => {
+                if numerics.decimal_sep == "," {
+                    "."
                 } else {
-                    TextCell::paint(colours.size(None), numerics.format_int(b))
+                    ","
                 }
             }
-            NumberPrefix::Prefixed(p, n)  => (p, n),
-        };
-
-        let symbol = prefix.symbol();
-        let number = if n < 10_f64 {
-            numerics.format_float(n, 1)
-        } else {
-            numerics.format_int(n.round() as isize)
+            ThousandsSeparators::Locale | ThousandsSeparators::Always => &numerics.thousands_sep,
         };
 
-        TextCell {
-            // symbol is guaranteed to be ASCII since unit prefixes are hardcoded.
-            width: DisplayWidth::from(&*number) + symbol.len(),
-            contents: if is_gradient_mode {
-                let csi = color_scale_info.unwrap();
-                vec![
-                    csi.adjust_style(colours.size(Some(prefix)), size as f32, csi.size)
-                        .paint(number),
-                    csi.adjust_style(colours.size(Some(prefix)), size as f32, csi.size)
-                        .paint(symbol),
-                ]
-            } else {
-                vec![
-                    colours.size(Some(prefix)).paint(number),
-                    colours.unit(Some(prefix)).paint(symbol),
-                ]
+        let mut grouped = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push_str(separator);
             }
-            .into(),
+            grouped.push(c);
         }
+        grouped
     }
 }
 
+const DECIMAL_PREFIXES: [Prefix; 6] = [
+    Prefix::Kilo,
+    Prefix::Mega,
+    Prefix::Giga,
+    Prefix::Tera,
+    Prefix::Peta,
+    Prefix::Exa,
+];
+
+const BINARY_PREFIXES: [Prefix; 6] = [
+    Prefix::Kibi,
+    Prefix::Mebi,
+    Prefix::Gibi,
+    Prefix::Tebi,
+    Prefix::Pebi,
+    Prefix::Exbi,
+];
+
+/// The prefix for 1000 or 1024 to the given power, from one to six.
+#[must_use]
+pub fn prefix_for_power(power: u8, binary: bool) -> Option<Prefix> {
+    let prefixes = if binary {
+        &BINARY_PREFIXES
+    } else {
+        &DECIMAL_PREFIXES
+    };
+    prefixes.get(usize::from(power).checked_sub(1)?).copied()
+}
+
+fn is_binary(prefix: Prefix) -> bool {
+    BINARY_PREFIXES.contains(&prefix)
+}
+
+/// The number of bytes in one of the given unit.
+fn amount(prefix: Prefix) -> f64 {
+    let (base, prefixes) = if is_binary(prefix) {
+        (1024_f64, &BINARY_PREFIXES)
+    } else {
+        (1000_f64, &DECIMAL_PREFIXES)
+    };
+    let power = prefixes.iter().position(|p| *p == prefix).unwrap_or(0) + 1;
+    base.powi(power as i32)
+}
+
 impl f::DeviceIDs {
     fn render<C: Colours>(self, colours: &C) -> TextCell {
         let major = self.major.to_string();
@@ -135,7 +257,7 @@ pub mod test {
     use super::Colours;
     use crate::fs::fields as f;
     use crate::output::cell::{DisplayWidth, TextCell};
-    use crate::output::table::SizeFormat;
+    use crate::output::table::{SizeFormat, SizeOptions, SizeSuffix, ThousandsSeparators};
 
     use locale::Numeric as NumericLocale;
     use nu_ansi_term::Color::*;
@@ -163,7 +285,7 @@ pub mod test {
             expected,
             directory.render(
                 &TestColours,
-                SizeFormat::JustBytes,
+                SizeFormat::JustBytes.into(),
                 &NumericLocale::english(),
                 None
             )
@@ -182,7 +304,7 @@ pub mod test {
             expected,
             directory.render(
                 &TestColours,
-                SizeFormat::DecimalBytes,
+                SizeFormat::DecimalBytes.into(),
                 &NumericLocale::english(),
                 None
             )
@@ -201,7 +323,7 @@ pub mod test {
             expected,
             directory.render(
                 &TestColours,
-                SizeFormat::BinaryBytes,
+                SizeFormat::BinaryBytes.into(),
                 &NumericLocale::english(),
                 None
             )
@@ -220,7 +342,7 @@ pub mod test {
             expected,
             directory.render(
                 &TestColours,
-                SizeFormat::JustBytes,
+                SizeFormat::JustBytes.into(),
                 &NumericLocale::english(),
                 None
             )
@@ -247,10 +369,92 @@ pub mod test {
             expected,
             directory.render(
                 &TestColours,
-                SizeFormat::JustBytes,
+                SizeFormat::JustBytes.into(),
                 &NumericLocale::english(),
                 None
             )
         );
     }
+
+    fn text(options: SizeOptions, size: u64) -> String {
+        options.format(size, &NumericLocale::english()).to_string()
+    }
+
+    #[test]
+    fn fixed_unit() {
+        let options = SizeOptions {
+            unit: Some(Prefix::Mega),
+            ..SizeOptions::default()
+        };
+        assert_eq!(text(options, 2_100_000), "2.1M");
+        assert_eq!(text(options, 1_234_567_890), "1,235M");
+        assert_eq!(text(options, 3), "0.0M");
+    }
+
+    #[test]
+    fn fixed_precision() {
+        let options = SizeOptions {
+            precision: Some(2),
+            ..SizeOptions::default()
+        };
+        assert_eq!(text(options, 2_100_000), "2.10M");
+        assert_eq!(text(options, 123_456_789), "123.46M");
+        assert_eq!(text(options, 999), "999");
+
+        let options = SizeOptions {
+            precision: Some(0),
+            ..options
+        };
+        assert_eq!(text(options, 2_600_000), "3M");
+    }
+
+    #[test]
+    fn long_suffixes() {
+        let si = SizeOptions {
+            suffix: SizeSuffix::Long,
+            ..SizeOptions::default()
+        };
+        let iec = SizeOptions {
+            format: SizeFormat::BinaryBytes,
+            ..si
+        };
+        assert_eq!(text(si, 2_100_000), "2.1MB");
+        assert_eq!(text(si, 12_000), "12kB");
+        assert_eq!(text(si, 512), "512B");
+        assert_eq!(text(iec, 1_048_576), "1.0MiB");
+        assert_eq!(text(iec, 2048), "2.0KiB");
+    }
+
+    #[test]
+    fn thousands_separators() {
+        let bytes = SizeOptions::from(SizeFormat::JustBytes);
+        let never = SizeOptions {
+            separators: ThousandsSeparators::Never,
+            ..bytes
+        };
+        let always = SizeOptions {
+            separators: ThousandsSeparators::Always,
+            ..bytes
+        };
+        let unseparated = NumericLocale::new(".", "");
+
+        assert_eq!(text(bytes, 1_048_576), "1,048,576");
+        assert_eq!(text(never, 1_048_576), "1048576");
+        assert_eq!(bytes.format(1_048_576, &unseparated).to_string(), "1048576");
+        assert_eq!(
+            always.format(1_048_576, &unseparated).to_string(),
+            "1,048,576"
+        );
+    }
+
+    #[test]
+    fn fixed_unit_picks_colour_by_size() {
+        let options = SizeOptions {
+            unit: Some(Prefix::Gibi),
+            ..SizeOptions::default()
+        };
+        let formatted = options.format(3 * 1024, &NumericLocale::english());
+        assert_eq!(formatted.prefix, Some(Prefix::Kibi));
+        assert_eq!(formatted.unit, "Gi");
+    }
 }
@@ -0,0 +1,222 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+use locale::Numeric as NumericLocale;
+use unit_prefix::{NumberPrefix, Prefix};
+
+use super::SizeColours as Colours;
+use crate::fs::fields as f;
+use crate::output::cell::{DisplayWidth, TextCell};
+use crate::output::color_scale::{ColorScaleInformation, ColorScaleMode};
+
+/// How many characters wide the bar is when a file takes up everything.
+const BAR_WIDTH: usize = 10;
+
+/// The characters for each eighth of a bar’s last character, from one eighth
+/// to a full block.
+const EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// The steps of the fixed colour scale: a share of at least each fraction
+/// gets the size colour for the prefix next to it, and anything smaller
+/// gets the colour for plain bytes.
+const FIXED_STEPS: [(f64, Prefix); 4] = [
+    (0.5, Prefix::Tebi),
+    (0.25, Prefix::Gibi),
+    (0.1, Prefix::Mebi),
+    (0.01, Prefix::Kibi),
+];
+
+impl f::Usage {
+    /// Renders the percentage, padded so they line up, followed by the bar.
+    /// Both are coloured like the file’s size would be, unless the size is
+    /// being colour scaled: then the gradient shades them by how the share
+    /// compares with the others, and the fixed scale picks the size colour
+    /// from the share’s step instead.
+    pub fn render<C: Colours>(
+        self,
+        colours: &C,
+        numerics: &NumericLocale,
+        color_scale_info: Option<ColorScaleInformation>,
+    ) -> TextCell {
+        let (Some(size), Some(ratio)) = (self.size, self.ratio()) else {
+            return TextCell::blank(colours.no_size());
+        };
+
+        let prefix = match NumberPrefix::binary(size as f64) {
+            NumberPrefix::Standalone(_) => None,
+            NumberPrefix::Prefixed(p, _) => Some(p),
+        };
+
+        let style = match color_scale_info {
+            Some(csi) if csi.options.mode == ColorScaleMode::Gradient => {
+                csi.adjust_style(colours.size(prefix), ratio as f32, csi.usage)
+            }
+            Some(csi) if csi.options.size => colours.size(fixed_step(ratio)),
+            _ => colours.size(prefix),
+        };
+
+        let percentage = format!(
+            "{:>5}%",
+            numerics.format_float((ratio * 100.0).min(100.0), 1)
+        );
+        let bar = bar(ratio);
+
+        TextCell {
+            width: DisplayWidth::from(&*percentage) + 1 + bar.chars().count(),
+            contents: vec![style.paint(percentage), style.paint(format!(" {bar}"))].into(),
+        }
+    }
+}
+
+/// The prefix whose size colour a share gets in the fixed colour scale.
+fn fixed_step(ratio: f64) -> Option<Prefix> {
+    FIXED_STEPS
+        .iter()
+        .find(|(fraction, _)| ratio >= *fraction)
+        .map(|&(_, prefix)| prefix)
+}
+
+/// A bar that’s as long as the given fraction of `BAR_WIDTH`, to the
+/// nearest eighth of a character.
+fn bar(ratio: f64) -> String {
+    let eighths = (ratio.clamp(0.0, 1.0) * (BAR_WIDTH * 8) as f64).round() as usize;
+    let mut bar = EIGHTHS[7].to_string().repeat(eighths / 8);
+    if let Some(part) = (eighths % 8).checked_sub(1) {
+        bar.push(EIGHTHS[part]);
+    }
+    bar
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::Colours;
+    use crate::fs::fields as f;
+    use crate::output::cell::{DisplayWidth, TextCell};
+    use crate::output::color_scale::{ColorScaleInformation, ColorScaleMode, ColorScaleOptions};
+
+    use locale::Numeric as NumericLocale;
+    use nu_ansi_term::Color::*;
+    use nu_ansi_term::Style;
+    use unit_prefix::Prefix;
+
+    struct TestColours;
+
+    #[rustfmt::skip]
+    impl Colours for TestColours {
+        fn size(&self, prefix: Option<Prefix>) -> Style {
+            match prefix {
+                None               => Fixed(66).normal(),
+                Some(Prefix::Kibi) => Fixed(67).normal(),
+                Some(Prefix::Mebi) => Fixed(68).normal(),
+                Some(Prefix::Gibi) => Fixed(69).normal(),
+                Some(_)            => Fixed(70).normal(),
+            }
+        }
+        fn unit(&self, _prefix: Option<Prefix>) -> Style { Fixed(77).bold() }
+        fn no_size(&self)                       -> Style { Black.italic() }
+
+        fn major(&self) -> Style { Blue.on(Red) }
+        fn comma(&self) -> Style { Green.italic() }
+        fn minor(&self) -> Style { Cyan.on(Yellow) }
+    }
+
+    #[test]
+    fn no_size() {
+        let usage = f::Usage {
+            size: None,
+            total: 100,
+        };
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(
+            expected,
+            usage.render(&TestColours, &NumericLocale::english(), None)
+        );
+    }
+
+    #[test]
+    fn nothing_to_share() {
+        let usage = f::Usage {
+            size: Some(0),
+            total: 0,
+        };
+        let expected = TextCell::blank(Black.italic());
+        assert_eq!(
+            expected,
+            usage.render(&TestColours, &NumericLocale::english(), None)
+        );
+    }
+
+    #[test]
+    fn partial_bar() {
+        let usage = f::Usage {
+            size: Some(125),
+            total: 1000,
+        };
+        let expected = TextCell {
+            width: DisplayWidth::from(9),
+            contents: vec![Fixed(66).paint(" 12.5%"), Fixed(66).paint(" █▎")].into(),
+        };
+        assert_eq!(
+            expected,
+            usage.render(&TestColours, &NumericLocale::english(), None)
+        );
+    }
+
+    #[test]
+    fn everything() {
+        let usage = f::Usage {
+            size: Some(1000),
+            total: 1000,
+        };
+        let expected = TextCell {
+            width: DisplayWidth::from(17),
+            contents: vec![Fixed(66).paint("100.0%"), Fixed(66).paint(" ██████████")].into(),
+        };
+        assert_eq!(
+            expected,
+            usage.render(&TestColours, &NumericLocale::english(), None)
+        );
+    }
+
+    fn fixed_scale(size: bool) -> Option<ColorScaleInformation> {
+        Some(ColorScaleInformation {
+            options: ColorScaleOptions {
+                mode: ColorScaleMode::Fixed,
+                size,
+                ..ColorScaleOptions::default()
+            },
+            accessed: None,
+            changed: None,
+            created: None,
+            modified: None,
+            size: None,
+            usage: None,
+            entries: None,
+        })
+    }
+
+    #[test]
+    fn fixed_scale_steps() {
+        let colour = |size, scale| {
+            let usage = f::Usage {
+                size: Some(size),
+                total: 1000,
+            };
+            let cell = usage.render(&TestColours, &NumericLocale::english(), scale);
+            cell.contents[0].style_ref().foreground
+        };
+
+        assert_eq!(colour(5, fixed_scale(true)), Some(Fixed(66)));
+        assert_eq!(colour(50, fixed_scale(true)), Some(Fixed(67)));
+        assert_eq!(colour(125, fixed_scale(true)), Some(Fixed(68)));
+        assert_eq!(colour(250, fixed_scale(true)), Some(Fixed(69)));
+        assert_eq!(colour(1000, fixed_scale(true)), Some(Fixed(70)));
+
+        // Without the size being scaled, the share doesn’t change its colour.
+        assert_eq!(colour(1000, fixed_scale(false)), Some(Fixed(66)));
+        assert_eq!(colour(1000, None), Some(Fixed(66)));
+    }
+}
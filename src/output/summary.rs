@@ -0,0 +1,186 @@
+// SPDX-FileCopyrightText: 2024 Christina Sørensen
+// SPDX-License-Identifier: EUPL-1.2
+//
+// SPDX-FileCopyrightText: 2023-2024 Christina Sørensen, eza contributors
+// SPDX-FileCopyrightText: 2014 Benjamin Sago
+// SPDX-License-Identifier: MIT
+//! The summary footer, which adds up the entries of each listed directory:
+//! how many files, directories and symbolic links were shown, how many were
+//! left out, and how big they are.
+
+use std::slice;
+
+use crate::fs::filter::FileFilter;
+use crate::fs::{Dir, File};
+use crate::output::table::{self, SizeOptions};
+
+/// Options for the summary footer.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct Options {
+    /// How to display the total size.
+    pub size: SizeOptions,
+}
+
+/// The totals for one listing.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct Summary {
+    /// The number of files shown, which is everything that isn’t a
+    /// directory or a symbolic link.
+    pub files: u64,
+
+    /// The number of directories shown.
+    pub dirs: u64,
+
+    /// The number of symbolic links shown.
+    pub symlinks: u64,
+
+    /// The number of dotfiles left out because `--all` wasn’t given.
+    pub hidden: u64,
+
+    /// The number of entries left out by `--ignore-glob`.
+    pub ignored: u64,
+
+    /// The total size of the entries shown, in bytes.
+    pub size: u64,
+}
+
+impl Summary {
+    /// The footer line for these totals.
+    #[must_use]
+    pub fn describe(&self, options: &Options) -> String {
+        let plural = |count: u64, one: &str, many: &str| {
+            format!("{count} {}", if count == 1 { one } else { many })
+        };
+
+        format!(
+            "{}, {}, {}, {} hidden, {} ignored, total size {}",
+            plural(self.files, "file", "files"),
+            plural(self.dirs, "directory", "directories"),
+            plural(self.symlinks, "symlink", "symlinks"),
+            self.hidden,
+            self.ignored,
+            options.size.text(self.size),
+        )
+    }
+
+    /// Adds the entries of a directory, given the ones that are shown after
+    /// filtering. The dotfiles and ignored entries left out are counted from
+    /// the names the directory has already read. The sizes are left out
+    /// when they’ve already been counted as part of the directory’s own size.
+    pub fn add_dir(
+        &mut self,
+        dir: &Dir,
+        files: &[File<'_>],
+        filter: &FileFilter,
+        count_size: bool,
+    ) {
+        self.hidden += dir.hidden_count(filter.dot_filter) as u64;
+        self.ignored += dir.ignored_count(filter.dot_filter, &filter.ignore_patterns) as u64;
+        self.add_files(files, count_size);
+    }
+
+    /// Adds files that are shown, along with their sizes if asked to.
+    pub fn add_files(&mut self, files: &[File<'_>], count_size: bool) {
+        if count_size {
+            self.size += table::usage_total(files);
+        }
+
+        for file in files.iter().filter(|f| !f.is_all_all && !f.is_ghost) {
+            if file.is_link() {
+                self.symlinks += 1;
+            } else if file.is_directory() {
+                self.dirs += 1;
+            } else {
+                self.files += 1;
+            }
+        }
+    }
+
+    /// Adds the root of a tree that has its contents drawn under it. The
+    /// root isn’t counted itself, but its size is used when `--total-size`
+    /// gave it one, as that counts each hard link beneath it only once.
+    pub fn add_tree_root(&mut self, root: &File<'_>) {
+        if root.is_recursive_size() {
+            self.size += table::usage_total(slice::from_ref(root));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fs::fields::Size;
+    use crate::fs::filter::Recursion;
+    use crate::fs::recursive_size::TotalSize;
+    use crate::fs::test::TestDir;
+    use crate::options::parser::test::mock_cli;
+
+    #[test]
+    fn describes_totals() {
+        let summary = Summary {
+            files: 1,
+            dirs: 2,
+            symlinks: 0,
+            hidden: 3,
+            ignored: 1,
+            size: 2_100_000,
+        };
+        assert_eq!(
+            summary.describe(&Options::default()),
+            "1 file, 2 directories, 0 symlinks, 3 hidden, 1 ignored, total size 2.1M"
+        );
+    }
+
+    #[test]
+    fn counts_a_directory() {
+        let root = TestDir::create();
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("one"), [0; 100]).unwrap();
+        std::fs::write(root.join("two.log"), [0; 10]).unwrap();
+        std::fs::write(root.join(".hidden"), [0; 1]).unwrap();
+        std::fs::write(root.join(".hidden.log"), [0; 1]).unwrap();
+
+        let filter = FileFilter::deduce(&mock_cli(vec!["-I", "*.log"]), false).unwrap();
+        let dir = Dir::read_dir(root.to_path_buf()).unwrap();
+        let mut files = dir
+            .files(filter.dot_filter, None, false, false, TotalSize::Off)
+            .collect::<Vec<_>>();
+        filter.filter_child_files(Recursion::Off, &mut files, None);
+
+        let mut summary = Summary::default();
+        summary.add_dir(&dir, &files, &filter, true);
+        assert_eq!(
+            summary,
+            Summary {
+                files: 1,
+                dirs: 1,
+                symlinks: 0,
+                hidden: 2,
+                ignored: 1,
+                size: 100,
+            }
+        );
+    }
+
+    #[test]
+    fn tree_root_counts_hard_links_once() {
+        let root = TestDir::create();
+        std::fs::write(root.join("data"), [0; 1000]).unwrap();
+        std::fs::hard_link(root.join("data"), root.join("link")).unwrap();
+
+        // The root’s own size is used rather than adding up the entries
+        // under it, which would count the file twice.
+        let file = File::from_args(root.to_path_buf(), None, None, false, TotalSize::All, None);
+        let Size::Some(size) = file.size() else {
+            panic!("no recursive size");
+        };
+        let mut summary = Summary::default();
+        summary.add_tree_root(&file);
+        assert_eq!(summary.size, size);
+
+        let file = File::from_args(root.to_path_buf(), None, None, false, TotalSize::Off, None);
+        let mut summary = Summary::default();
+        summary.add_tree_root(&file);
+        assert_eq!(summary.size, 0);
+    }
+}
@@ -13,10 +13,12 @@ use chrono::prelude::*;
 
 use log::debug;
 use std::sync::LazyLock;
+use unit_prefix::Prefix;
 #[cfg(unix)]
 use uzers::UsersCache;
 
 use crate::fs::feature::git::GitCache;
+use crate::fs::filter::FileFilter;
 use crate::fs::{File, fields as f};
 use crate::options::Vars;
 use crate::options::vars::EZA_WINDOWS_ATTRIBUTES;
@@ -25,7 +27,7 @@ use crate::output::color_scale::ColorScaleInformation;
 #[cfg(unix)]
 use crate::output::render::{GroupRender, OctalPermissionsRender, UserRender};
 use crate::output::render::{PermissionsPlusRender, TimeRender};
-use crate::output::time::TimeFormat;
+use crate::output::time::{TimeFormat, rfc3339};
 use crate::theme::Theme;
 
 use super::color_scale::ColorScaleMode;
@@ -33,7 +35,7 @@ use super::color_scale::ColorScaleMode;
 /// Options for displaying a table.
 #[derive(PartialEq, Eq, Debug)]
 pub struct Options {
-    pub size_format: SizeFormat,
+    pub size: SizeOptions,
     pub time_format: TimeFormat,
     pub user_format: UserFormat,
     pub group_format: GroupFormat,
@@ -43,7 +45,7 @@ pub struct Options {
 
 /// Extra columns to display in the table.
 #[allow(clippy::struct_excessive_bools)]
-#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Columns {
     /// At least one of these timestamps will be shown.
     pub time_types: TimeTypes,
@@ -56,19 +58,45 @@ pub struct Columns {
     pub git: bool,
     pub subdir_git_repos: bool,
     pub subdir_git_repos_no_stat: bool,
+    pub git_commit: bool,
+    pub git_diffstat: bool,
     pub octal: bool,
     pub security_context: bool,
     pub file_flags: bool,
+    pub usage: bool,
+    pub entries: bool,
 
     // Defaults to true:
     pub permissions: bool,
     pub filesize: bool,
     pub user: bool,
+
+    /// The exact columns to show, in order, as given with `--columns`.
+    /// This takes precedence over all the flags above, except that the Git
+    /// columns are still only shown when Git is available.
+    pub order: Option<Vec<Column>>,
 }
 
 impl Columns {
     #[must_use]
     pub fn collect(&self, actually_enable_git: bool, git_repos: bool) -> Vec<Column> {
+        if let Some(order) = &self.order {
+            return order
+                .iter()
+                .filter(|column| match column {
+                    Column::GitStatus => self.git && actually_enable_git,
+                    Column::SubdirGitRepo(true) => self.subdir_git_repos && git_repos,
+                    Column::SubdirGitRepo(false) => self.subdir_git_repos_no_stat && git_repos,
+                    #[cfg(feature = "git")]
+                    Column::LastCommit(_) => self.git_commit && actually_enable_git,
+                    #[cfg(feature = "git")]
+                    Column::GitDiffStat => self.git_diffstat && actually_enable_git,
+                    _ => true,
+                })
+                .copied()
+                .collect();
+        }
+
         let mut columns = Vec::with_capacity(4);
 
         if self.inode {
@@ -94,6 +122,14 @@ impl Columns {
             columns.push(Column::FileSize);
         }
 
+        if self.usage {
+            columns.push(Column::Usage);
+        }
+
+        if self.entries {
+            columns.push(Column::Entries);
+        }
+
         if self.blocksize {
             #[cfg(unix)]
             columns.push(Column::Blocksize);
@@ -138,6 +174,18 @@ impl Columns {
             columns.push(Column::GitStatus);
         }
 
+        #[cfg(feature = "git")]
+        if self.git_diffstat && actually_enable_git {
+            columns.push(Column::GitDiffStat);
+        }
+
+        #[cfg(feature = "git")]
+        if self.git_commit && actually_enable_git {
+            columns.push(Column::LastCommit(CommitField::Time));
+            columns.push(Column::LastCommit(CommitField::Author));
+            columns.push(Column::LastCommit(CommitField::Hash));
+        }
+
         if self.subdir_git_repos && git_repos {
             columns.push(Column::SubdirGitRepo(true));
         }
@@ -151,10 +199,12 @@ impl Columns {
 }
 
 /// A table contains these.
-#[derive(Debug, Copy, Clone)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum Column {
     Permissions,
     FileSize,
+    Usage,
+    Entries,
     Timestamp(TimeType),
     #[cfg(unix)]
     Blocksize,
@@ -168,6 +218,10 @@ pub enum Column {
     Inode,
     GitStatus,
     SubdirGitRepo(bool),
+    #[cfg(feature = "git")]
+    LastCommit(CommitField),
+    #[cfg(feature = "git")]
+    GitDiffStat,
     #[cfg(unix)]
     Octal,
     #[cfg(unix)]
@@ -190,9 +244,14 @@ impl Column {
     pub fn alignment(self) -> Alignment {
         #[allow(clippy::wildcard_in_or_patterns)]
         match self {
-            Self::FileSize | Self::HardLinks | Self::Inode | Self::Blocksize | Self::GitStatus => {
-                Alignment::Right
-            }
+            Self::FileSize
+            | Self::Entries
+            | Self::HardLinks
+            | Self::Inode
+            | Self::Blocksize
+            | Self::GitStatus => Alignment::Right,
+            #[cfg(feature = "git")]
+            Self::GitDiffStat => Alignment::Right,
             Self::Timestamp(_) | _ => Alignment::Left,
         }
     }
@@ -200,11 +259,25 @@ impl Column {
     #[cfg(windows)]
     pub fn alignment(self) -> Alignment {
         match self {
-            Self::FileSize | Self::GitStatus => Alignment::Right,
+            Self::FileSize | Self::Entries | Self::GitStatus => Alignment::Right,
+            #[cfg(feature = "git")]
+            Self::GitDiffStat => Alignment::Right,
             _ => Alignment::Left,
         }
     }
 
+    /// Whether this column shows information from Git, rather than from the
+    /// filesystem. These are the only columns filled in for ghost entries.
+    #[must_use]
+    pub fn is_git(self) -> bool {
+        match self {
+            Self::GitStatus | Self::SubdirGitRepo(_) => true,
+            #[cfg(feature = "git")]
+            Self::LastCommit(_) | Self::GitDiffStat => true,
+            _ => false,
+        }
+    }
+
     /// Get the text that should be printed at the top, when the user elects
     /// to have a header row printed.
     #[must_use]
@@ -215,6 +288,8 @@ impl Column {
             #[cfg(windows)]
             Self::Permissions => "Mode",
             Self::FileSize => "Size",
+            Self::Usage => "Usage",
+            Self::Entries => "Entries",
             Self::Timestamp(t) => t.header(),
             #[cfg(unix)]
             Self::Blocksize => "Blocksize",
@@ -228,6 +303,10 @@ impl Column {
             Self::Inode => "inode",
             Self::GitStatus => "Git",
             Self::SubdirGitRepo(_) => "Git Repo",
+            #[cfg(feature = "git")]
+            Self::LastCommit(f) => f.header(),
+            #[cfg(feature = "git")]
+            Self::GitDiffStat => "Changes",
             #[cfg(unix)]
             Self::Octal => "Octal",
             #[cfg(unix)]
@@ -254,6 +333,71 @@ pub enum SizeFormat {
     JustBytes,
 }
 
+/// Options for displaying file sizes, shared by the size and blocksize
+/// columns and anywhere else a number of bytes gets shown.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct SizeOptions {
+    /// Which kind of prefixes to pick from.
+    pub format: SizeFormat,
+
+    /// A prefix to always use, no matter how big the size is, instead of
+    /// picking the one that fits.
+    pub unit: Option<Prefix>,
+
+    /// How many decimal places to show. Without one, sizes under ten get one
+    /// decimal place and the rest are rounded to a whole number.
+    pub precision: Option<usize>,
+
+    /// How the units are spelt.
+    pub suffix: SizeSuffix,
+
+    /// When to group the digits of a size into thousands.
+    pub separators: ThousandsSeparators,
+}
+
+impl From<SizeFormat> for SizeOptions {
+    fn from(format: SizeFormat) -> Self {
+        Self {
+            format,
+            ..Self::default()
+        }
+    }
+}
+
+impl SizeOptions {
+    /// Formats a number of bytes as plain text, for the places outside the
+    /// table that show one, such as the length of an extended attribute.
+    #[must_use]
+    pub fn text(self, bytes: u64) -> String {
+        self.format(bytes, &ENVIRONMENT.numeric).to_string()
+    }
+}
+
+/// How the units of file sizes are spelt.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum SizeSuffix {
+    /// Just the prefix, such as “k” or “Mi”, and nothing for bytes.
+    #[default]
+    Short,
+
+    /// The full unit symbol, such as “kB” or “MiB”, and “B” for bytes.
+    Long,
+}
+
+/// When to group the digits of a size into thousands.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub enum ThousandsSeparators {
+    /// Use the locale’s separator, which may be nothing at all.
+    #[default]
+    Locale,
+
+    /// Use the locale’s separator, or a comma if it doesn’t have one.
+    Always,
+
+    /// Never group the digits.
+    Never,
+}
+
 /// Formatting options for user and group.
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum UserFormat {
@@ -312,6 +456,34 @@ impl TimeType {
     }
 }
 
+/// The parts of a file’s last Git commit that can be shown in their own
+/// columns.
+#[cfg(feature = "git")]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum CommitField {
+    /// The time the commit was made.
+    Time,
+
+    /// The name of the commit’s author.
+    Author,
+
+    /// The commit’s abbreviated hash.
+    Hash,
+}
+
+#[cfg(feature = "git")]
+impl CommitField {
+    /// Returns the text to use for a column’s heading in the columns output.
+    #[must_use]
+    pub fn header(self) -> &'static str {
+        match self {
+            Self::Time => "Date Committed",
+            Self::Author => "Author",
+            Self::Hash => "Commit",
+        }
+    }
+}
+
 /// How display file flags.
 #[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
 pub enum FlagsFormat {
@@ -410,13 +582,14 @@ pub struct Table<'a> {
     env: &'a Environment,
     widths: TableWidths,
     time_format: TimeFormat,
-    size_format: SizeFormat,
+    size: SizeOptions,
     #[cfg(unix)]
     user_format: UserFormat,
     #[cfg(unix)]
     group_format: GroupFormat,
     flags_format: FlagsFormat,
     git: Option<&'a GitCache>,
+    filter: &'a FileFilter,
 }
 
 #[derive(Clone)]
@@ -429,6 +602,7 @@ impl<'a> Table<'a> {
     pub fn new(
         options: &'a Options,
         git: Option<&'a GitCache>,
+        filter: &'a FileFilter,
         theme: &'a Theme,
         git_repos: bool,
     ) -> Table<'a> {
@@ -443,9 +617,10 @@ impl<'a> Table<'a> {
             widths,
             columns,
             git,
+            filter,
             env,
             time_format: options.time_format.clone(),
-            size_format: options.size_format,
+            size: options.size,
             #[cfg(unix)]
             user_format: options.user_format,
             #[cfg(unix)]
@@ -470,21 +645,39 @@ impl<'a> Table<'a> {
         Row { cells }
     }
 
+    /// The cells for this file, where `usage_total` is the [`usage_total`] of
+    /// the files listed alongside it.
     pub fn row_for_file(
         &self,
         file: &File<'_>,
         xattrs: bool,
         color_scale_info: Option<ColorScaleInformation>,
+        usage_total: u64,
     ) -> Row {
         let cells = self
             .columns
             .iter()
-            .map(|c| self.display(file, *c, xattrs, color_scale_info))
+            .map(|c| self.display(file, *c, xattrs, color_scale_info, usage_total))
             .collect();
 
         Row { cells }
     }
 
+    /// The text of each column’s heading, without any styling.
+    pub fn raw_header(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.columns.iter().map(|c| c.header())
+    }
+
+    /// The value of each column for this file, unpadded and unstyled, with
+    /// numbers and timestamps left un-humanised.
+    #[must_use]
+    pub fn raw_row_for_file(&self, file: &File<'_>, usage_total: u64) -> Vec<String> {
+        self.columns
+            .iter()
+            .map(|c| self.raw(file, *c, usage_total))
+            .collect()
+    }
+
     pub fn add_widths(&mut self, row: &Row) {
         self.widths.add_widths(row);
     }
@@ -521,24 +714,31 @@ impl<'a> Table<'a> {
         column: Column,
         xattrs: bool,
         color_scale_info: Option<ColorScaleInformation>,
+        usage_total: u64,
     ) -> TextCell {
+        // A ghost entry for a deleted file has nothing on disk to look at.
+        if file.is_ghost && !column.is_git() {
+            return TextCell::blank(self.theme.ui.punctuation());
+        }
+
         match column {
             Column::Permissions => self.permissions_plus(file, xattrs).render(self.theme),
-            Column::FileSize => file.size().render(
-                self.theme,
-                self.size_format,
-                &self.env.numeric,
-                color_scale_info,
-            ),
+            Column::FileSize => {
+                file.size()
+                    .render(self.theme, self.size, &self.env.numeric, color_scale_info)
+            }
+            Column::Usage => {
+                usage(file, usage_total).render(self.theme, &self.env.numeric, color_scale_info)
+            }
+            Column::Entries => self.entries(file, color_scale_info),
             #[cfg(unix)]
             Column::HardLinks => file.links().render(self.theme, &self.env.numeric),
             #[cfg(unix)]
             Column::Inode => file.inode().render(self.theme.ui.inode.unwrap_or_default()),
             #[cfg(unix)]
-            Column::Blocksize => {
-                file.blocksize()
-                    .render(self.theme, self.size_format, &self.env.numeric)
-            }
+            Column::Blocksize => file
+                .blocksize()
+                .render(self.theme, self.size, &self.env.numeric),
             #[cfg(unix)]
             Column::User => {
                 file.user()
@@ -559,6 +759,10 @@ impl<'a> Table<'a> {
                 .render(self.theme.ui.flags.unwrap_or_default(), self.flags_format),
             Column::GitStatus => self.git_status(file).render(self.theme),
             Column::SubdirGitRepo(status) => self.subdir_git_repo(file, status).render(self.theme),
+            #[cfg(feature = "git")]
+            Column::LastCommit(field) => self.last_commit(file, field),
+            #[cfg(feature = "git")]
+            Column::GitDiffStat => self.git_diff_stat(file).render(self.theme),
             #[cfg(unix)]
             Column::Octal => self
                 .octal_permissions(file)
@@ -581,14 +785,116 @@ impl<'a> Table<'a> {
         }
     }
 
+    fn raw(&self, file: &File<'_>, column: Column, usage_total: u64) -> String {
+        if file.is_ghost && !column.is_git() {
+            return String::new();
+        }
+
+        match column {
+            Column::FileSize => match file.size() {
+                f::Size::Some(bytes) => bytes.to_string(),
+                f::Size::None => String::new(),
+                f::Size::DeviceIDs(ids) => format!("{},{}", ids.major, ids.minor),
+            },
+            Column::Usage => usage(file, usage_total)
+                .ratio()
+                .map(|ratio| format!("{:.1}", ratio * 100.0))
+                .unwrap_or_default(),
+            Column::Entries => self
+                .filter
+                .count_entries(file, self.git)
+                .map(|count| count.to_string())
+                .unwrap_or_default(),
+            #[cfg(unix)]
+            Column::Blocksize => match file.blocksize() {
+                f::Blocksize::Some(bytes) => bytes.to_string(),
+                f::Blocksize::None => String::new(),
+            },
+            #[cfg(unix)]
+            Column::HardLinks => file.links().count.to_string(),
+            #[cfg(unix)]
+            Column::Inode => file.inode().0.to_string(),
+            Column::Timestamp(time_type) => time_type
+                .get_corresponding_time(file)
+                .map(rfc3339)
+                .unwrap_or_default(),
+            #[cfg(feature = "git")]
+            Column::LastCommit(CommitField::Time) => self
+                .git
+                .and_then(|g| g.last_commit(&file.path))
+                .map(|commit| rfc3339(commit.time))
+                .unwrap_or_default(),
+
+            // Everything else is already shown as-is, so just strip the styles.
+            _ => self.display(file, column, false, None, usage_total).plain(),
+        }
+    }
+
+    fn entries(
+        &self,
+        file: &File<'_>,
+        color_scale_info: Option<ColorScaleInformation>,
+    ) -> TextCell {
+        let Some(count) = self.filter.count_entries(file, self.git) else {
+            return TextCell::blank(self.theme.ui.punctuation());
+        };
+
+        let mut style = self.theme.ui.size.unwrap_or_default().number_byte();
+        if let Some(csi) = color_scale_info
+            && csi.options.mode == ColorScaleMode::Gradient
+        {
+            style = csi.adjust_style(style, count as f32, csi.entries);
+        }
+
+        TextCell::paint(style, self.env.numeric.format_int(count))
+    }
+
     fn git_status(&self, file: &File<'_>) -> f::Git {
         debug!("Getting Git status for file {:?}", file.path);
 
+        let prefix_lookup = file.is_directory() || file.is_ghost;
+        self.git
+            .map(|g| f::Git {
+                marker: g.marker(&file.path, prefix_lookup),
+                ..g.get(&file.path, prefix_lookup)
+            })
+            .unwrap_or_default()
+    }
+
+    #[cfg(feature = "git")]
+    fn git_diff_stat(&self, file: &File<'_>) -> f::GitDiffStat {
+        debug!("Getting Git diff stat for file {:?}", file.path);
+
         self.git
-            .map(|g| g.get(&file.path, file.is_directory()))
+            .map(|g| g.diff_stat(&file.path, file.is_directory() || file.is_ghost))
             .unwrap_or_default()
     }
 
+    #[cfg(feature = "git")]
+    fn last_commit(&self, file: &File<'_>, field: CommitField) -> TextCell {
+        debug!("Getting last commit for file {:?}", file.path);
+
+        let commit = self.git.and_then(|g| g.last_commit(&file.path));
+        let punctuation = self.theme.ui.punctuation();
+
+        match (field, commit) {
+            (CommitField::Time, commit) => commit.map(|c| c.time).render(
+                self.theme.ui.date.unwrap_or_default(),
+                self.env.time_offset,
+                self.time_format.clone(),
+            ),
+            (CommitField::Author, Some(commit)) => TextCell::paint(
+                self.theme.ui.git.unwrap_or_default().commit_author(),
+                commit.author,
+            ),
+            (CommitField::Hash, Some(commit)) => TextCell::paint(
+                self.theme.ui.git.unwrap_or_default().commit_hash(),
+                commit.hash,
+            ),
+            (_, None) => TextCell::blank(punctuation),
+        }
+    }
+
     fn subdir_git_repo(&self, file: &File<'_>, status: bool) -> f::SubdirGitRepo {
         debug!("Getting subdir repo status for path {:?}", file.path);
 
@@ -625,6 +931,32 @@ impl<'a> Table<'a> {
     }
 }
 
+/// The number of bytes a file adds to the usage column’s total, which is its
+/// size as shown in the size column. The `.` and `..` entries don’t count,
+/// as between them they’d hold everything else.
+fn usage_bytes(file: &File<'_>) -> Option<u64> {
+    match file.size() {
+        f::Size::Some(bytes) if !file.is_all_all => Some(bytes),
+        _ => None,
+    }
+}
+
+/// The total size of a group of files listed together, which the usage
+/// column shows each file’s share of.
+#[must_use]
+pub fn usage_total(files: &[File<'_>]) -> u64 {
+    files.iter().filter_map(usage_bytes).sum()
+}
+
+/// A file’s share of the total size of the files listed alongside it.
+#[must_use]
+pub fn usage(file: &File<'_>, total: u64) -> f::Usage {
+    f::Usage {
+        size: usage_bytes(file),
+        total,
+    }
+}
+
 pub struct TableWidths(Vec<usize>);
 
 impl Deref for TableWidths {
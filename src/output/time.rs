@@ -61,6 +61,13 @@ pub enum TimeFormat {
     },
 }
 
+/// Formats a timestamp as an RFC 3339 string in UTC, for output meant to be
+/// read by other programs rather than people.
+#[must_use]
+pub fn rfc3339(time: NaiveDateTime) -> String {
+    time.and_utc().to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
 impl TimeFormat {
     #[must_use]
     pub fn format(self, time: &DateTime<FixedOffset>) -> String {
@@ -160,6 +167,14 @@ static MAX_MONTH_WIDTH: LazyLock<usize> = LazyLock::new(|| {
 mod test {
     use super::*;
 
+    #[test]
+    fn rfc3339_is_utc() {
+        let time = DateTime::from_timestamp(1_700_000_000, 0)
+            .unwrap()
+            .naive_utc();
+        assert_eq!(rfc3339(time), "2023-11-14T22:13:20Z");
+    }
+
     #[test]
     fn short_month_width_japanese() {
         let max_month_width = 4;
@@ -83,13 +83,19 @@ impl Default for UiStyles {
 
             #[rustfmt::skip]
             git: Some(Git {
-                new:         Some(Green.normal()),
-                modified:    Some(Blue.normal()),
-                deleted:     Some(Red.normal()),
-                renamed:     Some(Yellow.normal()),
-                typechange:  Some(Purple.normal()),
-                ignored:     Some(Style::default().dimmed()),
-                conflicted:  Some(Red.normal()),
+                new:             Some(Green.normal()),
+                modified:        Some(Blue.normal()),
+                deleted:         Some(Red.normal()),
+                renamed:         Some(Yellow.normal()),
+                typechange:      Some(Purple.normal()),
+                ignored:         Some(Style::default().dimmed()),
+                conflicted:      Some(Red.normal()),
+                submodule:       Some(Cyan.normal()),
+                submodule_moved: Some(Cyan.bold()),
+                worktree:        Some(Purple.normal()),
+                lfs_pointer:     Some(Yellow.normal()),
+                commit_author:   Some(Style::default()),
+                commit_hash:     Some(Yellow.normal()),
             }),
 
             git_repo: Some(GitRepo {
@@ -97,6 +103,10 @@ impl Default for UiStyles {
                 branch_other: Some(Yellow.normal()),
                 git_clean: Some(Green.normal()),
                 git_dirty: Some(Yellow.bold()),
+                detached: Some(Cyan.normal()),
+                ahead_behind: Some(Blue.normal()),
+                stash: Some(Purple.normal()),
+                operation: Some(Red.bold()),
             }),
 
             security_context: Some(SecurityContext {
@@ -362,15 +362,19 @@ impl render::FiletypeColours for Theme {
 
 #[rustfmt::skip]
 impl render::GitColours for Theme {
-    fn not_modified(&self)  -> Style { self.ui.punctuation() }
+    fn not_modified(&self)    -> Style { self.ui.punctuation() }
     #[allow(clippy::new_ret_no_self)]
-    fn new(&self)           -> Style { self.ui.git.unwrap_or_default().new() }
-    fn modified(&self)      -> Style { self.ui.git.unwrap_or_default().modified() }
-    fn deleted(&self)       -> Style { self.ui.git.unwrap_or_default().deleted() }
-    fn renamed(&self)       -> Style { self.ui.git.unwrap_or_default().renamed() }
-    fn type_change(&self)   -> Style { self.ui.git.unwrap_or_default().typechange() }
-    fn ignored(&self)       -> Style { self.ui.git.unwrap_or_default().ignored() }
-    fn conflicted(&self)    -> Style { self.ui.git.unwrap_or_default().conflicted() }
+    fn new(&self)             -> Style { self.ui.git.unwrap_or_default().new() }
+    fn modified(&self)        -> Style { self.ui.git.unwrap_or_default().modified() }
+    fn deleted(&self)         -> Style { self.ui.git.unwrap_or_default().deleted() }
+    fn renamed(&self)         -> Style { self.ui.git.unwrap_or_default().renamed() }
+    fn type_change(&self)     -> Style { self.ui.git.unwrap_or_default().typechange() }
+    fn ignored(&self)         -> Style { self.ui.git.unwrap_or_default().ignored() }
+    fn conflicted(&self)      -> Style { self.ui.git.unwrap_or_default().conflicted() }
+    fn submodule(&self)       -> Style { self.ui.git.unwrap_or_default().submodule() }
+    fn submodule_moved(&self) -> Style { self.ui.git.unwrap_or_default().submodule_moved() }
+    fn worktree(&self)        -> Style { self.ui.git.unwrap_or_default().worktree() }
+    fn lfs_pointer(&self)     -> Style { self.ui.git.unwrap_or_default().lfs_pointer() }
 }
 
 #[rustfmt::skip]
@@ -380,6 +384,10 @@ impl render::GitRepoColours for Theme {
     fn no_repo(&self)      -> Style { self.ui.punctuation() }
     fn git_clean(&self)    -> Style { self.ui.git_repo.unwrap_or_default().git_clean() }
     fn git_dirty(&self)    -> Style { self.ui.git_repo.unwrap_or_default().git_dirty() }
+    fn detached(&self)     -> Style { self.ui.git_repo.unwrap_or_default().detached() }
+    fn ahead_behind(&self) -> Style { self.ui.git_repo.unwrap_or_default().ahead_behind() }
+    fn stash(&self)        -> Style { self.ui.git_repo.unwrap_or_default().stash() }
+    fn operation(&self)    -> Style { self.ui.git_repo.unwrap_or_default().operation() }
 }
 
 #[rustfmt::skip]
@@ -471,6 +479,7 @@ impl FileNameColours for Theme {
     fn broken_control_char(&self) -> Style { apply_overlay(self.ui.control_char(),   self.ui.broken_path_overlay()) }
     fn executable_file(&self)     -> Style { self.ui.filekinds.unwrap_or_default().executable() }
     fn mount_point(&self)         -> Style { self.ui.filekinds.unwrap_or_default().mount_point() }
+    fn git_deleted(&self)         -> Style { self.ui.git.unwrap_or_default().deleted() }
 
     fn colour_file(&self, file: &File<'_>) -> Style {
         self.exts
@@ -712,6 +721,12 @@ mod customs_test {
     test!(exa_gt:  ls "", exa "gt=38;5;127"  =>  colours c -> { c.git().typechange                        = Some(Fixed(127).normal()); });
     test!(exa_gi:  ls "", exa "gi=38;5;128"  =>  colours c -> { c.git().ignored                           = Some(Fixed(128).normal()); });
     test!(exa_gc:  ls "", exa "gc=38;5;129"  =>  colours c -> { c.git().conflicted                        = Some(Fixed(129).normal()); });
+    test!(exa_gs:  ls "", exa "gs=38;5;130"  =>  colours c -> { c.git().submodule                         = Some(Fixed(130).normal()); });
+    test!(exa_gS:  ls "", exa "gS=38;5;131"  =>  colours c -> { c.git().submodule_moved                   = Some(Fixed(131).normal()); });
+    test!(exa_gW:  ls "", exa "gW=38;5;132"  =>  colours c -> { c.git().worktree                          = Some(Fixed(132).normal()); });
+    test!(exa_gL:  ls "", exa "gL=38;5;133"  =>  colours c -> { c.git().lfs_pointer                       = Some(Fixed(133).normal()); });
+    test!(exa_gA:  ls "", exa "gA=38;5;134"  =>  colours c -> { c.git().commit_author                     = Some(Fixed(134).normal()); });
+    test!(exa_gH:  ls "", exa "gH=38;5;135"  =>  colours c -> { c.git().commit_hash                       = Some(Fixed(135).normal()); });
 
     test!(exa_xx:  ls "", exa "xx=38;5;128"  =>  colours c -> { c.punctuation                           = Some(Fixed(128).normal()); });
     test!(exa_da:  ls "", exa "da=38;5;129"  =>  colours c -> { c.date                                  = Some(Fixed(129).normal()); });
@@ -264,13 +264,19 @@ field_accessors!(Links, normal: Option<Style>, multi_link_file: Option<Style>);
 #[rustfmt::skip]
 #[derive(Clone, Copy, Debug,Eq, PartialEq, Serialize, Deserialize)]
 pub struct Git {
-    pub new: Option<Style>,         // ga
-    pub modified: Option<Style>,    // gm
-    pub deleted: Option<Style>,     // gd
-    pub renamed: Option<Style>,     // gv
-    pub typechange: Option<Style>,  // gt
-    pub ignored: Option<Style>,     // gi
-    pub conflicted: Option<Style>,  // gc
+    pub new: Option<Style>,             // ga
+    pub modified: Option<Style>,        // gm
+    pub deleted: Option<Style>,         // gd
+    pub renamed: Option<Style>,         // gv
+    pub typechange: Option<Style>,      // gt
+    pub ignored: Option<Style>,         // gi
+    pub conflicted: Option<Style>,      // gc
+    pub submodule: Option<Style>,       // gs
+    pub submodule_moved: Option<Style>, // gS
+    pub worktree: Option<Style>,        // gW
+    pub lfs_pointer: Option<Style>,     // gL
+    pub commit_author: Option<Style>,   // gA
+    pub commit_hash: Option<Style>,     // gH
 }
 
 field_accessors!(
@@ -281,7 +287,13 @@ field_accessors!(
     renamed: Option<Style>,
     typechange: Option<Style>,
     ignored: Option<Style>,
-    conflicted: Option<Style>
+    conflicted: Option<Style>,
+    submodule: Option<Style>,
+    submodule_moved: Option<Style>,
+    worktree: Option<Style>,
+    lfs_pointer: Option<Style>,
+    commit_author: Option<Style>,
+    commit_hash: Option<Style>
 );
 impl Default for Git {
     fn default() -> Self {
@@ -293,6 +305,12 @@ impl Default for Git {
             typechange: Some(Purple.normal()),
             ignored: Some(Style::default().dimmed()),
             conflicted: Some(Red.normal()),
+            submodule: Some(Cyan.normal()),
+            submodule_moved: Some(Cyan.bold()),
+            worktree: Some(Purple.normal()),
+            lfs_pointer: Some(Yellow.normal()),
+            commit_author: Some(Style::default()),
+            commit_hash: Some(Yellow.normal()),
         }
     }
 }
@@ -304,13 +322,21 @@ pub struct GitRepo {
     pub branch_other: Option<Style>, //Go
     pub git_clean: Option<Style>,    //Gc
     pub git_dirty: Option<Style>,    //Gd
+    pub detached: Option<Style>,     //Gh
+    pub ahead_behind: Option<Style>, //Ga
+    pub stash: Option<Style>,        //Gs
+    pub operation: Option<Style>,    //Gp
 }
 field_accessors!(
     GitRepo,
     branch_main: Option<Style>,
     branch_other: Option<Style>,
     git_clean: Option<Style>,
-    git_dirty: Option<Style>
+    git_dirty: Option<Style>,
+    detached: Option<Style>,
+    ahead_behind: Option<Style>,
+    stash: Option<Style>,
+    operation: Option<Style>
 );
 impl Default for GitRepo {
     fn default() -> Self {
@@ -319,6 +345,10 @@ impl Default for GitRepo {
             branch_other: Some(Yellow.normal()),
             git_clean: Some(Green.normal()),
             git_dirty: Some(Yellow.bold()),
+            detached: Some(Cyan.normal()),
+            ahead_behind: Some(Blue.normal()),
+            stash: Some(Purple.normal()),
+            operation: Some(Red.bold()),
         }
     }
 }
@@ -445,13 +475,19 @@ impl UiStyles {
 
             #[rustfmt::skip]
             git: Some(Git {
-                new:         Some(Style::default()),
-                modified:    Some(Style::default()),
-                deleted:     Some(Style::default()),
-                renamed:     Some(Style::default()),
-                typechange:  Some(Style::default()),
-                ignored:     Some(Style::default()),
-                conflicted:  Some(Style::default()),
+                new:             Some(Style::default()),
+                modified:        Some(Style::default()),
+                deleted:         Some(Style::default()),
+                renamed:         Some(Style::default()),
+                typechange:      Some(Style::default()),
+                ignored:         Some(Style::default()),
+                conflicted:      Some(Style::default()),
+                submodule:       Some(Style::default()),
+                submodule_moved: Some(Style::default()),
+                worktree:        Some(Style::default()),
+                lfs_pointer:     Some(Style::default()),
+                commit_author:   Some(Style::default()),
+                commit_hash:     Some(Style::default()),
             }),
 
             git_repo: Some(GitRepo {
@@ -459,6 +495,10 @@ impl UiStyles {
                 branch_other: Some(Style::default()),
                 git_clean: Some(Style::default()),
                 git_dirty: Some(Style::default()),
+                detached: Some(Style::default()),
+                ahead_behind: Some(Style::default()),
+                stash: Some(Style::default()),
+                operation: Some(Style::default()),
             }),
 
             security_context: Some(SecurityContext {
@@ -584,11 +624,21 @@ impl UiStyles {
             "gt" => self.git().typechange                 = Some(pair.to_style()),
             "gi" => self.git().ignored                    = Some(pair.to_style()),
             "gc" => self.git().conflicted                 = Some(pair.to_style()),
+            "gs" => self.git().submodule                  = Some(pair.to_style()),
+            "gS" => self.git().submodule_moved            = Some(pair.to_style()),
+            "gW" => self.git().worktree                   = Some(pair.to_style()),
+            "gL" => self.git().lfs_pointer                = Some(pair.to_style()),
+            "gA" => self.git().commit_author              = Some(pair.to_style()),
+            "gH" => self.git().commit_hash                = Some(pair.to_style()),
 
             "Gm" => self.git_repo().branch_main           = Some(pair.to_style()),
             "Go" => self.git_repo().branch_other          = Some(pair.to_style()),
             "Gc" => self.git_repo().git_clean             = Some(pair.to_style()),
             "Gd" => self.git_repo().git_dirty             = Some(pair.to_style()),
+            "Gh" => self.git_repo().detached              = Some(pair.to_style()),
+            "Ga" => self.git_repo().ahead_behind          = Some(pair.to_style()),
+            "Gs" => self.git_repo().stash                 = Some(pair.to_style()),
+            "Gp" => self.git_repo().operation             = Some(pair.to_style()),
             "xx" => self.punctuation                     = Some(pair.to_style()),
             "da" => self.date                            = Some(pair.to_style()),
             "in" => self.inode                           = Some(pair.to_style()),